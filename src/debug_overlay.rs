@@ -0,0 +1,351 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+use std::collections::VecDeque;
+
+use glam::Vec2;
+use glam_rect::Rect;
+
+use crate::color::Color;
+use crate::renderer2d::{FrameStats, Renderer2D};
+use crate::time::Timestamp;
+
+/// The number of frames shown in the frame time graph.
+const HISTORY_LENGTH: usize = 120;
+
+/// The size of each pixel of the bitmap font, in physical pixels.
+const FONT_SCALE: f32 = 2.0;
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+const CHAR_ADVANCE: f32 = (GLYPH_WIDTH + 1) as f32 * FONT_SCALE;
+const LINE_HEIGHT: f32 = (GLYPH_HEIGHT + 2) as f32 * FONT_SCALE;
+
+const MARGIN: f32 = 8.0;
+const PADDING: f32 = 6.0;
+const BAR_WIDTH: f32 = 2.0;
+const GRAPH_HEIGHT: f32 = 60.0;
+
+/// The frame time at the top of the graph, in seconds.
+const GRAPH_MAX_FRAME_TIME: f32 = 0.05;
+
+#[cfg(feature = "text")]
+const THUMBNAIL_SIZE: f32 = 64.0;
+
+const BACKGROUND_COLOR: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.75);
+const GRAPH_BACKGROUND_COLOR: Color = Color::from_rgba(1.0, 1.0, 1.0, 0.1);
+const GUIDE_COLOR: Color = Color::from_rgba(1.0, 1.0, 1.0, 0.3);
+const TEXT_COLOR: Color = Color::WHITE;
+
+/// Statistics drawn on top of each frame, after any post-processing or
+/// upscaling. See [crate::GLRenderer::set_debug_overlay_enabled].
+#[derive(Default)]
+pub(crate) struct DebugOverlay {
+    enabled: bool,
+    frame_times: VecDeque<f32>,
+    last_frame_start: Option<Timestamp>,
+}
+
+impl DebugOverlay {
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.frame_times.clear();
+            self.last_frame_start = None;
+        }
+
+        self.enabled = enabled;
+    }
+
+    pub(crate) fn on_frame_start(&mut self) {
+        if !self.enabled {
+            return;
+        }
+
+        let now = Timestamp::now();
+
+        if let Some(last_frame_start) = &self.last_frame_start {
+            if self.frame_times.len() == HISTORY_LENGTH {
+                self.frame_times.pop_front();
+            }
+
+            self.frame_times
+                .push_back(now.secs_since(last_frame_start) as f32);
+        }
+
+        self.last_frame_start = Some(now);
+    }
+
+    /// Queues the overlay for drawing. The renderer must already be set up to
+    /// draw to the window in physical pixels.
+    pub(crate) fn draw(&self, renderer: &mut Renderer2D, stats: &FrameStats) {
+        let average_frame_time = if self.frame_times.is_empty() {
+            0.0
+        } else {
+            self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32
+        };
+
+        let fps = if average_frame_time > 0.0 {
+            1.0 / average_frame_time
+        } else {
+            0.0
+        };
+
+        #[allow(unused_mut)]
+        let mut lines = vec![
+            format!("FPS {:.1}  {:.2} MS", fps, average_frame_time * 1000.0),
+            format!(
+                "DRAW CALLS {}  VERTICES {}",
+                stats.draw_calls, stats.vertices_uploaded
+            ),
+            format!(
+                "TEXTURE BINDS {}  PROGRAMS {}  BLEND {}",
+                stats.texture_binds, stats.program_changes, stats.blend_mode_changes
+            ),
+            format!(
+                "QUEUE {}  FLUSHES {}",
+                stats.render_queue_items, stats.render_queue_flushes
+            ),
+        ];
+
+        #[cfg(feature = "text")]
+        lines.push(format!(
+            "GLYPHS HIT {} MISS {}  REPACKS {}",
+            stats.glyph_cache_hits, stats.glyph_cache_misses, stats.glyph_cache_rearrangements
+        ));
+
+        #[cfg(feature = "text")]
+        let glyph_textures = renderer.glyph_cache_textures();
+
+        let graph_width = HISTORY_LENGTH as f32 * BAR_WIDTH;
+
+        let text_width = lines
+            .iter()
+            .map(|line| line.chars().count() as f32 * CHAR_ADVANCE)
+            .fold(0.0, f32::max);
+
+        #[cfg(feature = "text")]
+        let thumbnails_size = if glyph_textures.is_empty() {
+            Vec2::ZERO
+        } else {
+            Vec2::new(
+                glyph_textures.len() as f32 * (THUMBNAIL_SIZE + PADDING) - PADDING,
+                THUMBNAIL_SIZE + PADDING,
+            )
+        };
+
+        #[cfg(not(feature = "text"))]
+        let thumbnails_size = Vec2::ZERO;
+
+        let content_size = Vec2::new(
+            text_width.max(graph_width).max(thumbnails_size.x),
+            lines.len() as f32 * LINE_HEIGHT + PADDING + GRAPH_HEIGHT + thumbnails_size.y,
+        );
+
+        let top_left = Vec2::splat(MARGIN);
+
+        draw_rect(
+            renderer,
+            Rect::new(
+                top_left,
+                top_left + content_size + Vec2::splat(2.0 * PADDING),
+            ),
+            BACKGROUND_COLOR,
+        );
+
+        let mut position = top_left + Vec2::splat(PADDING);
+
+        for line in &lines {
+            draw_text(renderer, position, line, TEXT_COLOR);
+            position.y += LINE_HEIGHT;
+        }
+
+        position.y += PADDING;
+
+        self.draw_graph(renderer, position);
+
+        #[cfg(feature = "text")]
+        {
+            position.y += GRAPH_HEIGHT + PADDING;
+
+            for texture in glyph_textures {
+                renderer.draw_texture_rect(
+                    Rect::new(position, position + Vec2::splat(THUMBNAIL_SIZE)),
+                    texture,
+                );
+
+                position.x += THUMBNAIL_SIZE + PADDING;
+            }
+        }
+    }
+
+    fn draw_graph(&self, renderer: &mut Renderer2D, top_left: Vec2) {
+        let size = Vec2::new(HISTORY_LENGTH as f32 * BAR_WIDTH, GRAPH_HEIGHT);
+        let bottom = top_left.y + size.y;
+
+        draw_rect(
+            renderer,
+            Rect::new(top_left, top_left + size),
+            GRAPH_BACKGROUND_COLOR,
+        );
+
+        // Draw the newest frame on the right
+        let first_bar = HISTORY_LENGTH - self.frame_times.len();
+
+        for (i, frame_time) in self.frame_times.iter().enumerate() {
+            let height = (frame_time / GRAPH_MAX_FRAME_TIME).min(1.0) * size.y;
+            let x = top_left.x + (first_bar + i) as f32 * BAR_WIDTH;
+
+            let color = if *frame_time <= 1.0 / 55.0 {
+                Color::GREEN
+            } else if *frame_time <= 1.0 / 28.0 {
+                Color::YELLOW
+            } else {
+                Color::RED
+            };
+
+            draw_rect(
+                renderer,
+                Rect::new(
+                    Vec2::new(x, bottom - height),
+                    Vec2::new(x + BAR_WIDTH, bottom),
+                ),
+                color,
+            );
+        }
+
+        // Guides at 60 FPS and 30 FPS
+        for guide_frame_time in [1.0 / 60.0, 1.0 / 30.0] {
+            let y = bottom - guide_frame_time / GRAPH_MAX_FRAME_TIME * size.y;
+
+            draw_rect(
+                renderer,
+                Rect::new(
+                    Vec2::new(top_left.x, y),
+                    Vec2::new(top_left.x + size.x, y + 1.0),
+                ),
+                GUIDE_COLOR,
+            );
+        }
+    }
+}
+
+fn draw_rect(renderer: &mut Renderer2D, rect: Rect, color: Color) {
+    let [top_left, top_right, bottom_right, bottom_left] = rect.corners();
+
+    renderer.draw_triangle_three_color([top_left, top_right, bottom_right], [color; 3]);
+    renderer.draw_triangle_three_color([bottom_right, bottom_left, top_left], [color; 3]);
+}
+
+/// Draws text using the built-in bitmap font. Each horizontal run of pixels
+/// is drawn as a single rectangle.
+fn draw_text(renderer: &mut Renderer2D, position: Vec2, text: &str, color: Color) {
+    for (char_index, c) in text.chars().enumerate() {
+        let origin = position + Vec2::new(char_index as f32 * CHAR_ADVANCE, 0.0);
+
+        for (row_index, row) in glyph_rows(c).iter().enumerate() {
+            let y = origin.y + row_index as f32 * FONT_SCALE;
+            let mut column = 0;
+
+            while column < GLYPH_WIDTH {
+                if !is_pixel_set(*row, column) {
+                    column += 1;
+                    continue;
+                }
+
+                let run_start = column;
+
+                while column < GLYPH_WIDTH && is_pixel_set(*row, column) {
+                    column += 1;
+                }
+
+                draw_rect(
+                    renderer,
+                    Rect::new(
+                        Vec2::new(origin.x + run_start as f32 * FONT_SCALE, y),
+                        Vec2::new(origin.x + column as f32 * FONT_SCALE, y + FONT_SCALE),
+                    ),
+                    color,
+                );
+            }
+        }
+    }
+}
+
+#[inline]
+fn is_pixel_set(row: u8, column: usize) -> bool {
+    row & (1 << (GLYPH_WIDTH - 1 - column)) != 0
+}
+
+/// Returns the rows of a 3x5 pixel glyph, from top to bottom. In each row, the
+/// most significant of the three bits is the leftmost pixel. Lower case
+/// letters are drawn as upper case.
+fn glyph_rows(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ' ' => [0b000, 0b000, 0b000, 0b000, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        '(' => [0b010, 0b100, 0b100, 0b100, 0b010],
+        ')' => [0b010, 0b001, 0b001, 0b001, 0b010],
+        _ => [0b111, 0b001, 0b010, 0b000, 0b010],
+    }
+}
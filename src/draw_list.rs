@@ -0,0 +1,814 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+//! Recorded sequences of drawing operations, which can be replayed many
+//! times.
+//!
+//! See [DrawList] and [crate::Graphics2D::draw_list].
+
+use glam::{Affine2, Mat2, Vec2};
+use glam_rect::{IRect, Rect};
+
+use crate::color::Color;
+use crate::Graphics2D;
+
+#[cfg(feature = "text")]
+use crate::font::FormattedTextBlock;
+
+#[cfg(feature = "shapes")]
+use crate::shapes::Polygon;
+
+/// The type used to refer to images in a [DrawList] by default.
+///
+/// This is [crate::image::ImageHandle] when the `image-loading` feature is
+/// enabled. Otherwise, no images can be drawn, and this is
+/// [std::convert::Infallible].
+#[cfg(feature = "image-loading")]
+pub type DefaultDrawListImage = crate::image::ImageHandle;
+
+/// The type used to refer to images in a [DrawList] by default.
+///
+/// This is [crate::image::ImageHandle] when the `image-loading` feature is
+/// enabled. Otherwise, no images can be drawn, and this is
+/// [std::convert::Infallible].
+#[cfg(not(feature = "image-loading"))]
+pub type DefaultDrawListImage = std::convert::Infallible;
+
+#[derive(Clone)]
+enum DrawCommand<Image> {
+    ClearScreen(Color),
+    Triangle {
+        positions: [Vec2; 3],
+        colors: [Color; 3],
+    },
+    Quad {
+        positions: [Vec2; 4],
+        colors: [Color; 4],
+    },
+    Line {
+        start: Vec2,
+        end: Vec2,
+        thickness: f32,
+        color: Color,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+        color: Color,
+    },
+    CircleSection {
+        positions: [Vec2; 3],
+        colors: [Color; 3],
+        circle_coords: [Vec2; 3],
+    },
+    #[cfg(feature = "shapes")]
+    Polygon {
+        polygon: Polygon,
+        offset: Vec2,
+        color: Color,
+    },
+    #[cfg(feature = "text")]
+    Text {
+        position: Vec2,
+        color: Color,
+        text: FormattedTextBlock,
+        crop_window: Option<Rect>,
+    },
+    ImageTriangle {
+        positions: [Vec2; 3],
+        colors: [Color; 3],
+        image_coords: [Vec2; 3],
+        image: Image,
+    },
+    ImageQuad {
+        positions: [Vec2; 4],
+        colors: [Color; 4],
+        image_coords: [Vec2; 4],
+        image: Image,
+    },
+    SetClip(Option<IRect>),
+}
+
+/// A recorded sequence of drawing operations, which can be drawn any number of
+/// times using [crate::Graphics2D::draw_list] or
+/// [crate::Graphics2D::draw_list_transformed].
+///
+/// This is useful for complex content which rarely changes: the list can be
+/// built once, and then redrawn each frame without repeating the work
+/// required to build it.
+///
+/// The methods for recording operations mirror those in
+/// [crate::Graphics2D], and the coordinates are relative to the transform
+/// supplied when the list is drawn.
+///
+/// ```rust,no_run
+/// # use speedy2d::GLRenderer;
+/// use glam::Vec2;
+/// use speedy2d::color::Color;
+/// use speedy2d::draw_list::DrawList;
+/// use speedy2d::Rect;
+///
+/// let mut list = DrawList::new();
+/// list.draw_rectangle(Rect::new(Vec2::ZERO, Vec2::new(200.0, 100.0)), Color::GRAY);
+/// list.draw_circle((100.0, 50.0), 40.0, Color::BLUE);
+///
+/// # let mut renderer = unsafe {
+/// #     GLRenderer::new_for_gl_context((640, 480), |fn_name| {
+/// #         std::ptr::null() as *const _
+/// #     })
+/// # }.unwrap();
+/// # renderer.draw_frame(|graphics| {
+/// graphics.draw_list(&list, (20.0, 20.0));
+/// graphics.draw_list(&list, (20.0, 200.0));
+/// # });
+/// ```
+///
+/// # Threading
+///
+/// A `DrawList` is `Send` as long as its image type is. GPU image handles
+/// can't be sent between threads, so to build a list containing images on
+/// another thread, record the images using some other identifier (such as
+/// an index into a list of images), and then use [DrawList::map_images] to
+/// look up the corresponding [crate::image::ImageHandle] values on the
+/// rendering thread.
+///
+/// # Limitations
+///
+/// Text is positioned using the transform, but it is not rotated or scaled.
+/// Clip rectangles are transformed to the axis-aligned rectangle which
+/// contains them. If the list changes the clip, then the clip is removed
+/// after the list has been drawn.
+#[derive(Clone)]
+pub struct DrawList<Image = DefaultDrawListImage> {
+    commands: Vec<DrawCommand<Image>>,
+}
+
+impl<Image> Default for DrawList<Image> {
+    fn default() -> Self {
+        DrawList {
+            commands: Vec::new(),
+        }
+    }
+}
+
+impl DrawList {
+    /// Creates a new, empty, list.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<Image> DrawList<Image> {
+    /// Returns the number of operations in the list.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if no operations have been recorded.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Removes all the operations from the list.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Creates a copy of this list, in which each image has been replaced
+    /// using the provided function.
+    ///
+    /// For example, a list of type `DrawList<usize>` could be built on a
+    /// worker thread, and then converted to a `DrawList<ImageHandle>` by
+    /// looking up each index in a `Vec<ImageHandle>`.
+    pub fn map_images<NewImage, F: FnMut(&Image) -> NewImage>(
+        &self,
+        mut mapping: F,
+    ) -> DrawList<NewImage> {
+        let commands = self
+            .commands
+            .iter()
+            .map(|command| match command {
+                DrawCommand::ClearScreen(color) => DrawCommand::ClearScreen(*color),
+                DrawCommand::Triangle { positions, colors } => DrawCommand::Triangle {
+                    positions: *positions,
+                    colors: *colors,
+                },
+                DrawCommand::Quad { positions, colors } => DrawCommand::Quad {
+                    positions: *positions,
+                    colors: *colors,
+                },
+                DrawCommand::Line {
+                    start,
+                    end,
+                    thickness,
+                    color,
+                } => DrawCommand::Line {
+                    start: *start,
+                    end: *end,
+                    thickness: *thickness,
+                    color: *color,
+                },
+                DrawCommand::Circle {
+                    center,
+                    radius,
+                    color,
+                } => DrawCommand::Circle {
+                    center: *center,
+                    radius: *radius,
+                    color: *color,
+                },
+                DrawCommand::CircleSection {
+                    positions,
+                    colors,
+                    circle_coords,
+                } => DrawCommand::CircleSection {
+                    positions: *positions,
+                    colors: *colors,
+                    circle_coords: *circle_coords,
+                },
+                #[cfg(feature = "shapes")]
+                DrawCommand::Polygon {
+                    polygon,
+                    offset,
+                    color,
+                } => DrawCommand::Polygon {
+                    polygon: polygon.clone(),
+                    offset: *offset,
+                    color: *color,
+                },
+                #[cfg(feature = "text")]
+                DrawCommand::Text {
+                    position,
+                    color,
+                    text,
+                    crop_window,
+                } => DrawCommand::Text {
+                    position: *position,
+                    color: *color,
+                    text: text.clone(),
+                    crop_window: crop_window.clone(),
+                },
+                DrawCommand::ImageTriangle {
+                    positions,
+                    colors,
+                    image_coords,
+                    image,
+                } => DrawCommand::ImageTriangle {
+                    positions: *positions,
+                    colors: *colors,
+                    image_coords: *image_coords,
+                    image: mapping(image),
+                },
+                DrawCommand::ImageQuad {
+                    positions,
+                    colors,
+                    image_coords,
+                    image,
+                } => DrawCommand::ImageQuad {
+                    positions: *positions,
+                    colors: *colors,
+                    image_coords: *image_coords,
+                    image: mapping(image),
+                },
+                DrawCommand::SetClip(rect) => DrawCommand::SetClip(rect.clone()),
+            })
+            .collect();
+
+        DrawList { commands }
+    }
+
+    /// Records an operation which fills the screen with the specified color.
+    /// See [crate::Graphics2D::clear_screen].
+    pub fn clear_screen(&mut self, color: Color) {
+        self.commands.push(DrawCommand::ClearScreen(color));
+    }
+
+    /// Records a block of text at the specified position. See
+    /// [crate::Graphics2D::draw_text].
+    #[cfg(feature = "text")]
+    pub fn draw_text<V: Into<Vec2>>(
+        &mut self,
+        position: V,
+        color: Color,
+        text: &FormattedTextBlock,
+    ) {
+        self.commands.push(DrawCommand::Text {
+            position: position.into(),
+            color,
+            text: text.clone(),
+            crop_window: None,
+        });
+    }
+
+    /// Records a block of text at the specified position, cropped to the
+    /// specified window. See [crate::Graphics2D::draw_text_cropped].
+    #[cfg(feature = "text")]
+    pub fn draw_text_cropped<V: Into<Vec2>>(
+        &mut self,
+        position: V,
+        crop_window: Rect,
+        color: Color,
+        text: &FormattedTextBlock,
+    ) {
+        self.commands.push(DrawCommand::Text {
+            position: position.into(),
+            color,
+            text: text.clone(),
+            crop_window: Some(crop_window),
+        });
+    }
+
+    /// Records a polygon with a single color, with the specified offset. See
+    /// [crate::Graphics2D::draw_polygon].
+    #[cfg(feature = "shapes")]
+    pub fn draw_polygon<V: Into<Vec2>>(&mut self, polygon: &Polygon, offset: V, color: Color) {
+        self.commands.push(DrawCommand::Polygon {
+            polygon: polygon.clone(),
+            offset: offset.into(),
+            color,
+        });
+    }
+
+    /// Records a triangle with the specified colors. See
+    /// [crate::Graphics2D::draw_triangle_three_color].
+    pub fn draw_triangle_three_color(
+        &mut self,
+        vertex_positions_clockwise: [Vec2; 3],
+        vertex_colors_clockwise: [Color; 3],
+    ) {
+        self.commands.push(DrawCommand::Triangle {
+            positions: vertex_positions_clockwise,
+            colors: vertex_colors_clockwise,
+        });
+    }
+
+    /// Records a triangle with the specified color. See
+    /// [crate::Graphics2D::draw_triangle].
+    #[inline]
+    pub fn draw_triangle(&mut self, vertex_positions_clockwise: [Vec2; 3], color: Color) {
+        self.draw_triangle_three_color(vertex_positions_clockwise, [color, color, color]);
+    }
+
+    /// Records a quadrilateral with the specified colors. See
+    /// [crate::Graphics2D::draw_quad_four_color].
+    pub fn draw_quad_four_color(
+        &mut self,
+        vertex_positions_clockwise: [Vec2; 4],
+        vertex_colors: [Color; 4],
+    ) {
+        self.commands.push(DrawCommand::Quad {
+            positions: vertex_positions_clockwise,
+            colors: vertex_colors,
+        });
+    }
+
+    /// Records a quadrilateral with the specified color. See
+    /// [crate::Graphics2D::draw_quad].
+    #[inline]
+    pub fn draw_quad(&mut self, vertex_positions_clockwise: [Vec2; 4], color: Color) {
+        self.draw_quad_four_color(vertex_positions_clockwise, [color, color, color, color]);
+    }
+
+    /// Records a single-color rectangle. See
+    /// [crate::Graphics2D::draw_rectangle].
+    #[inline]
+    pub fn draw_rectangle(&mut self, rect: impl AsRef<Rect>, color: Color) {
+        let rect = rect.as_ref();
+
+        self.draw_quad(
+            [
+                rect.top_left,
+                rect.top_right(),
+                rect.bottom_right,
+                rect.bottom_left(),
+            ],
+            color,
+        );
+    }
+
+    /// Records a single-color line between the given points. See
+    /// [crate::Graphics2D::draw_line].
+    pub fn draw_line(
+        &mut self,
+        start_position: Vec2,
+        end_position: Vec2,
+        thickness: f32,
+        color: Color,
+    ) {
+        self.commands.push(DrawCommand::Line {
+            start: start_position,
+            end: end_position,
+            thickness,
+            color,
+        });
+    }
+
+    /// Records a circle, filled with a single color. See
+    /// [crate::Graphics2D::draw_circle].
+    pub fn draw_circle<V: Into<Vec2>>(&mut self, center_position: V, radius: f32, color: Color) {
+        self.commands.push(DrawCommand::Circle {
+            center: center_position.into(),
+            radius,
+            color,
+        });
+    }
+
+    /// Records a triangular subset of a circle. See
+    /// [crate::Graphics2D::draw_circle_section_triangular_three_color].
+    pub fn draw_circle_section_triangular_three_color(
+        &mut self,
+        vertex_positions_clockwise: [Vec2; 3],
+        vertex_colors: [Color; 3],
+        vertex_circle_coords_normalized: [Vec2; 3],
+    ) {
+        self.commands.push(DrawCommand::CircleSection {
+            positions: vertex_positions_clockwise,
+            colors: vertex_colors,
+            circle_coords: vertex_circle_coords_normalized,
+        });
+    }
+
+    /// Records part of an image, tinted with the provided colors. See
+    /// [crate::Graphics2D::draw_triangle_image_tinted_three_color].
+    pub fn draw_triangle_image_tinted_three_color(
+        &mut self,
+        vertex_positions_clockwise: [Vec2; 3],
+        vertex_colors: [Color; 3],
+        image_coords_normalized: [Vec2; 3],
+        image: Image,
+    ) {
+        self.commands.push(DrawCommand::ImageTriangle {
+            positions: vertex_positions_clockwise,
+            colors: vertex_colors,
+            image_coords: image_coords_normalized,
+            image,
+        });
+    }
+
+    /// Records part of an image, tinted with the provided colors. See
+    /// [crate::Graphics2D::draw_quad_image_tinted_four_color].
+    pub fn draw_quad_image_tinted_four_color(
+        &mut self,
+        vertex_positions_clockwise: [Vec2; 4],
+        vertex_colors: [Color; 4],
+        image_coords_normalized: [Vec2; 4],
+        image: Image,
+    ) {
+        self.commands.push(DrawCommand::ImageQuad {
+            positions: vertex_positions_clockwise,
+            colors: vertex_colors,
+            image_coords: image_coords_normalized,
+            image,
+        });
+    }
+
+    /// Records part of an image, tinted with the provided color, scaled to
+    /// fill the provided rectangle. See
+    /// [crate::Graphics2D::draw_rectangle_image_subset_tinted].
+    #[inline]
+    pub fn draw_rectangle_image_subset_tinted(
+        &mut self,
+        rect: impl AsRef<Rect>,
+        color: Color,
+        image_coords_normalized: impl AsRef<Rect>,
+        image: Image,
+    ) {
+        let rect = rect.as_ref();
+        let image_coords_normalized = image_coords_normalized.as_ref();
+
+        self.draw_quad_image_tinted_four_color(
+            [
+                rect.top_left,
+                rect.top_right(),
+                rect.bottom_right,
+                rect.bottom_left(),
+            ],
+            [color, color, color, color],
+            [
+                image_coords_normalized.top_left,
+                image_coords_normalized.top_right(),
+                image_coords_normalized.bottom_right,
+                image_coords_normalized.bottom_left(),
+            ],
+            image,
+        );
+    }
+
+    /// Records an image, tinted with the provided color, scaled to fill the
+    /// provided rectangle. See
+    /// [crate::Graphics2D::draw_rectangle_image_tinted].
+    #[inline]
+    pub fn draw_rectangle_image_tinted(
+        &mut self,
+        rect: impl AsRef<Rect>,
+        color: Color,
+        image: Image,
+    ) {
+        self.draw_rectangle_image_subset_tinted(
+            rect,
+            color,
+            Rect::new(Vec2::ZERO, Vec2::new(1.0, 1.0)),
+            image,
+        );
+    }
+
+    /// Records an image, scaled to fill the provided rectangle. See
+    /// [crate::Graphics2D::draw_rectangle_image].
+    #[inline]
+    pub fn draw_rectangle_image(&mut self, rect: impl AsRef<Rect>, image: Image) {
+        self.draw_rectangle_image_tinted(rect, Color::WHITE, image);
+    }
+
+    /// Records a change to the clip rectangle. See
+    /// [crate::Graphics2D::set_clip].
+    pub fn set_clip(&mut self, rect: Option<IRect>) {
+        self.commands.push(DrawCommand::SetClip(rect));
+    }
+}
+
+impl DrawList {
+    pub(crate) fn draw(&self, graphics: &mut Graphics2D, transform: Affine2) {
+        let is_translation = transform.matrix2 == Mat2::IDENTITY;
+
+        let point = |p: &Vec2| transform.transform_point2(*p);
+        let points3 = |p: &[Vec2; 3]| p.map(|p| transform.transform_point2(p));
+        let points4 = |p: &[Vec2; 4]| p.map(|p| transform.transform_point2(p));
+
+        let mut clip_changed = false;
+
+        for command in &self.commands {
+            match command {
+                DrawCommand::ClearScreen(color) => graphics.clear_screen(*color),
+
+                DrawCommand::Triangle { positions, colors } => {
+                    graphics.draw_triangle_three_color(points3(positions), *colors)
+                }
+
+                DrawCommand::Quad { positions, colors } => {
+                    graphics.draw_quad_four_color(points4(positions), *colors)
+                }
+
+                DrawCommand::Line {
+                    start,
+                    end,
+                    thickness,
+                    color,
+                } => {
+                    if is_translation {
+                        graphics.draw_line(point(start), point(end), *thickness, *color);
+                    } else if let Some(quad) = crate::line_quad(*start, *end, *thickness) {
+                        graphics.draw_quad(points4(&quad), *color);
+                    }
+                }
+
+                DrawCommand::Circle {
+                    center,
+                    radius,
+                    color,
+                } => {
+                    if is_translation {
+                        graphics.draw_circle(point(center), *radius, *color);
+                    } else {
+                        // The circle becomes an ellipse, which is drawn using
+                        // the same two sections as draw_circle()
+                        let top_left = point(&(*center + Vec2::new(-radius, -radius)));
+                        let top_right = point(&(*center + Vec2::new(*radius, -radius)));
+                        let bottom_right = point(&(*center + Vec2::new(*radius, *radius)));
+                        let bottom_left = point(&(*center + Vec2::new(-radius, *radius)));
+
+                        graphics.draw_circle_section_triangular_three_color(
+                            [top_left, top_right, bottom_right],
+                            [*color; 3],
+                            [
+                                Vec2::new(-1.0, -1.0),
+                                Vec2::new(1.0, -1.0),
+                                Vec2::new(1.0, 1.0),
+                            ],
+                        );
+
+                        graphics.draw_circle_section_triangular_three_color(
+                            [bottom_right, bottom_left, top_left],
+                            [*color; 3],
+                            [
+                                Vec2::new(1.0, 1.0),
+                                Vec2::new(-1.0, 1.0),
+                                Vec2::new(-1.0, -1.0),
+                            ],
+                        );
+                    }
+                }
+
+                DrawCommand::CircleSection {
+                    positions,
+                    colors,
+                    circle_coords,
+                } => graphics.draw_circle_section_triangular_three_color(
+                    points3(positions),
+                    *colors,
+                    *circle_coords,
+                ),
+
+                #[cfg(feature = "shapes")]
+                DrawCommand::Polygon {
+                    polygon,
+                    offset,
+                    color,
+                } => {
+                    if is_translation {
+                        graphics.draw_polygon(polygon, *offset + transform.translation, *color);
+                    } else {
+                        for triangle in &polygon.triangles {
+                            graphics.draw_triangle(
+                                triangle.map(|p| transform.transform_point2(p + *offset)),
+                                *color,
+                            );
+                        }
+                    }
+                }
+
+                #[cfg(feature = "text")]
+                DrawCommand::Text {
+                    position,
+                    color,
+                    text,
+                    crop_window,
+                } => match crop_window {
+                    None => graphics.draw_text(point(position), *color, text),
+                    Some(crop_window) => graphics.draw_text_cropped(
+                        point(position),
+                        transform_rect(&transform, crop_window),
+                        *color,
+                        text,
+                    ),
+                },
+
+                #[cfg(feature = "image-loading")]
+                DrawCommand::ImageTriangle {
+                    positions,
+                    colors,
+                    image_coords,
+                    image,
+                } => graphics.draw_triangle_image_tinted_three_color(
+                    points3(positions),
+                    *colors,
+                    *image_coords,
+                    image,
+                ),
+
+                #[cfg(feature = "image-loading")]
+                DrawCommand::ImageQuad {
+                    positions,
+                    colors,
+                    image_coords,
+                    image,
+                } => graphics.draw_quad_image_tinted_four_color(
+                    points4(positions),
+                    *colors,
+                    *image_coords,
+                    image,
+                ),
+
+                #[cfg(not(feature = "image-loading"))]
+                DrawCommand::ImageTriangle { image, .. } | DrawCommand::ImageQuad { image, .. } => {
+                    match *image {}
+                }
+
+                DrawCommand::SetClip(rect) => {
+                    clip_changed = true;
+
+                    graphics.set_clip(rect.as_ref().map(|rect| {
+                        let rect = transform_rect(
+                            &transform,
+                            &Rect::new(rect.top_left.as_vec2(), rect.bottom_right.as_vec2()),
+                        );
+
+                        IRect::new(
+                            rect.top_left.floor().as_ivec2(),
+                            rect.bottom_right.ceil().as_ivec2(),
+                        )
+                    }));
+                }
+            }
+        }
+
+        if clip_changed {
+            graphics.set_clip(None);
+        }
+    }
+}
+
+/// Returns the axis-aligned bounding box of the transformed rectangle.
+fn transform_rect(transform: &Affine2, rect: &Rect) -> Rect {
+    let corners = [
+        rect.top_left,
+        rect.top_right(),
+        rect.bottom_right,
+        rect.bottom_left(),
+    ]
+    .map(|p| transform.transform_point2(p));
+
+    let min = corners.iter().fold(Vec2::INFINITY, |min, p| min.min(*p));
+    let max = corners
+        .iter()
+        .fold(Vec2::NEG_INFINITY, |max, p| max.max(*p));
+
+    Rect::new(min, max)
+}
+
+#[cfg(test)]
+mod test {
+    use glam::{Affine2, Vec2};
+    use glam_rect::Rect;
+
+    use crate::color::Color;
+    use crate::draw_list::DrawList;
+    use crate::glbackend::{create_test_renderer, GLCall, RecordingGLBackend};
+    use crate::GLVersion;
+
+    fn uploaded_positions(callback: impl FnOnce(&mut crate::Graphics2D)) -> Vec<f32> {
+        let (backend, mut renderer) =
+            create_test_renderer(RecordingGLBackend::new(), (640, 480), GLVersion::OpenGL2_0);
+
+        backend.clear_calls();
+
+        renderer.draw_frame(callback);
+
+        backend
+            .calls()
+            .iter()
+            .filter_map(GLCall::buffer_data_as_f32)
+            .next()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_draw_list_is_send() {
+        fn assert_send<T: Send>() {}
+
+        assert_send::<DrawList<usize>>();
+    }
+
+    #[test]
+    fn test_draw_list_with_offset() {
+        let mut list = DrawList::new();
+
+        list.draw_rectangle(
+            Rect::new(Vec2::new(10.0, 20.0), Vec2::new(30.0, 40.0)),
+            Color::RED,
+        );
+
+        let positions = uploaded_positions(|graphics| {
+            graphics.draw_list(&list, (100.0, 200.0));
+        });
+
+        assert_eq!(
+            positions,
+            vec![
+                110.0, 220.0, 130.0, 220.0, 130.0, 240.0, 130.0, 240.0, 110.0, 240.0, 110.0, 220.0
+            ]
+        );
+    }
+
+    #[test]
+    fn test_draw_list_with_transform() {
+        let mut list = DrawList::new();
+
+        list.draw_triangle(
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(10.0, 10.0),
+            ],
+            Color::RED,
+        );
+
+        let positions = uploaded_positions(|graphics| {
+            graphics.draw_list_transformed(
+                &list,
+                Affine2::from_scale_angle_translation(Vec2::splat(2.0), 0.0, Vec2::new(5.0, 5.0)),
+            );
+        });
+
+        assert_eq!(positions, vec![5.0, 5.0, 25.0, 5.0, 25.0, 25.0]);
+    }
+}
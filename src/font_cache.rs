@@ -65,14 +65,16 @@ struct GlyphCacheKey {
 
 impl GlyphCacheKey {
     #[inline]
-    fn from(font_id: usize, positioned_glyph: &PositionedGlyph, screen_offset: Vec2) -> Self {
+    fn from(
+        font_id: usize,
+        positioned_glyph: &PositionedGlyph,
+        screen_offset: Vec2,
+        scale_factor: f32,
+    ) -> Self {
         // Assuming scale is uniform
-        let scale = QuantizedDimension::from_pixels(positioned_glyph.scale().y);
+        let scale = QuantizedDimension::from_pixels(positioned_glyph.scale().y * scale_factor);
 
-        let pos = Vec2::new(
-            positioned_glyph.position().x + screen_offset.x,
-            positioned_glyph.position().y + screen_offset.y,
-        );
+        let pos = (positioned_glyph.position() + screen_offset) * scale_factor;
 
         let subpixel_offset = (
             QuantizedDimension::from_pixels(pos.x - pos.x.round()),
@@ -88,12 +90,27 @@ impl GlyphCacheKey {
     }
 }
 
+/// Counts of glyph cache activity, for [crate::FrameStats].
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct GlyphCacheStats {
+    pub(crate) hits: u32,
+    pub(crate) misses: u32,
+    pub(crate) rearrangements: u32,
+}
+
 pub(crate) struct GlyphCache {
     last_frame: HashSet<GlyphCacheKey>,
     this_frame: HashSet<GlyphCacheKey>,
 
     cache_entries: HashMap<GlyphCacheKey, GlyphCacheEntry>,
     textures: Vec<GlyphCacheTexture>,
+
+    /// The number of physical pixels per drawing unit. Glyphs are rasterized
+    /// at physical resolution, so that text stays sharp when drawing in
+    /// logical pixels.
+    scale_factor: f32,
+
+    stats: GlyphCacheStats,
 }
 
 impl GlyphCache {
@@ -108,7 +125,12 @@ impl GlyphCache {
     ) {
         let positioned_glyph = glyph.glyph();
 
-        let key = GlyphCacheKey::from(glyph.font_id(), positioned_glyph, position);
+        let key = GlyphCacheKey::from(
+            glyph.font_id(),
+            positioned_glyph,
+            position,
+            self.scale_factor,
+        );
 
         let entry = match self.cache_entries.get(&key) {
             None => return, // This is valid for many glyphs, e.g. space
@@ -128,14 +150,17 @@ impl GlyphCache {
             top_left.as_vec2().div(texture_size),
             bottom_right.as_vec2().div(texture_size),
         );
-        let position = position + positioned_glyph.position();
+        let position = (position + positioned_glyph.position()) * self.scale_factor;
 
         // We round the position here as the offset is between -0.5 and 0.5
         let screen_region_start = position.round().as_ivec2() + entry.bounding_box_offset;
 
+        // The glyph was rasterized in physical pixels, so convert the region
+        // back into drawing units
         let mut screen_region = Rect::new(
-            screen_region_start.as_vec2(),
-            (screen_region_start + texture_entry.texture_area.size().as_ivec2()).as_vec2(),
+            screen_region_start.as_vec2() / self.scale_factor,
+            (screen_region_start + texture_entry.texture_area.size().as_ivec2()).as_vec2()
+                / self.scale_factor,
         );
 
         if let Some(crop_window) = crop_window {
@@ -248,7 +273,12 @@ impl GlyphCache {
         formatted_glyph: &FormattedGlyph,
         position: Vec2,
     ) {
-        let key = GlyphCacheKey::from(formatted_glyph.font_id(), formatted_glyph.glyph(), position);
+        let key = GlyphCacheKey::from(
+            formatted_glyph.font_id(),
+            formatted_glyph.glyph(),
+            position,
+            self.scale_factor,
+        );
 
         self.this_frame.insert(key.clone());
 
@@ -257,9 +287,12 @@ impl GlyphCache {
         match cache_entries.entry(key.clone()) {
             Entry::Occupied(_) => {
                 // Already in the cache, nothing to do
+                self.stats.hits += 1;
             }
 
             Entry::Vacant(entry) => {
+                self.stats.misses += 1;
+
                 let glyph = formatted_glyph
                     .glyph()
                     .unpositioned()
@@ -306,6 +339,11 @@ impl GlyphCache {
         }
     }
 
+    #[inline]
+    pub(crate) fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
     pub(crate) fn on_new_frame_start(&mut self) {
         self.last_frame.clear();
         std::mem::swap(&mut self.last_frame, &mut self.this_frame);
@@ -317,6 +355,7 @@ impl GlyphCache {
     ) -> Result<(), BacktraceError<ErrorMessage>> {
         if self.try_insert_pending().is_err() {
             // Not enough space. Rearrange everything!
+            self.stats.rearrangements += 1;
 
             self.textures.iter_mut().for_each(|texture| texture.clear());
 
@@ -381,9 +420,26 @@ impl GlyphCache {
             this_frame: HashSet::new(),
             cache_entries: HashMap::new(),
             textures: Vec::new(),
+            scale_factor: 1.0,
+            stats: GlyphCacheStats::default(),
         }
     }
 
+    /// Returns the cache activity since the last call, and resets the counts
+    /// to zero.
+    pub(crate) fn take_stats(&mut self) -> GlyphCacheStats {
+        std::mem::take(&mut self.stats)
+    }
+
+    #[inline]
+    pub(crate) fn texture_count(&self) -> usize {
+        self.textures.len()
+    }
+
+    pub(crate) fn textures(&self) -> impl Iterator<Item = &GLTexture> {
+        self.textures.iter().map(|texture| &texture.texture)
+    }
+
     fn try_insert_pending(&mut self) -> Result<(), GlyphCacheTextureAppendError> {
         for (key, entry) in &mut self.cache_entries {
             if entry.texture_id.is_none() {
@@ -539,6 +595,24 @@ impl BitmapRGBA {
         context: &GLContextManager,
         texture: &GLTexture,
     ) -> Result<(), BacktraceError<ErrorMessage>> {
+        if context.version().is_gles2_or_webgl1() {
+            // Glyphs are always white, so only the luminance and alpha
+            // channels need to be uploaded, halving the texture memory used.
+            let data: Vec<u8> = self
+                .data
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[0], pixel[3]])
+                .collect();
+
+            return texture.set_image_data(
+                context,
+                GLTextureImageFormatU8::LuminanceAlpha,
+                GLTextureSmoothing::NearestNeighbour,
+                &self.size,
+                data.as_slice(),
+            );
+        }
+
         texture.set_image_data(
             context,
             GLTextureImageFormatU8::RGBA,
@@ -14,6 +14,14 @@
  *  limitations under the License.
  */
 
+//! The low-level interface between Speedy2D and OpenGL.
+//!
+//! By default, Speedy2D uses [GLBackendGlow], which forwards each call to a
+//! real OpenGL context. A different implementation of [GLBackend] can be
+//! passed to [crate::GLRenderer::new_with_gl_backend], for example
+//! [RecordingGLBackend] to test rendering code without a GPU, or
+//! [SoftwareGLBackend] to draw on a machine without a GPU or display.
+
 use std::mem::MaybeUninit;
 
 use glow::{HasContext, PixelPackData};
@@ -22,6 +30,33 @@ use crate::error::{BacktraceError, ErrorMessage};
 use crate::glbackend::constants::*;
 use crate::glbackend::types::*;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::glbackend_recording::{GLCall, RecordingGLBackend};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::glbackend_software::SoftwareGLBackend;
+
+/// Creates a renderer which draws using the specified backend, for use in
+/// tests. The backend is returned so that its state can be inspected.
+#[cfg(test)]
+pub(crate) fn create_test_renderer<B, V>(
+    backend: B,
+    viewport_size_pixels: V,
+    gl_version: crate::GLVersion,
+) -> (std::rc::Rc<B>, crate::GLRenderer)
+where
+    B: GLBackend + 'static,
+    V: Into<glam::UVec2>,
+{
+    let backend = std::rc::Rc::new(backend);
+
+    let renderer =
+        crate::GLRenderer::new_with_gl_backend(viewport_size_pixels, backend.clone(), gl_version)
+            .unwrap();
+
+    (backend, renderer)
+}
+
+/// The types used for GL parameters and object handles.
 pub mod types {
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     pub type GLenum = u32;
@@ -33,9 +68,12 @@ pub mod types {
     pub type GLTypeProgram = glow::Program;
     pub type GLTypeBuffer = glow::Buffer;
     pub type GLTypeTexture = glow::Texture;
+    pub type GLTypeFramebuffer = glow::Framebuffer;
+    pub type GLTypeVertexArray = glow::VertexArray;
     pub type GLTypeUniformLocation = glow::UniformLocation;
 }
 
+/// The GL constants used by Speedy2D.
 pub mod constants {
     use crate::glbackend::types::GLenum;
 
@@ -43,6 +81,7 @@ pub mod constants {
     pub const GL_VERSION: GLenum = glow::VERSION;
 
     pub const GL_TEXTURE0: GLenum = glow::TEXTURE0;
+    pub const GL_TEXTURE1: GLenum = glow::TEXTURE1;
 
     pub const GL_TEXTURE_2D: GLenum = glow::TEXTURE_2D;
 
@@ -50,9 +89,12 @@ pub mod constants {
 
     pub const GL_SCISSOR_TEST: GLenum = glow::SCISSOR_TEST;
 
+    pub const GL_ZERO: GLenum = glow::ZERO;
     pub const GL_ONE: GLenum = glow::ONE;
     pub const GL_SRC_ALPHA: GLenum = glow::SRC_ALPHA;
     pub const GL_ONE_MINUS_SRC_ALPHA: GLenum = glow::ONE_MINUS_SRC_ALPHA;
+    pub const GL_DST_ALPHA: GLenum = glow::DST_ALPHA;
+    pub const GL_ONE_MINUS_DST_ALPHA: GLenum = glow::ONE_MINUS_DST_ALPHA;
 
     pub const GL_NEAREST: GLenum = glow::NEAREST;
     pub const GL_LINEAR: GLenum = glow::LINEAR;
@@ -72,6 +114,8 @@ pub mod constants {
     pub const GL_RED: GLenum = glow::RED;
     pub const GL_RGB: GLenum = glow::RGB;
     pub const GL_RGBA: GLenum = glow::RGBA;
+    pub const GL_LUMINANCE: GLenum = glow::LUMINANCE;
+    pub const GL_LUMINANCE_ALPHA: GLenum = glow::LUMINANCE_ALPHA;
 
     pub const GL_TEXTURE_WRAP_S: GLenum = glow::TEXTURE_WRAP_S;
     pub const GL_TEXTURE_WRAP_T: GLenum = glow::TEXTURE_WRAP_T;
@@ -99,6 +143,12 @@ pub mod constants {
     pub const GL_INFO_LOG_LENGTH: GLenum = glow::INFO_LOG_LENGTH;
 
     pub const GL_UNPACK_ALIGNMENT: GLenum = glow::UNPACK_ALIGNMENT;
+
+    pub const GL_FRAMEBUFFER: GLenum = glow::FRAMEBUFFER;
+    pub const GL_COLOR_ATTACHMENT0: GLenum = glow::COLOR_ATTACHMENT0;
+    pub const GL_FRAMEBUFFER_COMPLETE: GLenum = glow::FRAMEBUFFER_COMPLETE;
+    pub const GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT: GLenum =
+        glow::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT;
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
@@ -134,13 +184,43 @@ impl From<GLErrorCode> for BacktraceError<ErrorMessage> {
     }
 }
 
+/// A set of OpenGL functions, as used by Speedy2D.
+///
+/// The functions correspond directly to the OpenGL functions of the same
+/// name. Implementations are only expected to behave like a GL 2.0 (or
+/// GLES 2.0/WebGL) context, except for the vertex array functions, which are only
+/// used with [GLVersion::OpenGL3_3Core](crate::GLVersion::OpenGL3_3Core).
+///
+/// # Safety
+///
+/// Every `unsafe` function in this trait has the same contract as the
+/// OpenGL function it corresponds to. In particular, callers must ensure
+/// that:
+///
+/// * for implementations which forward to a real context, that context is
+///   current on the calling thread;
+/// * every handle passed in was created by this backend (and by the same
+///   context, or one sharing objects with it), and hasn't been deleted;
+/// * data passed to `gl_buffer_data`, `gl_tex_image_2d` and
+///   `gl_tex_sub_image_2d` is large enough for the specified size, format
+///   and unpack alignment, and the buffer passed to `gl_read_pixels` is
+///   large enough for the requested region.
+///
+/// Implementations which don't touch a real context, such as
+/// `RecordingGLBackend` and `SoftwareGLBackend`, must not rely on these
+/// conditions for memory safety.
+// The contract is shared by every function, and documented once above,
+// rather than repeating it in a `# Safety` section for each of them
+#[allow(clippy::missing_safety_doc)]
 pub trait GLBackend {
     unsafe fn gl_delete_program(&self, handle: GLTypeProgram);
     unsafe fn gl_delete_shader(&self, handle: GLTypeShader);
     unsafe fn gl_delete_buffer(&self, handle: GLTypeBuffer);
     unsafe fn gl_delete_texture(&self, handle: GLTypeTexture);
+    unsafe fn gl_delete_framebuffer(&self, handle: GLTypeFramebuffer);
+    unsafe fn gl_delete_vertex_array(&self, handle: GLTypeVertexArray);
     unsafe fn gl_active_texture(&self, unit: GLenum);
-    unsafe fn gl_bind_texture(&self, target: GLenum, handle: GLTypeTexture);
+    unsafe fn gl_bind_texture(&self, target: GLenum, handle: Option<GLTypeTexture>);
     unsafe fn gl_enable(&self, cap: GLenum);
     unsafe fn gl_disable(&self, cap: GLenum);
     #[allow(dead_code)]
@@ -157,12 +237,24 @@ pub trait GLBackend {
     unsafe fn gl_disable_vertex_attrib_array(&self, handle: GLuint);
     unsafe fn gl_uniform_1f(&self, handle: &GLTypeUniformLocation, value: f32);
     unsafe fn gl_uniform_1i(&self, handle: &GLTypeUniformLocation, value: GLint);
+    unsafe fn gl_uniform_2f(&self, handle: &GLTypeUniformLocation, x: f32, y: f32);
     unsafe fn gl_attach_shader(&self, program: GLTypeProgram, shader: GLTypeShader);
     unsafe fn gl_link_program(&self, program: GLTypeProgram);
     unsafe fn gl_shader_source(&self, handle: GLTypeShader, source: &str);
     unsafe fn gl_compile_shader(&self, handle: GLTypeShader);
     unsafe fn gl_tex_parameter_i(&self, target: GLenum, parameter: GLenum, value: GLint);
     unsafe fn gl_bind_buffer(&self, target: GLenum, handle: GLTypeBuffer);
+    unsafe fn gl_bind_framebuffer(&self, target: GLenum, handle: Option<GLTypeFramebuffer>);
+    unsafe fn gl_bind_vertex_array(&self, handle: Option<GLTypeVertexArray>);
+    unsafe fn gl_framebuffer_texture_2d(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        texture_target: GLenum,
+        texture: GLTypeTexture,
+        level: GLint,
+    );
+    unsafe fn gl_check_framebuffer_status(&self, target: GLenum) -> GLenum;
     unsafe fn gl_buffer_data(&self, target: GLenum, data: &[u8], usage: GLenum);
     unsafe fn gl_draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei);
     unsafe fn gl_clear_color(&self, r: f32, g: f32, b: f32, a: f32);
@@ -224,6 +316,11 @@ pub trait GLBackend {
 
     unsafe fn gl_gen_texture(&self) -> Result<GLTypeTexture, BacktraceError<ErrorMessage>>;
 
+    unsafe fn gl_gen_framebuffer(&self) -> Result<GLTypeFramebuffer, BacktraceError<ErrorMessage>>;
+
+    unsafe fn gl_gen_vertex_array(&self)
+        -> Result<GLTypeVertexArray, BacktraceError<ErrorMessage>>;
+
     #[must_use]
     unsafe fn gl_get_error(&self) -> GLenum;
 
@@ -300,11 +397,13 @@ pub trait GLBackend {
     );
 }
 
+/// A [GLBackend] which forwards each call to a `glow` context.
 pub struct GLBackendGlow {
     context: glow::Context,
 }
 
 impl GLBackendGlow {
+    /// Wraps the specified `glow` context.
     #[must_use]
     pub fn new(context: glow::Context) -> Self {
         GLBackendGlow { context }
@@ -328,12 +427,20 @@ impl GLBackend for GLBackendGlow {
         self.context.delete_texture(handle)
     }
 
+    unsafe fn gl_delete_framebuffer(&self, handle: GLTypeFramebuffer) {
+        self.context.delete_framebuffer(handle)
+    }
+
+    unsafe fn gl_delete_vertex_array(&self, handle: GLTypeVertexArray) {
+        self.context.delete_vertex_array(handle)
+    }
+
     unsafe fn gl_active_texture(&self, unit: GLenum) {
         self.context.active_texture(unit)
     }
 
-    unsafe fn gl_bind_texture(&self, target: GLenum, handle: GLTypeTexture) {
-        self.context.bind_texture(target, Some(handle))
+    unsafe fn gl_bind_texture(&self, target: GLenum, handle: Option<GLTypeTexture>) {
+        self.context.bind_texture(target, handle)
     }
 
     unsafe fn gl_enable(&self, cap: GLenum) {
@@ -379,6 +486,10 @@ impl GLBackend for GLBackendGlow {
         self.context.uniform_1_i32(Some(handle), value)
     }
 
+    unsafe fn gl_uniform_2f(&self, handle: &GLTypeUniformLocation, x: f32, y: f32) {
+        self.context.uniform_2_f32(Some(handle), x, y)
+    }
+
     unsafe fn gl_attach_shader(&self, program: GLTypeProgram, shader: GLTypeShader) {
         self.context.attach_shader(program, shader)
     }
@@ -403,6 +514,35 @@ impl GLBackend for GLBackendGlow {
         self.context.bind_buffer(target, Some(handle))
     }
 
+    unsafe fn gl_bind_framebuffer(&self, target: GLenum, handle: Option<GLTypeFramebuffer>) {
+        self.context.bind_framebuffer(target, handle)
+    }
+
+    unsafe fn gl_bind_vertex_array(&self, handle: Option<GLTypeVertexArray>) {
+        self.context.bind_vertex_array(handle)
+    }
+
+    unsafe fn gl_framebuffer_texture_2d(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        texture_target: GLenum,
+        texture: GLTypeTexture,
+        level: GLint,
+    ) {
+        self.context.framebuffer_texture_2d(
+            target,
+            attachment,
+            texture_target,
+            Some(texture),
+            level,
+        )
+    }
+
+    unsafe fn gl_check_framebuffer_status(&self, target: GLenum) -> GLenum {
+        self.context.check_framebuffer_status(target)
+    }
+
     unsafe fn gl_buffer_data(&self, target: u32, data: &[u8], usage: u32) {
         self.context.buffer_data_u8_slice(target, data, usage)
     }
@@ -565,6 +705,26 @@ impl GLBackend for GLBackendGlow {
         Ok(handle)
     }
 
+    unsafe fn gl_gen_framebuffer(&self) -> Result<GLTypeFramebuffer, BacktraceError<ErrorMessage>> {
+        let handle = self
+            .context
+            .create_framebuffer()
+            .map_err(|err| ErrorMessage::msg(format!("Failed to create framebuffer: {err}")))?;
+
+        Ok(handle)
+    }
+
+    unsafe fn gl_gen_vertex_array(
+        &self,
+    ) -> Result<GLTypeVertexArray, BacktraceError<ErrorMessage>> {
+        let handle = self
+            .context
+            .create_vertex_array()
+            .map_err(|err| ErrorMessage::msg(format!("Failed to create vertex array: {err}")))?;
+
+        Ok(handle)
+    }
+
     unsafe fn gl_get_error(&self) -> GLenum {
         self.context.get_error()
     }
@@ -0,0 +1,1079 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::mem::MaybeUninit;
+use std::num::NonZeroU32;
+
+use crate::error::{BacktraceError, ErrorMessage};
+use crate::glbackend::constants::*;
+use crate::glbackend::types::*;
+use crate::glbackend::GLBackend;
+
+/// A single call made to a [RecordingGLBackend].
+///
+/// Handles (programs, shaders, buffers, textures, framebuffers, vertex
+/// arrays, and uniform locations) are represented by their raw numeric IDs.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub enum GLCall {
+    DeleteProgram(GLuint),
+    DeleteShader(GLuint),
+    DeleteBuffer(GLuint),
+    DeleteTexture(GLuint),
+    DeleteFramebuffer(GLuint),
+    DeleteVertexArray(GLuint),
+    ActiveTexture(GLenum),
+    BindTexture {
+        target: GLenum,
+        texture: Option<GLuint>,
+    },
+    Enable(GLenum),
+    Disable(GLenum),
+    BlendFunc {
+        sfactor: GLenum,
+        dfactor: GLenum,
+    },
+    BlendFuncSeparate {
+        sfactor: GLenum,
+        dfactor: GLenum,
+        sfactor_alpha: GLenum,
+        dfactor_alpha: GLenum,
+    },
+    UseProgram(GLuint),
+    EnableVertexAttribArray(GLuint),
+    DisableVertexAttribArray(GLuint),
+    Uniform1f {
+        location: GLuint,
+        value: f32,
+    },
+    Uniform1i {
+        location: GLuint,
+        value: GLint,
+    },
+    Uniform2f {
+        location: GLuint,
+        x: f32,
+        y: f32,
+    },
+    AttachShader {
+        program: GLuint,
+        shader: GLuint,
+    },
+    LinkProgram(GLuint),
+    ShaderSource {
+        shader: GLuint,
+        source: String,
+    },
+    CompileShader(GLuint),
+    TexParameterI {
+        target: GLenum,
+        parameter: GLenum,
+        value: GLint,
+    },
+    BindBuffer {
+        target: GLenum,
+        buffer: GLuint,
+    },
+    BindFramebuffer {
+        target: GLenum,
+        framebuffer: Option<GLuint>,
+    },
+    BindVertexArray(Option<GLuint>),
+    FramebufferTexture2D {
+        target: GLenum,
+        attachment: GLenum,
+        texture_target: GLenum,
+        texture: GLuint,
+        level: GLint,
+    },
+    CheckFramebufferStatus(GLenum),
+    BufferData {
+        target: GLenum,
+        data: Vec<u8>,
+        usage: GLenum,
+    },
+    DrawArrays {
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+    },
+    ClearColor {
+        r: f32,
+        g: f32,
+        b: f32,
+        a: f32,
+    },
+    Clear(GLenum),
+    EnableDebugMessageCallback,
+    GetString(GLenum),
+    Viewport {
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    },
+    Scissor {
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    },
+    PixelStoreI {
+        param: GLenum,
+        value: GLint,
+    },
+    VertexAttribPointerF32 {
+        index: GLuint,
+        size: GLsizei,
+        data_type: GLenum,
+        normalized: bool,
+        stride: GLsizei,
+        offset: GLsizei,
+    },
+    TexImage2D {
+        target: GLenum,
+        level: GLint,
+        internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        data_type: GLenum,
+        pixels: Option<Vec<u8>>,
+    },
+    TexSubImage2D {
+        target: GLenum,
+        level: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        data_type: GLenum,
+        pixels: Vec<u8>,
+    },
+    CreateProgram(GLuint),
+    CreateShader {
+        shader_type: GLenum,
+        shader: GLuint,
+    },
+    GenBuffer(GLuint),
+    GenTexture(GLuint),
+    GenFramebuffer(GLuint),
+    GenVertexArray(GLuint),
+    GetAttribLocation {
+        program: GLuint,
+        name: String,
+    },
+    GetUniformLocation {
+        program: GLuint,
+        name: String,
+    },
+    ReadPixels {
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        data_type: GLenum,
+    },
+}
+
+impl GLCall {
+    /// If this is a [GLCall::BufferData] call, returns the uploaded data
+    /// interpreted as `f32` values, which is how Speedy2D uploads its vertex
+    /// attributes.
+    #[must_use]
+    pub fn buffer_data_as_f32(&self) -> Option<Vec<f32>> {
+        match self {
+            GLCall::BufferData { data, .. } => Some(
+                data.chunks_exact(4)
+                    .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// A [GLBackend] which doesn't require a GPU or display. Every call is
+/// recorded along with its arguments, and can later be inspected using
+/// [RecordingGLBackend::calls].
+///
+/// Object creation functions return unique fake handles, shaders compile and
+/// link successfully (unless [RecordingGLBackend::fail_shaders_containing] is
+/// used), framebuffers are always complete, and reading pixels returns
+/// zeroes. Queries for the current error, and for the
+/// status or info log of a shader or program, are not recorded.
+///
+/// ```rust,no_run
+/// use std::rc::Rc;
+/// use glam::Vec2;
+/// use speedy2d::color::Color;
+/// use speedy2d::glbackend::RecordingGLBackend;
+/// use speedy2d::{GLRenderer, GLVersion, Rect};
+///
+/// let backend = Rc::new(RecordingGLBackend::new());
+///
+/// let mut renderer =
+///     GLRenderer::new_with_gl_backend((640, 480), backend.clone(), GLVersion::OpenGL2_0)
+///         .unwrap();
+///
+/// // Ignore the calls made during setup
+/// backend.clear_calls();
+///
+/// renderer.draw_frame(|graphics| {
+///     graphics.draw_rectangle(
+///         Rect::new(Vec2::new(10.0, 10.0), Vec2::new(50.0, 50.0)),
+///         Color::RED,
+///     );
+/// });
+///
+/// assert_eq!(backend.draw_call_count(), 1);
+/// ```
+pub struct RecordingGLBackend {
+    calls: RefCell<Vec<GLCall>>,
+    next_handle: Cell<u32>,
+    attribute_locations: RefCell<HashMap<GLuint, Vec<String>>>,
+    failing_shader_text: RefCell<Option<String>>,
+    failed_shaders: RefCell<HashSet<GLuint>>,
+}
+
+impl RecordingGLBackend {
+    /// Creates a new backend with no recorded calls.
+    #[must_use]
+    pub fn new() -> Self {
+        RecordingGLBackend {
+            calls: RefCell::new(Vec::new()),
+            next_handle: Cell::new(1),
+            attribute_locations: RefCell::new(HashMap::new()),
+            failing_shader_text: RefCell::new(None),
+            failed_shaders: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// Returns a copy of all the calls recorded so far, in order.
+    #[must_use]
+    pub fn calls(&self) -> Vec<GLCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// Returns all the calls recorded so far, and clears the log.
+    pub fn take_calls(&self) -> Vec<GLCall> {
+        std::mem::take(&mut *self.calls.borrow_mut())
+    }
+
+    /// Clears the log of recorded calls. This is useful to ignore the calls
+    /// made while creating the renderer.
+    pub fn clear_calls(&self) {
+        self.calls.borrow_mut().clear();
+    }
+
+    /// Makes any shader whose source contains the specified text fail to
+    /// compile from now on, to test how shader errors are handled.
+    pub fn fail_shaders_containing<S: Into<String>>(&self, text: S) {
+        *self.failing_shader_text.borrow_mut() = Some(text.into());
+    }
+
+    /// Returns the number of recorded calls to `glDrawArrays`.
+    #[must_use]
+    pub fn draw_call_count(&self) -> usize {
+        self.calls
+            .borrow()
+            .iter()
+            .filter(|call| matches!(call, GLCall::DrawArrays { .. }))
+            .count()
+    }
+
+    fn record(&self, call: GLCall) {
+        self.calls.borrow_mut().push(call);
+    }
+
+    fn next_handle(&self) -> NonZeroU32 {
+        let handle = self.next_handle.get();
+        self.next_handle.set(handle + 1);
+        NonZeroU32::new(handle).unwrap()
+    }
+}
+
+impl Default for RecordingGLBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GLBackend for RecordingGLBackend {
+    unsafe fn gl_delete_program(&self, handle: GLTypeProgram) {
+        self.record(GLCall::DeleteProgram(handle.0.get()))
+    }
+
+    unsafe fn gl_delete_shader(&self, handle: GLTypeShader) {
+        self.record(GLCall::DeleteShader(handle.0.get()))
+    }
+
+    unsafe fn gl_delete_buffer(&self, handle: GLTypeBuffer) {
+        self.record(GLCall::DeleteBuffer(handle.0.get()))
+    }
+
+    unsafe fn gl_delete_texture(&self, handle: GLTypeTexture) {
+        self.record(GLCall::DeleteTexture(handle.0.get()))
+    }
+
+    unsafe fn gl_delete_framebuffer(&self, handle: GLTypeFramebuffer) {
+        self.record(GLCall::DeleteFramebuffer(handle.0.get()))
+    }
+
+    unsafe fn gl_delete_vertex_array(&self, handle: GLTypeVertexArray) {
+        self.record(GLCall::DeleteVertexArray(handle.0.get()))
+    }
+
+    unsafe fn gl_active_texture(&self, unit: GLenum) {
+        self.record(GLCall::ActiveTexture(unit))
+    }
+
+    unsafe fn gl_bind_texture(&self, target: GLenum, handle: Option<GLTypeTexture>) {
+        self.record(GLCall::BindTexture {
+            target,
+            texture: handle.map(|handle| handle.0.get()),
+        })
+    }
+
+    unsafe fn gl_enable(&self, cap: GLenum) {
+        self.record(GLCall::Enable(cap))
+    }
+
+    unsafe fn gl_disable(&self, cap: GLenum) {
+        self.record(GLCall::Disable(cap))
+    }
+
+    unsafe fn gl_blend_func(&self, sfactor: GLenum, dfactor: GLenum) {
+        self.record(GLCall::BlendFunc { sfactor, dfactor })
+    }
+
+    unsafe fn gl_blend_func_separate(
+        &self,
+        sfactor: GLenum,
+        dfactor: GLenum,
+        sfactor_alpha: GLenum,
+        dfactor_alpha: GLenum,
+    ) {
+        self.record(GLCall::BlendFuncSeparate {
+            sfactor,
+            dfactor,
+            sfactor_alpha,
+            dfactor_alpha,
+        })
+    }
+
+    unsafe fn gl_use_program(&self, handle: GLTypeProgram) {
+        self.record(GLCall::UseProgram(handle.0.get()))
+    }
+
+    unsafe fn gl_enable_vertex_attrib_array(&self, handle: GLuint) {
+        self.record(GLCall::EnableVertexAttribArray(handle))
+    }
+
+    unsafe fn gl_disable_vertex_attrib_array(&self, handle: GLuint) {
+        self.record(GLCall::DisableVertexAttribArray(handle))
+    }
+
+    unsafe fn gl_uniform_1f(&self, handle: &GLTypeUniformLocation, value: f32) {
+        self.record(GLCall::Uniform1f {
+            location: handle.0,
+            value,
+        })
+    }
+
+    unsafe fn gl_uniform_1i(&self, handle: &GLTypeUniformLocation, value: GLint) {
+        self.record(GLCall::Uniform1i {
+            location: handle.0,
+            value,
+        })
+    }
+
+    unsafe fn gl_uniform_2f(&self, handle: &GLTypeUniformLocation, x: f32, y: f32) {
+        self.record(GLCall::Uniform2f {
+            location: handle.0,
+            x,
+            y,
+        })
+    }
+
+    unsafe fn gl_attach_shader(&self, program: GLTypeProgram, shader: GLTypeShader) {
+        self.record(GLCall::AttachShader {
+            program: program.0.get(),
+            shader: shader.0.get(),
+        })
+    }
+
+    unsafe fn gl_link_program(&self, program: GLTypeProgram) {
+        self.record(GLCall::LinkProgram(program.0.get()))
+    }
+
+    unsafe fn gl_shader_source(&self, handle: GLTypeShader, source: &str) {
+        if let Some(text) = &*self.failing_shader_text.borrow() {
+            if source.contains(text.as_str()) {
+                self.failed_shaders.borrow_mut().insert(handle.0.get());
+            }
+        }
+
+        self.record(GLCall::ShaderSource {
+            shader: handle.0.get(),
+            source: source.to_string(),
+        })
+    }
+
+    unsafe fn gl_compile_shader(&self, handle: GLTypeShader) {
+        self.record(GLCall::CompileShader(handle.0.get()))
+    }
+
+    unsafe fn gl_tex_parameter_i(&self, target: GLenum, parameter: GLenum, value: GLint) {
+        self.record(GLCall::TexParameterI {
+            target,
+            parameter,
+            value,
+        })
+    }
+
+    unsafe fn gl_bind_buffer(&self, target: GLenum, handle: GLTypeBuffer) {
+        self.record(GLCall::BindBuffer {
+            target,
+            buffer: handle.0.get(),
+        })
+    }
+
+    unsafe fn gl_bind_framebuffer(&self, target: GLenum, handle: Option<GLTypeFramebuffer>) {
+        self.record(GLCall::BindFramebuffer {
+            target,
+            framebuffer: handle.map(|handle| handle.0.get()),
+        })
+    }
+
+    unsafe fn gl_bind_vertex_array(&self, handle: Option<GLTypeVertexArray>) {
+        self.record(GLCall::BindVertexArray(handle.map(|handle| handle.0.get())))
+    }
+
+    unsafe fn gl_framebuffer_texture_2d(
+        &self,
+        target: GLenum,
+        attachment: GLenum,
+        texture_target: GLenum,
+        texture: GLTypeTexture,
+        level: GLint,
+    ) {
+        self.record(GLCall::FramebufferTexture2D {
+            target,
+            attachment,
+            texture_target,
+            texture: texture.0.get(),
+            level,
+        })
+    }
+
+    unsafe fn gl_check_framebuffer_status(&self, target: GLenum) -> GLenum {
+        self.record(GLCall::CheckFramebufferStatus(target));
+        GL_FRAMEBUFFER_COMPLETE
+    }
+
+    unsafe fn gl_buffer_data(&self, target: GLenum, data: &[u8], usage: GLenum) {
+        self.record(GLCall::BufferData {
+            target,
+            data: data.to_vec(),
+            usage,
+        })
+    }
+
+    unsafe fn gl_draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei) {
+        self.record(GLCall::DrawArrays { mode, first, count })
+    }
+
+    unsafe fn gl_clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.record(GLCall::ClearColor { r, g, b, a })
+    }
+
+    unsafe fn gl_clear(&self, mask: GLenum) {
+        self.record(GLCall::Clear(mask))
+    }
+
+    unsafe fn gl_enable_debug_message_callback(&self) {
+        self.record(GLCall::EnableDebugMessageCallback)
+    }
+
+    unsafe fn gl_get_string(&self, parameter: GLenum) -> String {
+        self.record(GLCall::GetString(parameter));
+        String::from("RecordingGLBackend")
+    }
+
+    unsafe fn gl_viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        self.record(GLCall::Viewport {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    unsafe fn gl_scissor(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.record(GLCall::Scissor {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    unsafe fn gl_pixel_store_i(&self, param: GLenum, value: GLint) {
+        self.record(GLCall::PixelStoreI { param, value })
+    }
+
+    unsafe fn gl_vertex_attrib_pointer_f32(
+        &self,
+        index: GLuint,
+        size: GLsizei,
+        data_type: GLenum,
+        normalized: bool,
+        stride: GLsizei,
+        offset: GLsizei,
+    ) {
+        self.record(GLCall::VertexAttribPointerF32 {
+            index,
+            size,
+            data_type,
+            normalized,
+            stride,
+            offset,
+        })
+    }
+
+    unsafe fn gl_tex_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        border: GLint,
+        format: GLenum,
+        data_type: GLenum,
+        pixels: Option<&[u8]>,
+    ) {
+        self.record(GLCall::TexImage2D {
+            target,
+            level,
+            internal_format,
+            width,
+            height,
+            border,
+            format,
+            data_type,
+            pixels: pixels.map(<[u8]>::to_vec),
+        })
+    }
+
+    unsafe fn gl_tex_sub_image_2d(
+        &self,
+        target: GLenum,
+        level: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        data_type: GLenum,
+        pixels: &[u8],
+    ) {
+        self.record(GLCall::TexSubImage2D {
+            target,
+            level,
+            x,
+            y,
+            width,
+            height,
+            format,
+            data_type,
+            pixels: pixels.to_vec(),
+        })
+    }
+
+    unsafe fn gl_create_program(&self) -> Result<GLTypeProgram, BacktraceError<ErrorMessage>> {
+        let handle = self.next_handle();
+        self.record(GLCall::CreateProgram(handle.get()));
+        Ok(glow::NativeProgram(handle))
+    }
+
+    unsafe fn gl_create_shader(
+        &self,
+        shader_type: GLenum,
+    ) -> Result<GLTypeShader, BacktraceError<ErrorMessage>> {
+        let handle = self.next_handle();
+        self.record(GLCall::CreateShader {
+            shader_type,
+            shader: handle.get(),
+        });
+        Ok(glow::NativeShader(handle))
+    }
+
+    unsafe fn gl_gen_buffer(&self) -> Result<GLTypeBuffer, BacktraceError<ErrorMessage>> {
+        let handle = self.next_handle();
+        self.record(GLCall::GenBuffer(handle.get()));
+        Ok(glow::NativeBuffer(handle))
+    }
+
+    unsafe fn gl_gen_texture(&self) -> Result<GLTypeTexture, BacktraceError<ErrorMessage>> {
+        let handle = self.next_handle();
+        self.record(GLCall::GenTexture(handle.get()));
+        Ok(glow::NativeTexture(handle))
+    }
+
+    unsafe fn gl_gen_framebuffer(&self) -> Result<GLTypeFramebuffer, BacktraceError<ErrorMessage>> {
+        let handle = self.next_handle();
+        self.record(GLCall::GenFramebuffer(handle.get()));
+        Ok(glow::NativeFramebuffer(handle))
+    }
+
+    unsafe fn gl_gen_vertex_array(
+        &self,
+    ) -> Result<GLTypeVertexArray, BacktraceError<ErrorMessage>> {
+        let handle = self.next_handle();
+        self.record(GLCall::GenVertexArray(handle.get()));
+        Ok(glow::NativeVertexArray(handle))
+    }
+
+    unsafe fn gl_get_error(&self) -> GLenum {
+        GL_NO_ERROR
+    }
+
+    unsafe fn gl_get_attrib_location(&self, program: GLTypeProgram, name: &str) -> Option<GLuint> {
+        self.record(GLCall::GetAttribLocation {
+            program: program.0.get(),
+            name: name.to_string(),
+        });
+
+        // Each distinct attribute name gets its own location within a program
+        let mut locations = self.attribute_locations.borrow_mut();
+        let names = locations.entry(program.0.get()).or_default();
+
+        let index = match names.iter().position(|existing| existing == name) {
+            Some(index) => index,
+            None => {
+                names.push(name.to_string());
+                names.len() - 1
+            }
+        };
+
+        Some(index as GLuint)
+    }
+
+    unsafe fn gl_get_uniform_location(
+        &self,
+        program: GLTypeProgram,
+        name: &str,
+    ) -> Option<GLTypeUniformLocation> {
+        self.record(GLCall::GetUniformLocation {
+            program: program.0.get(),
+            name: name.to_string(),
+        });
+
+        Some(glow::NativeUniformLocation(self.next_handle().get()))
+    }
+
+    unsafe fn gl_get_program_link_status(&self, _program: GLTypeProgram) -> bool {
+        true
+    }
+
+    unsafe fn gl_get_shader_compile_status(&self, shader: GLTypeShader) -> bool {
+        !self.failed_shaders.borrow().contains(&shader.0.get())
+    }
+
+    unsafe fn gl_get_program_info_log(
+        &self,
+        _program: GLTypeProgram,
+    ) -> Result<String, BacktraceError<ErrorMessage>> {
+        Ok(String::new())
+    }
+
+    unsafe fn gl_get_shader_info_log(
+        &self,
+        shader: GLTypeShader,
+    ) -> Result<String, BacktraceError<ErrorMessage>> {
+        if self.failed_shaders.borrow().contains(&shader.0.get()) {
+            Ok("Simulated shader compile failure".to_string())
+        } else {
+            Ok(String::new())
+        }
+    }
+
+    unsafe fn gl_read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        data_type: GLenum,
+        data: &mut [MaybeUninit<u8>],
+    ) {
+        self.record(GLCall::ReadPixels {
+            x,
+            y,
+            width,
+            height,
+            format,
+            data_type,
+        });
+
+        for byte in data {
+            byte.write(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use glam::{UVec2, Vec2};
+    use glam_rect::Rect;
+
+    use crate::color::Color;
+    use crate::glbackend::constants::*;
+    use crate::glbackend::types::GLint;
+    use crate::glbackend::{create_test_renderer, GLCall, RecordingGLBackend};
+    use crate::{GLRenderer, GLVersion};
+
+    fn create_renderer() -> (Rc<RecordingGLBackend>, GLRenderer) {
+        let (backend, renderer) =
+            create_test_renderer(RecordingGLBackend::new(), (640, 480), GLVersion::OpenGL2_0);
+
+        backend.clear_calls();
+
+        (backend, renderer)
+    }
+
+    #[test]
+    fn test_rectangles_batched_into_one_draw_call() {
+        let (backend, mut renderer) = create_renderer();
+
+        renderer.draw_frame(|graphics| {
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(10.0, 20.0), Vec2::new(30.0, 40.0)),
+                Color::RED,
+            );
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(50.0, 60.0), Vec2::new(70.0, 80.0)),
+                Color::BLUE,
+            );
+        });
+
+        let calls = backend.calls();
+
+        assert_eq!(backend.draw_call_count(), 1);
+
+        assert!(calls.contains(&GLCall::DrawArrays {
+            mode: GL_TRIANGLES,
+            first: 0,
+            count: 12
+        }));
+
+        assert!(calls.contains(&GLCall::BlendFuncSeparate {
+            sfactor: GL_SRC_ALPHA,
+            dfactor: GL_ONE_MINUS_SRC_ALPHA,
+            sfactor_alpha: GL_ONE,
+            dfactor_alpha: GL_ONE_MINUS_SRC_ALPHA
+        }));
+    }
+
+    #[test]
+    fn test_uploaded_vertex_positions() {
+        let (backend, mut renderer) = create_renderer();
+
+        renderer.draw_frame(|graphics| {
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(10.0, 20.0), Vec2::new(30.0, 40.0)),
+                Color::RED,
+            );
+        });
+
+        let positions = backend
+            .calls()
+            .iter()
+            .filter_map(GLCall::buffer_data_as_f32)
+            .next()
+            .unwrap();
+
+        assert_eq!(
+            positions,
+            vec![10.0, 20.0, 30.0, 20.0, 30.0, 40.0, 30.0, 40.0, 10.0, 40.0, 10.0, 20.0]
+        );
+    }
+
+    #[test]
+    fn test_empty_frame_has_no_draw_calls() {
+        let (backend, mut renderer) = create_renderer();
+
+        renderer.draw_frame(|graphics| graphics.clear_screen(Color::WHITE));
+
+        assert_eq!(backend.draw_call_count(), 0);
+        assert!(backend
+            .calls()
+            .contains(&GLCall::Clear(GL_COLOR_BUFFER_BIT)));
+    }
+
+    #[test]
+    fn test_frame_stats() {
+        let (_backend, mut renderer) = create_renderer();
+
+        renderer.draw_frame(|graphics| {
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(10.0, 20.0), Vec2::new(30.0, 40.0)),
+                Color::RED,
+            );
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(50.0, 60.0), Vec2::new(70.0, 80.0)),
+                Color::BLUE,
+            );
+        });
+
+        let stats = renderer.last_frame_stats();
+
+        assert_eq!(stats.draw_calls, 1);
+        assert_eq!(stats.vertices_uploaded, 12);
+        assert_eq!(stats.render_queue_items, 4);
+        assert_eq!(stats.render_queue_flushes, 1);
+        assert_eq!(stats.texture_binds, 0);
+
+        renderer.draw_frame(|_graphics| {});
+
+        assert_eq!(renderer.last_frame_stats().draw_calls, 0);
+    }
+
+    #[test]
+    fn test_debug_overlay_excluded_from_frame_stats() {
+        let (backend, mut renderer) = create_renderer();
+
+        renderer.set_debug_overlay_enabled(true);
+        renderer.draw_frame(|graphics| graphics.clear_screen(Color::WHITE));
+
+        assert!(backend.draw_call_count() > 0);
+        assert_eq!(renderer.last_frame_stats().draw_calls, 0);
+    }
+
+    #[test]
+    fn test_virtual_resolution_upscaled_with_letterbox() {
+        let (backend, mut renderer) =
+            create_test_renderer(RecordingGLBackend::new(), (700, 400), GLVersion::OpenGL2_0);
+
+        // 700x400 isn't a multiple of 320x180, so the largest integer scale
+        // (2) leaves bars of 30px at the sides and 20px at the top and bottom
+        renderer
+            .set_virtual_resolution(Some(UVec2::new(320, 180)))
+            .unwrap();
+
+        backend.clear_calls();
+
+        renderer.draw_frame(|graphics| {
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(10.0, 20.0), Vec2::new(30.0, 40.0)),
+                Color::RED,
+            );
+        });
+
+        let uploads: Vec<Vec<f32>> = backend
+            .calls()
+            .iter()
+            .filter_map(GLCall::buffer_data_as_f32)
+            .collect();
+
+        assert!(uploads.contains(&vec![
+            10.0, 20.0, 30.0, 20.0, 30.0, 40.0, 30.0, 40.0, 10.0, 40.0, 10.0, 20.0
+        ]));
+
+        assert!(uploads.contains(&vec![
+            30.0, 20.0, 670.0, 20.0, 670.0, 380.0, 670.0, 380.0, 30.0, 380.0, 30.0, 20.0
+        ]));
+
+        assert!(backend.calls().contains(&GLCall::BindFramebuffer {
+            target: GL_FRAMEBUFFER,
+            framebuffer: None
+        }));
+    }
+
+    #[test]
+    fn test_virtual_resolution_mouse_position() {
+        let (_backend, mut renderer) =
+            create_test_renderer(RecordingGLBackend::new(), (700, 400), GLVersion::OpenGL2_0);
+
+        assert_eq!(
+            renderer.convert_window_position_to_virtual(Vec2::new(15.0, 25.0)),
+            Vec2::new(15.0, 25.0)
+        );
+
+        renderer
+            .set_virtual_resolution(Some(UVec2::new(320, 180)))
+            .unwrap();
+
+        assert_eq!(
+            renderer.convert_window_position_to_virtual(Vec2::new(40.0, 34.0)),
+            Vec2::new(5.0, 7.0)
+        );
+
+        // Left and bottom letterbox bars
+        assert_eq!(
+            renderer.convert_window_position_to_virtual(Vec2::new(10.0, 200.0)),
+            Vec2::new(-10.0, 90.0)
+        );
+        assert_eq!(
+            renderer.convert_window_position_to_virtual(Vec2::new(350.0, 390.0)),
+            Vec2::new(160.0, 185.0)
+        );
+    }
+
+    #[test]
+    fn test_gl_3_3_core_binds_vertex_array() {
+        let (backend, mut renderer) = create_test_renderer(
+            RecordingGLBackend::new(),
+            (640, 480),
+            GLVersion::OpenGL3_3Core,
+        );
+
+        let vertex_array = backend
+            .calls()
+            .iter()
+            .find_map(|call| match call {
+                GLCall::GenVertexArray(handle) => Some(*handle),
+                _ => None,
+            })
+            .unwrap();
+
+        renderer.draw_frame(|graphics| {
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(10.0, 20.0), Vec2::new(30.0, 40.0)),
+                Color::RED,
+            );
+        });
+
+        assert!(backend
+            .calls()
+            .contains(&GLCall::BindVertexArray(Some(vertex_array))));
+        assert_eq!(backend.draw_call_count(), 1);
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_gles2_and_webgl1_use_v100_shaders_and_luminance_alpha_glyphs() {
+        use crate::font::{Font, TextLayout, TextOptions};
+
+        let font = Font::new(include_bytes!("../assets/fonts/NotoSans-Regular.ttf")).unwrap();
+        let text = font.layout_text("Hello", 20.0, TextOptions::new());
+
+        for version in [GLVersion::OpenGLES2_0, GLVersion::WebGL1_0] {
+            let (backend, mut renderer) =
+                create_test_renderer(RecordingGLBackend::new(), (640, 480), version);
+
+            let sources: Vec<String> = backend
+                .calls()
+                .iter()
+                .filter_map(|call| match call {
+                    GLCall::ShaderSource { source, .. } => Some(source.clone()),
+                    _ => None,
+                })
+                .collect();
+
+            assert!(!sources.is_empty(), "{version:?}");
+            assert!(
+                sources
+                    .iter()
+                    .all(|source| source.starts_with("#version 100")),
+                "{version:?}"
+            );
+
+            backend.clear_calls();
+            renderer.draw_frame(|graphics| graphics.draw_text(Vec2::ZERO, Color::BLACK, &text));
+
+            let glyph_textures: Vec<&GLCall> = backend
+                .calls()
+                .iter()
+                .filter(|call| matches!(call, GLCall::TexImage2D { .. }))
+                .collect();
+
+            assert!(!glyph_textures.is_empty(), "{version:?}");
+
+            for call in glyph_textures {
+                match call {
+                    GLCall::TexImage2D {
+                        internal_format,
+                        width,
+                        height,
+                        format,
+                        pixels: Some(pixels),
+                        ..
+                    } => {
+                        assert_eq!(*internal_format, GL_LUMINANCE_ALPHA as GLint);
+                        assert_eq!(*format, GL_LUMINANCE_ALPHA);
+                        assert_eq!(pixels.len(), (*width * *height * 2) as usize);
+                    }
+                    _ => panic!("Glyph texture uploaded without data: {call:?}"),
+                }
+            }
+
+            assert_ne!(backend.draw_call_count(), 0, "{version:?}");
+        }
+    }
+
+    #[cfg(feature = "image-loading")]
+    #[test]
+    fn test_context_loss_restores_retained_images() {
+        use crate::image::{ImageDataType, ImageSmoothingMode};
+
+        let (backend, mut renderer) = create_renderer();
+
+        renderer.set_retain_image_data(true);
+
+        let image = renderer
+            .create_image_from_raw_pixels(
+                ImageDataType::RGBA,
+                ImageSmoothingMode::NearestNeighbor,
+                glam::UVec2::new(1, 1),
+                &[10, 20, 30, 40],
+            )
+            .unwrap();
+
+        renderer.notify_context_lost();
+        assert!(renderer.is_context_lost());
+
+        backend.clear_calls();
+        renderer.draw_frame(|graphics| graphics.draw_image(Vec2::ZERO, &image));
+        assert_eq!(backend.draw_call_count(), 0);
+
+        renderer.notify_context_restored().unwrap();
+        assert!(!renderer.is_context_lost());
+
+        assert!(backend.calls().iter().any(|call| matches!(
+            call,
+            GLCall::TexImage2D {
+                pixels: Some(pixels),
+                ..
+            } if pixels == &[10, 20, 30, 40]
+        )));
+
+        backend.clear_calls();
+        renderer.draw_frame(|graphics| graphics.draw_image(Vec2::ZERO, &image));
+        assert_eq!(backend.draw_call_count(), 1);
+    }
+}
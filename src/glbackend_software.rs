@@ -0,0 +1,1463 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::mem::MaybeUninit;
+use std::num::NonZeroU32;
+
+use glam::{UVec2, Vec2, Vec4};
+
+use crate::error::{BacktraceError, ErrorMessage};
+use crate::glbackend::constants::*;
+use crate::glbackend::types::*;
+use crate::glbackend::GLBackend;
+
+/// A block of RGBA pixels, used both for textures and for the default
+/// framebuffer. As in GL, row zero is the first row uploaded (for textures)
+/// or the bottom row (for framebuffers).
+#[derive(Default)]
+struct Surface {
+    size: UVec2,
+    pixels: Vec<[u8; 4]>,
+    linear_filtering: bool,
+}
+
+impl Surface {
+    fn new(size: UVec2) -> Self {
+        Surface {
+            size,
+            pixels: vec![[0; 4]; size.x as usize * size.y as usize],
+            linear_filtering: false,
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.size.x as usize + x as usize
+    }
+
+    /// Returns the texel at the specified position, clamped to the edges.
+    fn texel(&self, x: i64, y: i64) -> Vec4 {
+        let x = x.clamp(0, self.size.x as i64 - 1) as u32;
+        let y = y.clamp(0, self.size.y as i64 - 1) as u32;
+
+        let [r, g, b, a] = self.pixels[self.index(x, y)];
+
+        Vec4::new(r as f32, g as f32, b as f32, a as f32) / 255.0
+    }
+
+    /// Equivalent to `texture2D()`, with `GL_CLAMP_TO_EDGE` wrapping.
+    fn sample(&self, coord: Vec2) -> Vec4 {
+        if self.size.x == 0 || self.size.y == 0 {
+            return Vec4::new(0.0, 0.0, 0.0, 1.0);
+        }
+
+        let position = coord * self.size.as_vec2();
+
+        if !self.linear_filtering {
+            return self.texel(position.x.floor() as i64, position.y.floor() as i64);
+        }
+
+        let position = position - Vec2::splat(0.5);
+        let base = position.floor();
+        let fract = position - base;
+
+        let (x, y) = (base.x as i64, base.y as i64);
+
+        let top = self.texel(x, y).lerp(self.texel(x + 1, y), fract.x);
+        let bottom = self.texel(x, y + 1).lerp(self.texel(x + 1, y + 1), fract.x);
+
+        top.lerp(bottom, fract.y)
+    }
+
+    fn fill(&mut self, region: PixelRegion, color: [u8; 4]) {
+        for y in region.y_min..region.y_max {
+            for x in region.x_min..region.x_max {
+                let index = self.index(x, y);
+                self.pixels[index] = color;
+            }
+        }
+    }
+}
+
+/// A half-open range of pixels, in framebuffer coordinates.
+#[derive(Debug, Clone, Copy)]
+struct PixelRegion {
+    x_min: u32,
+    y_min: u32,
+    x_max: u32,
+    y_max: u32,
+}
+
+impl PixelRegion {
+    fn from_gl_rect(x: GLint, y: GLint, width: GLsizei, height: GLsizei) -> Self {
+        PixelRegion {
+            x_min: x.max(0) as u32,
+            y_min: y.max(0) as u32,
+            x_max: (x + width).max(0) as u32,
+            y_max: (y + height).max(0) as u32,
+        }
+    }
+
+    fn intersect(&self, other: &PixelRegion) -> Self {
+        PixelRegion {
+            x_min: self.x_min.max(other.x_min),
+            y_min: self.y_min.max(other.y_min),
+            x_max: self.x_max.min(other.x_max),
+            y_max: self.y_max.min(other.y_max),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AttributePointer {
+    buffer: GLuint,
+    size: GLsizei,
+    stride: GLsizei,
+    offset: GLsizei,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum UniformValue {
+    Float(f32),
+    Int(GLint),
+}
+
+struct Uniform {
+    program: GLuint,
+    name: String,
+    value: Option<UniformValue>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BlendState {
+    enabled: bool,
+    src_rgb: GLenum,
+    dst_rgb: GLenum,
+    src_alpha: GLenum,
+    dst_alpha: GLenum,
+}
+
+/// The inputs to the Renderer2D fragment shader at a single vertex.
+#[derive(Debug, Clone, Copy)]
+struct Vertex {
+    position: Vec2,
+    color: Vec4,
+    texture_coord: Vec2,
+    texture_mix: f32,
+    circle_mix: f32,
+}
+
+impl Vertex {
+    fn interpolate(vertices: &[Vertex; 3], weights: [f32; 3]) -> Self {
+        let [a, b, c] = vertices;
+        let [wa, wb, wc] = weights;
+
+        Vertex {
+            position: a.position * wa + b.position * wb + c.position * wc,
+            color: a.color * wa + b.color * wb + c.color * wc,
+            texture_coord: a.texture_coord * wa + b.texture_coord * wb + c.texture_coord * wc,
+            texture_mix: a.texture_mix * wa + b.texture_mix * wb + c.texture_mix * wc,
+            circle_mix: a.circle_mix * wa + b.circle_mix * wb + c.circle_mix * wc,
+        }
+    }
+}
+
+struct SoftwareState {
+    textures: HashMap<GLuint, Surface>,
+    buffers: HashMap<GLuint, Vec<f32>>,
+    framebuffers: HashMap<GLuint, Option<GLuint>>,
+    program_attributes: HashMap<GLuint, Vec<String>>,
+    uniforms: HashMap<GLuint, Uniform>,
+    unsupported_shaders: HashSet<GLuint>,
+    unsupported_programs: HashSet<GLuint>,
+
+    default_framebuffer: Surface,
+
+    bound_framebuffer: Option<GLuint>,
+    bound_array_buffer: Option<GLuint>,
+    bound_textures: [Option<GLuint>; SoftwareState::TEXTURE_UNITS],
+    active_texture_unit: usize,
+    current_program: Option<GLuint>,
+    attribute_pointers: HashMap<GLuint, AttributePointer>,
+
+    blend: BlendState,
+    scissor_enabled: bool,
+    scissor: PixelRegion,
+    viewport: (GLint, GLint, GLsizei, GLsizei),
+    clear_color: Vec4,
+    unpack_alignment: usize,
+}
+
+impl SoftwareState {
+    const TEXTURE_UNITS: usize = 8;
+
+    fn new() -> Self {
+        SoftwareState {
+            textures: HashMap::new(),
+            buffers: HashMap::new(),
+            framebuffers: HashMap::new(),
+            program_attributes: HashMap::new(),
+            uniforms: HashMap::new(),
+            unsupported_shaders: HashSet::new(),
+            unsupported_programs: HashSet::new(),
+            default_framebuffer: Surface::default(),
+            bound_framebuffer: None,
+            bound_array_buffer: None,
+            bound_textures: [None; SoftwareState::TEXTURE_UNITS],
+            active_texture_unit: 0,
+            current_program: None,
+            attribute_pointers: HashMap::new(),
+            blend: BlendState {
+                enabled: false,
+                src_rgb: GL_ONE,
+                dst_rgb: GL_ZERO,
+                src_alpha: GL_ONE,
+                dst_alpha: GL_ZERO,
+            },
+            scissor_enabled: false,
+            scissor: PixelRegion::from_gl_rect(0, 0, 0, 0),
+            viewport: (0, 0, 0, 0),
+            clear_color: Vec4::ZERO,
+            unpack_alignment: 4,
+        }
+    }
+
+    fn bound_texture(&self) -> Option<GLuint> {
+        self.bound_textures[self.active_texture_unit]
+    }
+
+    /// The texture attached to the bound framebuffer, or `None` if the
+    /// default framebuffer is bound.
+    fn target_texture(&self) -> Option<GLuint> {
+        self.bound_framebuffer
+            .and_then(|framebuffer| self.framebuffers.get(&framebuffer).copied().flatten())
+    }
+
+    /// Temporarily removes the current render target, so that textures can be
+    /// sampled while it is being written to.
+    fn with_target<R>(&mut self, action: impl FnOnce(&mut Self, &mut Surface) -> R) -> Option<R> {
+        match self.target_texture() {
+            None if self.bound_framebuffer.is_some() => {
+                log::warn!("Software renderer: bound framebuffer has no attachment");
+                None
+            }
+            None => {
+                let mut target = std::mem::take(&mut self.default_framebuffer);
+                let result = action(self, &mut target);
+                self.default_framebuffer = target;
+                Some(result)
+            }
+            Some(texture) => {
+                let mut target = self.textures.remove(&texture)?;
+                let result = action(self, &mut target);
+                self.textures.insert(texture, target);
+                Some(result)
+            }
+        }
+    }
+
+    fn uniform(&self, program: GLuint, name: &str) -> Option<UniformValue> {
+        self.uniforms
+            .values()
+            .find(|uniform| uniform.program == program && uniform.name == name)
+            .and_then(|uniform| uniform.value)
+    }
+
+    fn uniform_float(&self, program: GLuint, name: &str) -> f32 {
+        match self.uniform(program, name) {
+            Some(UniformValue::Float(value)) => value,
+            _ => 0.0,
+        }
+    }
+
+    fn uniform_int(&self, program: GLuint, name: &str) -> GLint {
+        match self.uniform(program, name) {
+            Some(UniformValue::Int(value)) => value,
+            _ => 0,
+        }
+    }
+
+    /// Reads the value of an attribute for a single vertex, padding missing
+    /// components as GL does.
+    fn read_attribute(&self, location: GLuint, vertex: usize) -> Vec4 {
+        let mut result = Vec4::new(0.0, 0.0, 0.0, 1.0);
+
+        let pointer = match self.attribute_pointers.get(&location) {
+            None => return result,
+            Some(pointer) => pointer,
+        };
+
+        let data = match self.buffers.get(&pointer.buffer) {
+            None => return result,
+            Some(data) => data,
+        };
+
+        let size = pointer.size as usize;
+        let stride = match pointer.stride {
+            0 => size,
+            stride => stride as usize / 4,
+        };
+        let start = pointer.offset as usize / 4 + vertex * stride;
+
+        for (component, value) in data.iter().skip(start).take(size.min(4)).enumerate() {
+            result[component] = *value;
+        }
+
+        result
+    }
+
+    fn draw_arrays(&mut self, mode: GLenum, first: GLint, count: GLsizei) {
+        if mode != GL_TRIANGLES {
+            log::warn!("Software renderer: unsupported draw mode {mode:#x}");
+            return;
+        }
+
+        let program = match self.current_program {
+            None => return,
+            Some(program) => program,
+        };
+
+        let attributes = match self.program_attributes.get(&program) {
+            None => return,
+            Some(attributes) => attributes,
+        };
+
+        let location = |name: &str| {
+            attributes
+                .iter()
+                .position(|attribute| attribute == name)
+                .map(|location| location as GLuint)
+        };
+
+        // Only the Renderer2D shaders can be interpreted
+        let (position, color, texture_coord, texture_mix, circle_mix) = match (
+            location("in_Position"),
+            location("in_Color"),
+            location("in_TextureCoord"),
+            location("in_TextureMix"),
+            location("in_CircleMix"),
+        ) {
+            (Some(a), Some(b), Some(c), Some(d), Some(e)) => (a, b, c, d, e),
+            _ => {
+                log::warn!("Software renderer: ignoring draw using unsupported shader");
+                return;
+            }
+        };
+
+        let scale = Vec2::new(
+            self.uniform_float(program, "in_ScaleX"),
+            self.uniform_float(program, "in_ScaleY"),
+        );
+
+        let texture_unit = self.uniform_int(program, "in_Texture") as usize;
+        let texture = self.bound_textures.get(texture_unit).copied().flatten();
+
+        let (vp_x, vp_y, vp_width, vp_height) = self.viewport;
+        let viewport_origin = Vec2::new(vp_x as f32, vp_y as f32);
+        let viewport_size = Vec2::new(vp_width as f32, vp_height as f32);
+
+        let vertices: Vec<Vertex> = (first.max(0) as usize..(first + count).max(0) as usize)
+            .map(|index| {
+                let input = self.read_attribute(position, index);
+
+                // Equivalent to the Renderer2D vertex shader, followed by the
+                // viewport transform
+                let clip = Vec2::new(input.x * scale.x - 1.0, input.y * scale.y + 1.0);
+
+                Vertex {
+                    position: viewport_origin + (clip + Vec2::ONE) * 0.5 * viewport_size,
+                    color: self.read_attribute(color, index),
+                    texture_coord: self
+                        .read_attribute(texture_coord, index)
+                        .truncate()
+                        .truncate(),
+                    texture_mix: self.read_attribute(texture_mix, index).x,
+                    circle_mix: self.read_attribute(circle_mix, index).x,
+                }
+            })
+            .collect();
+
+        let mut bounds = PixelRegion::from_gl_rect(vp_x, vp_y, vp_width, vp_height);
+
+        if self.scissor_enabled {
+            bounds = bounds.intersect(&self.scissor);
+        }
+
+        self.with_target(|state, target| {
+            let bounds = bounds.intersect(&PixelRegion::from_gl_rect(
+                0,
+                0,
+                target.size.x as GLsizei,
+                target.size.y as GLsizei,
+            ));
+
+            let texture = texture.and_then(|texture| state.textures.get(&texture));
+
+            for triangle in vertices.chunks_exact(3) {
+                state.rasterize_triangle(
+                    target,
+                    bounds,
+                    texture,
+                    &[triangle[0], triangle[1], triangle[2]],
+                );
+            }
+        });
+    }
+
+    fn rasterize_triangle(
+        &self,
+        target: &mut Surface,
+        bounds: PixelRegion,
+        texture: Option<&Surface>,
+        vertices: &[Vertex; 3],
+    ) {
+        fn edge(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+            (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+        }
+
+        // With counter-clockwise winding, this is the "top-left" fill rule
+        fn is_top_left(a: Vec2, b: Vec2) -> bool {
+            (a.y == b.y && b.x < a.x) || b.y < a.y
+        }
+
+        let mut vertices = *vertices;
+
+        let area = edge(
+            vertices[0].position,
+            vertices[1].position,
+            vertices[2].position,
+        );
+
+        if area == 0.0 {
+            return;
+        }
+
+        if area < 0.0 {
+            vertices.swap(1, 2);
+        }
+
+        let area = area.abs();
+        let [p0, p1, p2] = [
+            vertices[0].position,
+            vertices[1].position,
+            vertices[2].position,
+        ];
+
+        let min = p0.min(p1).min(p2).floor().max(Vec2::ZERO);
+        let max = p0.max(p1).max(p2).ceil().max(Vec2::ZERO);
+
+        let region = bounds.intersect(&PixelRegion {
+            x_min: min.x as u32,
+            y_min: min.y as u32,
+            x_max: max.x as u32,
+            y_max: max.y as u32,
+        });
+
+        let edges = [(p1, p2), (p2, p0), (p0, p1)];
+
+        for y in region.y_min..region.y_max {
+            for x in region.x_min..region.x_max {
+                let point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+
+                let mut weights = [0.0; 3];
+                let mut inside = true;
+
+                for (weight, (a, b)) in weights.iter_mut().zip(edges.iter()) {
+                    let value = edge(*a, *b, point);
+
+                    if value < 0.0 || (value == 0.0 && !is_top_left(*a, *b)) {
+                        inside = false;
+                        break;
+                    }
+
+                    *weight = value / area;
+                }
+
+                if !inside {
+                    continue;
+                }
+
+                let fragment = Vertex::interpolate(&vertices, weights);
+                let source = SoftwareState::shade(texture, &fragment);
+
+                let index = target.index(x, y);
+                target.pixels[index] = self.blend(source, target.pixels[index]);
+            }
+        }
+    }
+
+    /// Equivalent to the Renderer2D fragment shader.
+    fn shade(texture: Option<&Surface>, fragment: &Vertex) -> Vec4 {
+        let coord = fragment.texture_coord;
+
+        let texture_color = match texture {
+            None => Vec4::new(0.0, 0.0, 0.0, 1.0),
+            Some(texture) => texture.sample(coord),
+        };
+
+        let circle_alpha = if coord.length_squared() >= 1.0 {
+            0.0
+        } else {
+            1.0
+        };
+
+        let color = fragment.color
+            * (Vec4::splat(1.0 - fragment.texture_mix - fragment.circle_mix)
+                + texture_color * fragment.texture_mix
+                + Vec4::new(1.0, 1.0, 1.0, circle_alpha) * fragment.circle_mix);
+
+        color.clamp(Vec4::ZERO, Vec4::ONE)
+    }
+
+    fn blend(&self, source: Vec4, destination: [u8; 4]) -> [u8; 4] {
+        let result = if self.blend.enabled {
+            let [r, g, b, a] = destination;
+            let destination = Vec4::new(r as f32, g as f32, b as f32, a as f32) / 255.0;
+
+            let factor = |factor: GLenum| match factor {
+                GL_ZERO => 0.0,
+                GL_ONE => 1.0,
+                GL_SRC_ALPHA => source.w,
+                GL_ONE_MINUS_SRC_ALPHA => 1.0 - source.w,
+                GL_DST_ALPHA => destination.w,
+                GL_ONE_MINUS_DST_ALPHA => 1.0 - destination.w,
+                _ => {
+                    log::warn!("Software renderer: unsupported blend factor {factor:#x}");
+                    1.0
+                }
+            };
+
+            let rgb = source.truncate() * factor(self.blend.src_rgb)
+                + destination.truncate() * factor(self.blend.dst_rgb);
+
+            let alpha = source.w * factor(self.blend.src_alpha)
+                + destination.w * factor(self.blend.dst_alpha);
+
+            rgb.extend(alpha)
+        } else {
+            source
+        };
+
+        color_to_u8(result)
+    }
+
+    fn upload_pixels(
+        &self,
+        surface: &mut Surface,
+        offset: UVec2,
+        size: UVec2,
+        format: GLenum,
+        pixels: &[u8],
+    ) {
+        let bytes_per_pixel = match format {
+            GL_RED | GL_LUMINANCE => 1,
+            GL_LUMINANCE_ALPHA => 2,
+            GL_RGB => 3,
+            GL_RGBA => 4,
+            _ => {
+                log::warn!("Software renderer: unsupported texture format {format:#x}");
+                return;
+            }
+        };
+
+        let row_bytes = size.x as usize * bytes_per_pixel;
+        let alignment = self.unpack_alignment.max(1);
+        let row_stride = row_bytes.div_ceil(alignment) * alignment;
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let start = y as usize * row_stride + x as usize * bytes_per_pixel;
+
+                let pixel = match pixels.get(start..start + bytes_per_pixel) {
+                    None => return,
+                    Some(&[l]) if format == GL_LUMINANCE => [l, l, l, 255],
+                    Some(&[r]) => [r, 0, 0, 255],
+                    Some(&[l, a]) => [l, l, l, a],
+                    Some(&[r, g, b]) => [r, g, b, 255],
+                    Some(&[r, g, b, a]) => [r, g, b, a],
+                    Some(_) => unreachable!(),
+                };
+
+                let (x, y) = (x + offset.x, y + offset.y);
+
+                if x < surface.size.x && y < surface.size.y {
+                    let index = surface.index(x, y);
+                    surface.pixels[index] = pixel;
+                }
+            }
+        }
+    }
+}
+
+fn color_to_u8(color: Vec4) -> [u8; 4] {
+    let color = (color.clamp(Vec4::ZERO, Vec4::ONE) * 255.0).round();
+    [color.x as u8, color.y as u8, color.z as u8, color.w as u8]
+}
+
+/// A [GLBackend] which draws using a pure-Rust software rasterizer, into
+/// in-memory buffers. This doesn't require a GPU or display, and is useful
+/// for tests, or for generating images on a headless server.
+///
+/// Rather than compiling GLSL, the backend interprets the Speedy2D shaders
+/// directly. This covers everything drawn using [crate::Graphics2D],
+/// including images, text, circles, clipping, and
+/// [crate::Graphics2D::capture]. Any other shader fails to compile, so
+/// post-processing effects are disabled and frames are drawn unprocessed.
+///
+/// The default framebuffer is resized to match the viewport, so its size
+/// follows [crate::GLRenderer::set_viewport_size_pixels].
+///
+/// ```rust,no_run
+/// use std::rc::Rc;
+/// use speedy2d::color::Color;
+/// use speedy2d::glbackend::SoftwareGLBackend;
+/// use speedy2d::{GLRenderer, GLVersion};
+///
+/// let backend = Rc::new(SoftwareGLBackend::new());
+///
+/// let mut renderer =
+///     GLRenderer::new_with_gl_backend((640, 480), backend.clone(), GLVersion::OpenGL2_0)
+///         .unwrap();
+///
+/// renderer.draw_frame(|graphics| {
+///     graphics.clear_screen(Color::WHITE);
+///     graphics.draw_circle((100.0, 100.0), 75.0, Color::BLUE);
+/// });
+///
+/// let (size, pixels) = backend.framebuffer_rgba();
+/// ```
+pub struct SoftwareGLBackend {
+    state: RefCell<SoftwareState>,
+    next_handle: Cell<u32>,
+}
+
+impl SoftwareGLBackend {
+    /// Present in the source of every Renderer2D shader.
+    const R2D_SHADER_MARKER: &'static str = "CircleMix";
+
+    const UNSUPPORTED_SHADER_MESSAGE: &'static str =
+        "The software renderer only supports the Renderer2D shaders";
+
+    /// Creates a new software backend. The default framebuffer has a size of
+    /// zero until the renderer sets the viewport.
+    #[must_use]
+    pub fn new() -> Self {
+        SoftwareGLBackend {
+            state: RefCell::new(SoftwareState::new()),
+            next_handle: Cell::new(1),
+        }
+    }
+
+    /// Returns the size and contents of the default framebuffer (the
+    /// equivalent of the window). Pixels are in RGBA format, starting at the
+    /// top left.
+    #[must_use]
+    pub fn framebuffer_rgba(&self) -> (UVec2, Vec<u8>) {
+        let state = self.state.borrow();
+        let framebuffer = &state.default_framebuffer;
+
+        let data = framebuffer
+            .pixels
+            .chunks_exact(framebuffer.size.x.max(1) as usize)
+            .rev()
+            .flatten()
+            .flatten()
+            .copied()
+            .collect();
+
+        (framebuffer.size, data)
+    }
+
+    fn next_handle(&self) -> NonZeroU32 {
+        let handle = self.next_handle.get();
+        self.next_handle.set(handle + 1);
+        NonZeroU32::new(handle).unwrap()
+    }
+}
+
+impl Default for SoftwareGLBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GLBackend for SoftwareGLBackend {
+    unsafe fn gl_delete_program(&self, handle: GLTypeProgram) {
+        let mut state = self.state.borrow_mut();
+        state.program_attributes.remove(&handle.0.get());
+        state.unsupported_programs.remove(&handle.0.get());
+        state
+            .uniforms
+            .retain(|_, uniform| uniform.program != handle.0.get());
+    }
+
+    unsafe fn gl_delete_shader(&self, handle: GLTypeShader) {
+        self.state
+            .borrow_mut()
+            .unsupported_shaders
+            .remove(&handle.0.get());
+    }
+
+    unsafe fn gl_delete_buffer(&self, handle: GLTypeBuffer) {
+        self.state.borrow_mut().buffers.remove(&handle.0.get());
+    }
+
+    unsafe fn gl_delete_texture(&self, handle: GLTypeTexture) {
+        let mut state = self.state.borrow_mut();
+        state.textures.remove(&handle.0.get());
+
+        for binding in state.bound_textures.iter_mut() {
+            if *binding == Some(handle.0.get()) {
+                *binding = None;
+            }
+        }
+    }
+
+    unsafe fn gl_delete_framebuffer(&self, handle: GLTypeFramebuffer) {
+        let mut state = self.state.borrow_mut();
+        state.framebuffers.remove(&handle.0.get());
+
+        if state.bound_framebuffer == Some(handle.0.get()) {
+            state.bound_framebuffer = None;
+        }
+    }
+
+    unsafe fn gl_delete_vertex_array(&self, _handle: GLTypeVertexArray) {}
+
+    unsafe fn gl_active_texture(&self, unit: GLenum) {
+        let unit = (unit - GL_TEXTURE0) as usize;
+        self.state.borrow_mut().active_texture_unit = unit.min(SoftwareState::TEXTURE_UNITS - 1);
+    }
+
+    unsafe fn gl_bind_texture(&self, _target: GLenum, handle: Option<GLTypeTexture>) {
+        let mut state = self.state.borrow_mut();
+        let unit = state.active_texture_unit;
+        let handle = handle.map(|handle| handle.0.get());
+
+        if let Some(handle) = handle {
+            state.textures.entry(handle).or_default();
+        }
+
+        state.bound_textures[unit] = handle;
+    }
+
+    unsafe fn gl_enable(&self, cap: GLenum) {
+        let mut state = self.state.borrow_mut();
+        match cap {
+            GL_BLEND => state.blend.enabled = true,
+            GL_SCISSOR_TEST => state.scissor_enabled = true,
+            _ => {}
+        }
+    }
+
+    unsafe fn gl_disable(&self, cap: GLenum) {
+        let mut state = self.state.borrow_mut();
+        match cap {
+            GL_BLEND => state.blend.enabled = false,
+            GL_SCISSOR_TEST => state.scissor_enabled = false,
+            _ => {}
+        }
+    }
+
+    unsafe fn gl_blend_func(&self, sfactor: GLenum, dfactor: GLenum) {
+        self.gl_blend_func_separate(sfactor, dfactor, sfactor, dfactor)
+    }
+
+    unsafe fn gl_blend_func_separate(
+        &self,
+        sfactor: GLenum,
+        dfactor: GLenum,
+        sfactor_alpha: GLenum,
+        dfactor_alpha: GLenum,
+    ) {
+        let mut state = self.state.borrow_mut();
+        state.blend.src_rgb = sfactor;
+        state.blend.dst_rgb = dfactor;
+        state.blend.src_alpha = sfactor_alpha;
+        state.blend.dst_alpha = dfactor_alpha;
+    }
+
+    unsafe fn gl_use_program(&self, handle: GLTypeProgram) {
+        self.state.borrow_mut().current_program = Some(handle.0.get());
+    }
+
+    unsafe fn gl_enable_vertex_attrib_array(&self, _handle: GLuint) {}
+
+    unsafe fn gl_disable_vertex_attrib_array(&self, _handle: GLuint) {}
+
+    unsafe fn gl_uniform_1f(&self, handle: &GLTypeUniformLocation, value: f32) {
+        if let Some(uniform) = self.state.borrow_mut().uniforms.get_mut(&handle.0) {
+            uniform.value = Some(UniformValue::Float(value));
+        }
+    }
+
+    unsafe fn gl_uniform_1i(&self, handle: &GLTypeUniformLocation, value: GLint) {
+        if let Some(uniform) = self.state.borrow_mut().uniforms.get_mut(&handle.0) {
+            uniform.value = Some(UniformValue::Int(value));
+        }
+    }
+
+    unsafe fn gl_uniform_2f(&self, _handle: &GLTypeUniformLocation, _x: f32, _y: f32) {
+        // Only used by the post-processing shaders, which aren't supported
+    }
+
+    unsafe fn gl_attach_shader(&self, program: GLTypeProgram, shader: GLTypeShader) {
+        let mut state = self.state.borrow_mut();
+
+        if state.unsupported_shaders.contains(&shader.0.get()) {
+            state.unsupported_programs.insert(program.0.get());
+        }
+    }
+
+    unsafe fn gl_link_program(&self, _program: GLTypeProgram) {}
+
+    unsafe fn gl_shader_source(&self, handle: GLTypeShader, source: &str) {
+        // Only the Renderer2D shaders can be interpreted
+        if !source.contains(Self::R2D_SHADER_MARKER) {
+            self.state
+                .borrow_mut()
+                .unsupported_shaders
+                .insert(handle.0.get());
+        }
+    }
+
+    unsafe fn gl_compile_shader(&self, _handle: GLTypeShader) {}
+
+    unsafe fn gl_tex_parameter_i(&self, _target: GLenum, parameter: GLenum, value: GLint) {
+        let mut state = self.state.borrow_mut();
+
+        if parameter != GL_TEXTURE_MAG_FILTER {
+            return;
+        }
+
+        if let Some(texture) = state.bound_texture() {
+            if let Some(texture) = state.textures.get_mut(&texture) {
+                texture.linear_filtering = value == GL_LINEAR as GLint;
+            }
+        }
+    }
+
+    unsafe fn gl_bind_buffer(&self, target: GLenum, handle: GLTypeBuffer) {
+        if target == GL_ARRAY_BUFFER {
+            self.state.borrow_mut().bound_array_buffer = Some(handle.0.get());
+        }
+    }
+
+    unsafe fn gl_bind_framebuffer(&self, _target: GLenum, handle: Option<GLTypeFramebuffer>) {
+        self.state.borrow_mut().bound_framebuffer = handle.map(|handle| handle.0.get());
+    }
+
+    unsafe fn gl_bind_vertex_array(&self, _handle: Option<GLTypeVertexArray>) {
+        // Vertex attribute state is global in this backend. Speedy2D sets the
+        // attribute pointers each time it uploads vertex data, so vertex
+        // array objects don't need to be emulated.
+    }
+
+    unsafe fn gl_framebuffer_texture_2d(
+        &self,
+        _target: GLenum,
+        _attachment: GLenum,
+        _texture_target: GLenum,
+        texture: GLTypeTexture,
+        _level: GLint,
+    ) {
+        let mut state = self.state.borrow_mut();
+
+        if let Some(framebuffer) = state.bound_framebuffer {
+            state
+                .framebuffers
+                .insert(framebuffer, Some(texture.0.get()));
+        }
+    }
+
+    unsafe fn gl_check_framebuffer_status(&self, _target: GLenum) -> GLenum {
+        match self.state.borrow().target_texture() {
+            Some(_) => GL_FRAMEBUFFER_COMPLETE,
+            None => GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT,
+        }
+    }
+
+    unsafe fn gl_buffer_data(&self, target: GLenum, data: &[u8], _usage: GLenum) {
+        let mut state = self.state.borrow_mut();
+
+        if target != GL_ARRAY_BUFFER {
+            return;
+        }
+
+        if let Some(buffer) = state.bound_array_buffer {
+            let data = data
+                .chunks_exact(4)
+                .map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect();
+
+            state.buffers.insert(buffer, data);
+        }
+    }
+
+    unsafe fn gl_draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei) {
+        self.state.borrow_mut().draw_arrays(mode, first, count)
+    }
+
+    unsafe fn gl_clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.state.borrow_mut().clear_color = Vec4::new(r, g, b, a);
+    }
+
+    unsafe fn gl_clear(&self, mask: GLenum) {
+        if mask & GL_COLOR_BUFFER_BIT == 0 {
+            return;
+        }
+
+        let mut state = self.state.borrow_mut();
+
+        let color = color_to_u8(state.clear_color);
+        let scissor = state.scissor_enabled.then_some(state.scissor);
+
+        state.with_target(|_, target| {
+            let mut region =
+                PixelRegion::from_gl_rect(0, 0, target.size.x as GLsizei, target.size.y as GLsizei);
+
+            if let Some(scissor) = scissor {
+                region = region.intersect(&scissor);
+            }
+
+            target.fill(region, color);
+        });
+    }
+
+    unsafe fn gl_enable_debug_message_callback(&self) {}
+
+    unsafe fn gl_get_string(&self, parameter: GLenum) -> String {
+        match parameter {
+            GL_VERSION => String::from("2.0 Speedy2D software renderer"),
+            _ => String::new(),
+        }
+    }
+
+    unsafe fn gl_viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        let mut state = self.state.borrow_mut();
+
+        state.viewport = (x, y, width, height);
+
+        // The default framebuffer follows the size of the "window"
+        if state.bound_framebuffer.is_none() {
+            let size = UVec2::new((x + width).max(0) as u32, (y + height).max(0) as u32);
+
+            if state.default_framebuffer.size != size {
+                state.default_framebuffer = Surface::new(size);
+            }
+        }
+    }
+
+    unsafe fn gl_scissor(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.state.borrow_mut().scissor = PixelRegion::from_gl_rect(x, y, width, height);
+    }
+
+    unsafe fn gl_pixel_store_i(&self, param: GLenum, value: GLint) {
+        if param == GL_UNPACK_ALIGNMENT {
+            self.state.borrow_mut().unpack_alignment = value.max(1) as usize;
+        }
+    }
+
+    unsafe fn gl_vertex_attrib_pointer_f32(
+        &self,
+        index: GLuint,
+        size: GLsizei,
+        _data_type: GLenum,
+        _normalized: bool,
+        stride: GLsizei,
+        offset: GLsizei,
+    ) {
+        let mut state = self.state.borrow_mut();
+
+        if let Some(buffer) = state.bound_array_buffer {
+            state.attribute_pointers.insert(
+                index,
+                AttributePointer {
+                    buffer,
+                    size,
+                    stride,
+                    offset,
+                },
+            );
+        }
+    }
+
+    unsafe fn gl_tex_image_2d(
+        &self,
+        _target: GLenum,
+        _level: GLint,
+        _internal_format: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        _border: GLint,
+        format: GLenum,
+        _data_type: GLenum,
+        pixels: Option<&[u8]>,
+    ) {
+        let mut state = self.state.borrow_mut();
+
+        let texture = match state.bound_texture() {
+            None => return,
+            Some(texture) => texture,
+        };
+
+        let size = UVec2::new(width.max(0) as u32, height.max(0) as u32);
+
+        let mut surface = Surface::new(size);
+
+        if let Some(existing) = state.textures.get(&texture) {
+            surface.linear_filtering = existing.linear_filtering;
+        }
+
+        if let Some(pixels) = pixels {
+            state.upload_pixels(&mut surface, UVec2::ZERO, size, format, pixels);
+        }
+
+        state.textures.insert(texture, surface);
+    }
+
+    unsafe fn gl_tex_sub_image_2d(
+        &self,
+        _target: GLenum,
+        _level: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        _data_type: GLenum,
+        pixels: &[u8],
+    ) {
+        let mut state = self.state.borrow_mut();
+
+        let texture = match state.bound_texture() {
+            None => return,
+            Some(texture) => texture,
+        };
+
+        if let Some(mut surface) = state.textures.remove(&texture) {
+            state.upload_pixels(
+                &mut surface,
+                UVec2::new(x.max(0) as u32, y.max(0) as u32),
+                UVec2::new(width.max(0) as u32, height.max(0) as u32),
+                format,
+                pixels,
+            );
+
+            state.textures.insert(texture, surface);
+        }
+    }
+
+    unsafe fn gl_create_program(&self) -> Result<GLTypeProgram, BacktraceError<ErrorMessage>> {
+        Ok(glow::NativeProgram(self.next_handle()))
+    }
+
+    unsafe fn gl_create_shader(
+        &self,
+        _shader_type: GLenum,
+    ) -> Result<GLTypeShader, BacktraceError<ErrorMessage>> {
+        Ok(glow::NativeShader(self.next_handle()))
+    }
+
+    unsafe fn gl_gen_buffer(&self) -> Result<GLTypeBuffer, BacktraceError<ErrorMessage>> {
+        Ok(glow::NativeBuffer(self.next_handle()))
+    }
+
+    unsafe fn gl_gen_texture(&self) -> Result<GLTypeTexture, BacktraceError<ErrorMessage>> {
+        Ok(glow::NativeTexture(self.next_handle()))
+    }
+
+    unsafe fn gl_gen_framebuffer(&self) -> Result<GLTypeFramebuffer, BacktraceError<ErrorMessage>> {
+        let handle = self.next_handle();
+        self.state
+            .borrow_mut()
+            .framebuffers
+            .insert(handle.get(), None);
+        Ok(glow::NativeFramebuffer(handle))
+    }
+
+    unsafe fn gl_gen_vertex_array(
+        &self,
+    ) -> Result<GLTypeVertexArray, BacktraceError<ErrorMessage>> {
+        Ok(glow::NativeVertexArray(self.next_handle()))
+    }
+
+    unsafe fn gl_get_error(&self) -> GLenum {
+        GL_NO_ERROR
+    }
+
+    unsafe fn gl_get_attrib_location(&self, program: GLTypeProgram, name: &str) -> Option<GLuint> {
+        let mut state = self.state.borrow_mut();
+        let names = state.program_attributes.entry(program.0.get()).or_default();
+
+        let index = match names.iter().position(|existing| existing == name) {
+            Some(index) => index,
+            None => {
+                names.push(name.to_string());
+                names.len() - 1
+            }
+        };
+
+        Some(index as GLuint)
+    }
+
+    unsafe fn gl_get_uniform_location(
+        &self,
+        program: GLTypeProgram,
+        name: &str,
+    ) -> Option<GLTypeUniformLocation> {
+        let handle = self.next_handle().get();
+
+        self.state.borrow_mut().uniforms.insert(
+            handle,
+            Uniform {
+                program: program.0.get(),
+                name: name.to_string(),
+                value: None,
+            },
+        );
+
+        Some(glow::NativeUniformLocation(handle))
+    }
+
+    unsafe fn gl_get_program_link_status(&self, program: GLTypeProgram) -> bool {
+        !self
+            .state
+            .borrow()
+            .unsupported_programs
+            .contains(&program.0.get())
+    }
+
+    unsafe fn gl_get_shader_compile_status(&self, shader: GLTypeShader) -> bool {
+        !self
+            .state
+            .borrow()
+            .unsupported_shaders
+            .contains(&shader.0.get())
+    }
+
+    unsafe fn gl_get_program_info_log(
+        &self,
+        program: GLTypeProgram,
+    ) -> Result<String, BacktraceError<ErrorMessage>> {
+        if self.gl_get_program_link_status(program) {
+            Ok(String::new())
+        } else {
+            Ok(Self::UNSUPPORTED_SHADER_MESSAGE.to_string())
+        }
+    }
+
+    unsafe fn gl_get_shader_info_log(
+        &self,
+        shader: GLTypeShader,
+    ) -> Result<String, BacktraceError<ErrorMessage>> {
+        if self.gl_get_shader_compile_status(shader) {
+            Ok(String::new())
+        } else {
+            Ok(Self::UNSUPPORTED_SHADER_MESSAGE.to_string())
+        }
+    }
+
+    unsafe fn gl_read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        _data_type: GLenum,
+        data: &mut [MaybeUninit<u8>],
+    ) {
+        let bytes_per_pixel = match format {
+            GL_RGB => 3,
+            _ => 4,
+        };
+
+        let mut output = data.iter_mut();
+
+        self.state.borrow_mut().with_target(|_, source| {
+            for row in y..(y + height) {
+                for column in x..(x + width) {
+                    let pixel = if row >= 0
+                        && column >= 0
+                        && (column as u32) < source.size.x
+                        && (row as u32) < source.size.y
+                    {
+                        source.pixels[source.index(column as u32, row as u32)]
+                    } else {
+                        [0; 4]
+                    };
+
+                    for byte in &pixel[..bytes_per_pixel] {
+                        if let Some(output) = output.next() {
+                            output.write(*byte);
+                        }
+                    }
+                }
+            }
+        });
+
+        // Ensure the whole buffer is initialized, even if nothing was read
+        for output in output {
+            output.write(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use glam::{IVec2, UVec2, Vec2};
+    use glam_rect::{IRect, Rect};
+
+    use crate::color::Color;
+    use crate::glbackend::{create_test_renderer, SoftwareGLBackend};
+    use crate::image::ImageDataType;
+    use crate::postprocess::PostProcessPass;
+    use crate::{GLRenderer, GLVersion};
+
+    fn create_renderer(width: u32, height: u32) -> (Rc<SoftwareGLBackend>, GLRenderer) {
+        create_test_renderer(
+            SoftwareGLBackend::new(),
+            (width, height),
+            GLVersion::OpenGL2_0,
+        )
+    }
+
+    fn pixel_at(backend: &SoftwareGLBackend, x: u32, y: u32) -> [u8; 4] {
+        let (size, data) = backend.framebuffer_rgba();
+        let start = ((y * size.x + x) * 4) as usize;
+        data[start..start + 4].try_into().unwrap()
+    }
+
+    /// A pixel matches the reference image if no channel differs by more
+    /// than this.
+    const REFERENCE_CHANNEL_TOLERANCE: u8 = 8;
+
+    /// The proportion of pixels which may fail to match, to allow for edges
+    /// which the GPU rasterizes slightly differently.
+    const REFERENCE_MISMATCH_TOLERANCE: f32 = 0.01;
+
+    /// Draws one of the scenes from `test/main.rs`, and compares a capture of
+    /// it against the image generated by the GL implementation.
+    fn check_reference_image(
+        name: &str,
+        width: u32,
+        height: u32,
+        draw: impl FnOnce(&mut GLRenderer),
+    ) {
+        let (_backend, mut renderer) = create_renderer(width, height);
+
+        draw(&mut renderer);
+
+        let actual = renderer.draw_frame(|graphics| graphics.capture(ImageDataType::RGBA));
+
+        let path = format!(
+            "{}/test/assets/expected_images/test_{name}.png",
+            env!("CARGO_MANIFEST_DIR")
+        );
+
+        let expected = ::image::open(&path).unwrap().into_rgba8();
+
+        assert_eq!(actual.size(), UVec2::new(width, height), "{name}");
+        assert_eq!(expected.dimensions(), (width, height), "{name}");
+
+        let mismatched = actual
+            .data()
+            .chunks_exact(4)
+            .zip(expected.as_raw().chunks_exact(4))
+            .filter(|(actual, expected)| {
+                actual
+                    .iter()
+                    .zip(expected.iter())
+                    .any(|(a, b)| a.abs_diff(*b) > REFERENCE_CHANNEL_TOLERANCE)
+            })
+            .count();
+
+        let allowed = ((width * height) as f32 * REFERENCE_MISMATCH_TOLERANCE) as usize;
+
+        assert!(
+            mismatched <= allowed,
+            "{name}: {mismatched} pixels differ from the reference image (allowed {allowed})"
+        );
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let (backend, mut renderer) = create_renderer(40, 30);
+
+        renderer.draw_frame(|graphics| {
+            graphics.clear_screen(Color::WHITE);
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(10.0, 5.0), Vec2::new(20.0, 15.0)),
+                Color::RED,
+            );
+        });
+
+        assert_eq!(backend.framebuffer_rgba().0, UVec2::new(40, 30));
+
+        assert_eq!(pixel_at(&backend, 10, 5), [255, 0, 0, 255]);
+        assert_eq!(pixel_at(&backend, 19, 14), [255, 0, 0, 255]);
+        assert_eq!(pixel_at(&backend, 9, 5), [255, 255, 255, 255]);
+        assert_eq!(pixel_at(&backend, 20, 14), [255, 255, 255, 255]);
+        assert_eq!(pixel_at(&backend, 19, 15), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_alpha_blending() {
+        let (backend, mut renderer) = create_renderer(10, 10);
+
+        renderer.draw_frame(|graphics| {
+            graphics.clear_screen(Color::BLACK);
+            graphics.draw_rectangle(
+                Rect::new(Vec2::ZERO, Vec2::new(10.0, 10.0)),
+                Color::from_rgba(1.0, 1.0, 1.0, 0.5),
+            );
+        });
+
+        assert_eq!(pixel_at(&backend, 5, 5), [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn test_circle() {
+        let (backend, mut renderer) = create_renderer(20, 20);
+
+        renderer.draw_frame(|graphics| {
+            graphics.clear_screen(Color::WHITE);
+            graphics.draw_circle((10.0, 10.0), 8.0, Color::BLUE);
+        });
+
+        assert_eq!(pixel_at(&backend, 10, 10), [0, 0, 255, 255]);
+        assert_eq!(pixel_at(&backend, 10, 3), [0, 0, 255, 255]);
+        assert_eq!(pixel_at(&backend, 3, 3), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_clip() {
+        let (backend, mut renderer) = create_renderer(20, 20);
+
+        renderer.draw_frame(|graphics| {
+            graphics.clear_screen(Color::WHITE);
+            graphics.set_clip(Some(IRect::new(IVec2::new(5, 5), IVec2::new(10, 10))));
+            graphics.draw_rectangle(Rect::new(Vec2::ZERO, Vec2::new(20.0, 20.0)), Color::GREEN);
+        });
+
+        assert_eq!(pixel_at(&backend, 5, 5), [0, 255, 0, 255]);
+        assert_eq!(pixel_at(&backend, 9, 9), [0, 255, 0, 255]);
+        assert_eq!(pixel_at(&backend, 4, 5), [255, 255, 255, 255]);
+        assert_eq!(pixel_at(&backend, 10, 9), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_unsupported_shader_leaves_frame_unprocessed() {
+        let (backend, mut renderer) = create_renderer(20, 20);
+
+        renderer.set_post_processing(vec![PostProcessPass::Vignette {
+            strength: 1.0,
+            radius: 0.0,
+        }]);
+
+        for _ in 0..2 {
+            renderer.draw_frame(|graphics| {
+                graphics.clear_screen(Color::WHITE);
+                graphics.draw_rectangle(Rect::new(Vec2::ZERO, Vec2::new(10.0, 10.0)), Color::RED);
+            });
+
+            assert_eq!(pixel_at(&backend, 0, 0), [255, 0, 0, 255]);
+            assert_eq!(pixel_at(&backend, 19, 19), [255, 255, 255, 255]);
+        }
+    }
+
+    #[test]
+    fn test_reference_basic_rectangles() {
+        check_reference_image("basic_rectangles", 50, 50, |renderer| {
+            renderer.draw_frame(|graphics| {
+                graphics.clear_screen(Color::BLUE);
+                graphics.draw_rectangle(
+                    Rect::from_tuples((10.0, 20.0), (30.0, 40.0)),
+                    Color::MAGENTA,
+                );
+                graphics
+                    .draw_rectangle(Rect::from_tuples((15.0, 30.0), (49.0, 48.0)), Color::GREEN);
+            });
+        });
+    }
+
+    #[test]
+    fn test_reference_basic_circles() {
+        check_reference_image("basic_circles", 50, 50, |renderer| {
+            renderer.draw_frame(|graphics| {
+                graphics.clear_screen(Color::WHITE);
+                graphics.draw_circle((20.0, 20.0), 10.0, Color::RED);
+                graphics.draw_circle((40.0, 40.0), 5.0, Color::BLUE);
+            });
+        });
+    }
+
+    #[test]
+    fn test_reference_clip_area() {
+        check_reference_image("clip_area", 100, 100, |renderer| {
+            renderer.draw_frame(|graphics| {
+                graphics.clear_screen(Color::LIGHT_GRAY);
+                graphics.set_clip(Some(IRect::from_tuples((10, 10), (30, 20))));
+                graphics.draw_rectangle(Rect::from_tuples((0.0, 0.0), (20.0, 40.0)), Color::RED);
+                graphics.draw_rectangle(Rect::from_tuples((20.0, 0.0), (40.0, 40.0)), Color::BLUE);
+            });
+        });
+    }
+
+    #[test]
+    fn test_reference_half_circle() {
+        check_reference_image("half_circle", 300, 300, |renderer| {
+            renderer.draw_frame(|graphics| {
+                graphics.clear_screen(Color::WHITE);
+                graphics.draw_circle_section_triangular_three_color(
+                    [
+                        Vec2::new(100.0, 100.0),
+                        Vec2::new(200.0, 100.0),
+                        Vec2::new(200.0, 200.0),
+                    ],
+                    [Color::MAGENTA, Color::MAGENTA, Color::MAGENTA],
+                    [
+                        Vec2::new(-1.0, -1.0),
+                        Vec2::new(1.0, -1.0),
+                        Vec2::new(1.0, 1.0),
+                    ],
+                );
+            });
+        });
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn test_reference_clip_area_text() {
+        use crate::font::{Font, TextLayout, TextOptions};
+
+        let font = Font::new(include_bytes!("../assets/fonts/NotoSans-Regular.ttf")).unwrap();
+        let text = font.layout_text("Hello World", 100.0, TextOptions::new());
+
+        check_reference_image("clip_area_2", 400, 150, |renderer| {
+            renderer.draw_frame(|graphics| {
+                graphics.clear_screen(Color::WHITE);
+                graphics.set_clip(Some(IRect::from_tuples((25, 25), (250, 75))));
+                graphics.clear_screen(Color::GREEN);
+                graphics.draw_text(Vec2::new(0.0, 0.0), Color::BLACK, &text);
+            });
+        });
+    }
+}
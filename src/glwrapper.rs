@@ -14,7 +14,7 @@
  *  limitations under the License.
  */
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::fmt::{Debug, Formatter};
@@ -22,13 +22,14 @@ use std::hash::{Hash, Hasher};
 use std::num::TryFromIntError;
 use std::ptr;
 use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::color::Color;
 use crate::error::{BacktraceError, Context, ErrorMessage};
 use crate::glbackend::constants::*;
 use crate::glbackend::types::{
-    GLTypeBuffer, GLTypeProgram, GLTypeShader, GLTypeTexture, GLTypeUniformLocation, GLenum, GLint,
-    GLuint,
+    GLTypeBuffer, GLTypeFramebuffer, GLTypeProgram, GLTypeShader, GLTypeTexture,
+    GLTypeUniformLocation, GLenum, GLint, GLuint,
 };
 use crate::glbackend::GLBackend;
 
@@ -37,11 +38,32 @@ use crate::{ImageDataType, RawBitmapData};
 
 use glam::UVec2;
 
+/// The version of GL (and therefore GLSL) which Speedy2D targets.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
 #[allow(dead_code)]
 pub enum GLVersion {
+    /// OpenGL 2.0, using GLSL version 110.
     OpenGL2_0,
+    /// OpenGL 3.3 core profile, using GLSL version 330 core. Vertex array
+    /// objects are used, as required by the core profile.
+    OpenGL3_3Core,
+    /// WebGL 2.0, using GLSL version 300 es.
     WebGL2_0,
+    /// OpenGL ES 2.0, using GLSL version 100.
+    OpenGLES2_0,
+    /// WebGL 1.0, using GLSL version 100.
+    WebGL1_0,
+}
+
+impl GLVersion {
+    /// Returns true for the versions limited to the OpenGL ES 2.0 feature
+    /// set. These don't support sized internal texture formats, or single
+    /// channel red textures.
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_gles2_or_webgl1(self) -> bool {
+        matches!(self, GLVersion::OpenGLES2_0 | GLVersion::WebGL1_0)
+    }
 }
 
 impl From<TryFromIntError> for BacktraceError<ErrorMessage> {
@@ -68,6 +90,8 @@ enum GLHandleType {
     Shader,
     Buffer,
     Texture,
+    Framebuffer,
+    VertexArray,
 }
 
 trait GLHandleId: Debug + Hash + PartialEq + Eq {
@@ -95,10 +119,26 @@ struct GLHandleTypeTexture {
     handle: GLTypeTexture,
 }
 
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct GLHandleTypeFramebuffer {
+    handle: GLTypeFramebuffer,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct GLHandleTypeVertexArray {
+    handle: GLTypeVertexArray,
+}
+
 struct GLHandle<HandleType: GLHandleId> {
     context: Weak<RefCell<GLContextManagerState>>,
     handle: HandleType,
     handle_type: GLHandleType,
+    // The context generation in which the handle was created. Handles from
+    // before a context loss no longer refer to anything.
+    generation: u64,
+    // The share group of the context in which the handle was created. The
+    // handle means nothing to contexts outside this group.
+    share_group: Rc<Cell<u64>>,
 }
 
 impl<HandleType: GLHandleId> Debug for GLHandle<HandleType> {
@@ -151,6 +191,8 @@ impl<HandleType: GLHandleId> GLHandle<HandleType> {
             GLHandleType::Shader => gl_clear_and_log_old_error(context),
             GLHandleType::Buffer => {}
             GLHandleType::Texture => {}
+            GLHandleType::Framebuffer => {}
+            GLHandleType::VertexArray => {}
         }
 
         let handle = handle_creator().context("Handle creation failed")?;
@@ -160,12 +202,16 @@ impl<HandleType: GLHandleId> GLHandle<HandleType> {
             GLHandleType::Shader => gl_check_error_always(context)?,
             GLHandleType::Buffer => {}
             GLHandleType::Texture => {}
+            GLHandleType::Framebuffer => {}
+            GLHandleType::VertexArray => {}
         }
 
         Ok(GLHandle {
             context: Rc::downgrade(&context.state),
             handle,
             handle_type,
+            generation: context.generation(),
+            share_group: RefCell::borrow(&context.state).share_group.clone(),
         })
     }
 
@@ -179,7 +225,14 @@ impl<HandleType: GLHandleId> GLHandle<HandleType> {
 impl<HandleType: GLHandleId> Drop for GLHandle<HandleType> {
     fn drop(&mut self) {
         if let Some(context) = self.obtain_context_if_valid() {
-            self.handle.delete(&context);
+            // After a context loss, the ID may have been reused, and if a
+            // context from another share group is current, the ID would
+            // refer to one of its objects instead
+            if context.generation() == self.generation
+                && is_share_group_current(self.share_group.get())
+            {
+                self.handle.delete(&context);
+            }
         }
     }
 }
@@ -216,10 +269,28 @@ impl GLHandleId for GLHandleTypeTexture {
     }
 }
 
+impl GLHandleId for GLHandleTypeFramebuffer {
+    type HandleRawType = GLTypeFramebuffer;
+
+    fn delete(&self, context: &GLContextManager) {
+        context.with_gl_backend(|backend| unsafe { backend.gl_delete_framebuffer(self.handle) });
+    }
+}
+
+impl GLHandleId for GLHandleTypeVertexArray {
+    type HandleRawType = GLTypeVertexArray;
+
+    fn delete(&self, context: &GLContextManager) {
+        context.with_gl_backend(|backend| unsafe { backend.gl_delete_vertex_array(self.handle) });
+    }
+}
+
 #[derive(Debug)]
 pub struct GLProgram {
     handle: GLHandle<GLHandleTypeProgram>,
     attribute_handles: HashMap<&'static str, GLAttributeHandle>,
+    // Only present for GL 3.3 core, where a vertex array object is required
+    vertex_array: Option<GLHandle<GLHandleTypeVertexArray>>,
 }
 
 impl Hash for GLProgram {
@@ -252,6 +323,7 @@ impl GLProgram {
                     })
                 })?,
                 attribute_handles: HashMap::new(),
+                vertex_array: None,
             })
         })
     }
@@ -309,6 +381,16 @@ impl GLProgram {
             );
         }
 
+        if context.version() == GLVersion::OpenGL3_3Core {
+            program.vertex_array = Some(context.with_gl_backend(|backend| {
+                GLHandle::wrap(context, GLHandleType::VertexArray, || unsafe {
+                    Ok(GLHandleTypeVertexArray {
+                        handle: backend.gl_gen_vertex_array()?,
+                    })
+                })
+            })?);
+        }
+
         Ok(program)
     }
 
@@ -318,6 +400,12 @@ impl GLProgram {
                 backend.gl_use_program(self.get_handle());
             }
 
+            if let Some(vertex_array) = &self.vertex_array {
+                unsafe {
+                    backend.gl_bind_vertex_array(Some(vertex_array.handle.handle));
+                }
+            }
+
             for attribute in self.attribute_handles.values() {
                 unsafe {
                     backend.gl_enable_vertex_attrib_array(attribute.handle);
@@ -328,6 +416,14 @@ impl GLProgram {
 
     fn disable(&self, context: &GLContextManager) {
         context.with_gl_backend(|backend| {
+            if self.vertex_array.is_some() {
+                // The enabled attributes are part of the vertex array state
+                unsafe {
+                    backend.gl_bind_vertex_array(None);
+                }
+                return;
+            }
+
             for attribute in self.attribute_handles.values() {
                 unsafe {
                     backend.gl_disable_vertex_attrib_array(attribute.handle);
@@ -469,6 +565,10 @@ impl GLUniformHandle {
     pub fn set_value_int(&self, context: &GLContextManager, value: i32) {
         context.with_gl_backend(|backend| unsafe { backend.gl_uniform_1i(&self.handle, value) })
     }
+
+    pub fn set_value_vec2(&self, context: &GLContextManager, x: f32, y: f32) {
+        context.with_gl_backend(|backend| unsafe { backend.gl_uniform_2f(&self.handle, x, y) })
+    }
 }
 
 pub enum GLBufferTarget {
@@ -558,6 +658,7 @@ pub enum GLTextureSmoothing {
 pub enum GLTextureImageFormatU8 {
     #[allow(dead_code)]
     Red,
+    LuminanceAlpha,
     RGB,
     RGBA,
 }
@@ -573,17 +674,26 @@ impl From<ImageDataType> for GLTextureImageFormatU8 {
 }
 
 impl GLTextureImageFormatU8 {
-    fn get_internal_format(&self) -> GLenum {
+    fn get_internal_format(&self, version: GLVersion) -> GLenum {
+        if version.is_gles2_or_webgl1() {
+            // Sized internal formats aren't supported, and the internal
+            // format must match the format of the data
+            return self.get_format(version);
+        }
+
         match self {
             GLTextureImageFormatU8::Red => GL_R8,
+            GLTextureImageFormatU8::LuminanceAlpha => GL_LUMINANCE_ALPHA,
             GLTextureImageFormatU8::RGB => GL_RGB8,
             GLTextureImageFormatU8::RGBA => GL_RGBA8,
         }
     }
 
-    fn get_format(&self) -> GLenum {
+    fn get_format(&self, version: GLVersion) -> GLenum {
         match self {
+            GLTextureImageFormatU8::Red if version.is_gles2_or_webgl1() => GL_LUMINANCE,
             GLTextureImageFormatU8::Red => GL_RED,
+            GLTextureImageFormatU8::LuminanceAlpha => GL_LUMINANCE_ALPHA,
             GLTextureImageFormatU8::RGB => GL_RGB,
             GLTextureImageFormatU8::RGBA => GL_RGBA,
         }
@@ -592,38 +702,152 @@ impl GLTextureImageFormatU8 {
     fn get_bytes_per_pixel(&self) -> usize {
         match self {
             GLTextureImageFormatU8::Red => 1,
+            GLTextureImageFormatU8::LuminanceAlpha => 2,
             GLTextureImageFormatU8::RGB => 3,
             GLTextureImageFormatU8::RGBA => 4,
         }
     }
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+/// A copy of the data most recently uploaded to a texture, kept so that the
+/// texture can be recreated after a context loss.
+#[derive(Debug)]
+#[cfg_attr(not(feature = "image-loading"), allow(dead_code))]
+struct GLTextureRetainedData {
+    format: GLTextureImageFormatU8,
+    smoothing: GLTextureSmoothing,
+    size: UVec2,
+    data: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct GLTextureInner {
+    handle: RefCell<GLHandle<GLHandleTypeTexture>>,
+    retained_data: RefCell<Option<GLTextureRetainedData>>,
+}
+
+#[derive(Clone, Debug)]
 pub struct GLTexture {
-    handle: Rc<GLHandle<GLHandleTypeTexture>>,
+    inner: Rc<GLTextureInner>,
 }
 
+impl Hash for GLTexture {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.inner).hash(state);
+    }
+}
+
+impl PartialEq for GLTexture {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for GLTexture {}
+
 impl GLHandleOwner<GLHandleTypeTexture> for GLTexture {
     fn get_handle(&self) -> <GLHandleTypeTexture as GLHandleId>::HandleRawType {
-        self.handle.handle.handle
+        self.inner.handle.borrow().handle.handle
     }
 }
 
 impl GLTexture {
     fn new(context: &GLContextManager) -> Result<Self, BacktraceError<ErrorMessage>> {
-        let handle = GLHandle::wrap(context, GLHandleType::Texture, || {
+        Ok(GLTexture {
+            inner: Rc::new(GLTextureInner {
+                handle: RefCell::new(GLTexture::new_handle(context)?),
+                retained_data: RefCell::new(None),
+            }),
+        })
+    }
+
+    fn new_handle(
+        context: &GLContextManager,
+    ) -> Result<GLHandle<GLHandleTypeTexture>, BacktraceError<ErrorMessage>> {
+        GLHandle::wrap(context, GLHandleType::Texture, || {
             context.with_gl_backend(|backend| unsafe {
                 Ok(GLHandleTypeTexture {
                     handle: backend.gl_gen_texture()?,
                 })
             })
-        })?;
-
-        Ok(GLTexture {
-            handle: Rc::new(handle),
         })
     }
 
+    /// Returns true if the texture was created before the most recent
+    /// context loss, and so no longer exists.
+    #[must_use]
+    fn is_lost(&self, context: &GLContextManager) -> bool {
+        self.inner.handle.borrow().generation != context.generation()
+    }
+
+    #[inline]
+    #[must_use]
+    fn share_group(&self) -> u64 {
+        self.inner.handle.borrow().share_group.get()
+    }
+
+    /// Uploads the data in the same way as [GLTexture::set_image_data], and
+    /// also keeps a copy of it, so that the texture is recreated
+    /// automatically by [GLContextManager::restore] after a context loss.
+    #[cfg(feature = "image-loading")]
+    pub fn set_image_data_retained(
+        &self,
+        context: &GLContextManager,
+        format: GLTextureImageFormatU8,
+        smoothing: GLTextureSmoothing,
+        size: &UVec2,
+        data: &[u8],
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        self.set_image_data(context, format.clone(), smoothing.clone(), size, data)?;
+
+        let previous = self
+            .inner
+            .retained_data
+            .replace(Some(GLTextureRetainedData {
+                format,
+                smoothing,
+                size: *size,
+                data: data.to_vec(),
+            }));
+
+        if previous.is_none() {
+            let mut state = RefCell::borrow_mut(&context.state);
+            let retained_textures = &mut state.retained_textures;
+
+            // Remove dropped textures before the list would need to grow
+            if retained_textures.len() == retained_textures.capacity() {
+                retained_textures.retain(|inner| inner.strong_count() > 0);
+            }
+
+            retained_textures.push(Rc::downgrade(&self.inner));
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the handle with a newly created texture, and uploads the
+    /// retained data into it.
+    fn restore(&self, context: &GLContextManager) -> Result<(), BacktraceError<ErrorMessage>> {
+        let handle = GLTexture::new_handle(context)?;
+
+        // The old handle isn't deleted, as its generation is out of date
+        drop(self.inner.handle.replace(handle));
+
+        let retained_data = self.inner.retained_data.borrow();
+
+        if let Some(retained) = retained_data.as_ref() {
+            self.set_image_data(
+                context,
+                retained.format.clone(),
+                retained.smoothing.clone(),
+                &retained.size,
+                &retained.data,
+            )?;
+        }
+
+        Ok(())
+    }
+
     pub fn set_image_data(
         &self,
         context: &GLContextManager,
@@ -667,13 +891,13 @@ impl GLTexture {
                 GL_TEXTURE_2D,
                 0,
                 format
-                    .get_internal_format()
+                    .get_internal_format(context.version())
                     .try_into()
                     .context("Failed to cast internal format")?,
                 size.x.try_into()?,
                 size.y.try_into()?,
                 0,
-                format.get_format(),
+                format.get_format(context.version()),
                 GL_UNSIGNED_BYTE,
                 Some(data),
             );
@@ -683,6 +907,71 @@ impl GLTexture {
     }
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GLFramebuffer {
+    handle: Rc<GLHandle<GLHandleTypeFramebuffer>>,
+    texture: GLTexture,
+}
+
+impl GLHandleOwner<GLHandleTypeFramebuffer> for GLFramebuffer {
+    fn get_handle(&self) -> <GLHandleTypeFramebuffer as GLHandleId>::HandleRawType {
+        self.handle.handle.handle
+    }
+}
+
+impl GLFramebuffer {
+    fn new(
+        context: &GLContextManager,
+        texture: &GLTexture,
+    ) -> Result<Self, BacktraceError<ErrorMessage>> {
+        let handle = GLHandle::wrap(context, GLHandleType::Framebuffer, || {
+            context.with_gl_backend(|backend| unsafe {
+                Ok(GLHandleTypeFramebuffer {
+                    handle: backend.gl_gen_framebuffer()?,
+                })
+            })
+        })?;
+
+        let framebuffer = GLFramebuffer {
+            handle: Rc::new(handle),
+            texture: texture.clone(),
+        };
+
+        let previous_framebuffer = RefCell::borrow(&context.state).active_framebuffer.clone();
+
+        context.bind_framebuffer(Some(&framebuffer));
+
+        let status = context.with_gl_backend(|backend| unsafe {
+            backend.gl_framebuffer_texture_2d(
+                GL_FRAMEBUFFER,
+                GL_COLOR_ATTACHMENT0,
+                GL_TEXTURE_2D,
+                texture.get_handle(),
+                0,
+            );
+
+            backend.gl_check_framebuffer_status(GL_FRAMEBUFFER)
+        });
+
+        context.bind_framebuffer(previous_framebuffer.as_ref());
+
+        if status != GL_FRAMEBUFFER_COMPLETE {
+            return Err(ErrorMessage::msg(format!(
+                "Framebuffer incomplete (status {status:#x})"
+            )));
+        }
+
+        Ok(framebuffer)
+    }
+
+    /// The texture which this framebuffer renders into.
+    #[inline]
+    #[must_use]
+    pub fn texture(&self) -> &GLTexture {
+        &self.texture
+    }
+}
+
 #[must_use]
 fn obtain_context_if_valid(state: &RefCell<GLContextManagerState>) -> Option<GLContextManager> {
     let state = state.borrow_mut();
@@ -707,15 +996,54 @@ fn obtain_context_from_weak_if_valid(
     }
 }
 
+static NEXT_SHARE_GROUP: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// The share group of the context which is current on this thread, if
+    /// known. See [GLContextManager::mark_current].
+    static CURRENT_SHARE_GROUP: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Returns a share group which no existing context belongs to.
+#[must_use]
+fn new_share_group() -> u64 {
+    NEXT_SHARE_GROUP.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Returns true if objects from the specified share group can be used by the
+/// current context. If the current context isn't known, this is assumed.
+#[must_use]
+fn is_share_group_current(share_group: u64) -> bool {
+    CURRENT_SHARE_GROUP.with(|current| match current.get() {
+        None => true,
+        Some(current) => current == share_group,
+    })
+}
+
+/// Counts the GL state changes which weren't avoided by the state cache.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GLStateChangeCounts {
+    pub texture_binds: u32,
+    pub program_changes: u32,
+    pub blend_mode_changes: u32,
+}
+
 struct GLContextManagerState {
     is_valid: bool,
     active_texture: Option<GLTexture>,
     active_program: Option<Rc<GLProgram>>,
     active_blend_mode: Option<GLBlendEnabled>,
+    active_framebuffer: Option<GLFramebuffer>,
     viewport_size: Option<UVec2>,
     scissor_enabled: bool,
+    state_change_counts: GLStateChangeCounts,
     gl_backend: Rc<dyn GLBackend + 'static>,
     gl_version: GLVersion,
+    generation: u64,
+    // Shared with every handle created by the context, so that they follow it
+    // if it joins another share group
+    share_group: Rc<Cell<u64>>,
+    retained_textures: Vec<Weak<GLTextureInner>>,
     weak_ref_to_self: Weak<RefCell<GLContextManagerState>>,
 }
 
@@ -741,10 +1069,15 @@ impl GLContextManager {
                 active_texture: None,
                 active_program: None,
                 active_blend_mode: None,
+                active_framebuffer: None,
                 viewport_size: None,
                 scissor_enabled: false,
+                state_change_counts: GLStateChangeCounts::default(),
                 gl_backend,
                 gl_version,
+                generation: 0,
+                share_group: Rc::new(Cell::new(new_share_group())),
+                retained_textures: Vec::new(),
                 weak_ref_to_self: Weak::new(),
             })),
         };
@@ -761,6 +1094,97 @@ impl GLContextManager {
         RefCell::borrow_mut(&self.state).is_valid = false;
     }
 
+    /// Replaces the backend after a context loss, for cases where the old
+    /// context can't be restored and a new one was created instead. This
+    /// must be followed by a call to [GLContextManager::restore].
+    pub fn replace_gl_backend(&self, gl_backend: Rc<dyn GLBackend>, gl_version: GLVersion) {
+        let mut state = RefCell::borrow_mut(&self.state);
+        state.gl_backend = gl_backend;
+        state.gl_version = gl_version;
+
+        // The new context doesn't share objects with any other. Handles from
+        // the old context keep their share group, but are lost anyway.
+        state.share_group = Rc::new(Cell::new(new_share_group()));
+    }
+
+    /// Returns the share group of the context. Contexts in the same share
+    /// group can use each other's GL objects, such as textures.
+    #[inline]
+    #[must_use]
+    pub fn share_group(&self) -> u64 {
+        RefCell::borrow(&self.state).share_group.get()
+    }
+
+    /// Moves the context, and the GL objects it has created, into the
+    /// specified share group. This is used when the context was created to
+    /// share objects with a context in that group.
+    #[cfg(all(feature = "windowing", not(target_arch = "wasm32")))]
+    pub fn join_share_group(&self, share_group: u64) {
+        RefCell::borrow(&self.state).share_group.set(share_group);
+    }
+
+    /// Records that this context has been made current on this thread, so
+    /// that GL objects from other share groups aren't deleted using it.
+    #[cfg(all(feature = "windowing", not(target_arch = "wasm32")))]
+    pub fn mark_current(&self) {
+        let share_group = self.share_group();
+        CURRENT_SHARE_GROUP.with(|current| current.set(Some(share_group)));
+    }
+
+    /// Marks the context as valid again after a context loss. All existing
+    /// GL objects are considered lost, apart from textures whose data was
+    /// retained using [GLTexture::set_image_data_retained], which are
+    /// recreated.
+    pub fn restore(&self) -> Result<(), BacktraceError<ErrorMessage>> {
+        log::info!("Restoring GL context manager");
+
+        let (active_texture, active_program, active_framebuffer) = {
+            let mut state = RefCell::borrow_mut(&self.state);
+
+            state.is_valid = true;
+            state.generation += 1;
+            state.active_blend_mode = None;
+            state.viewport_size = None;
+            state.scissor_enabled = false;
+
+            (
+                state.active_texture.take(),
+                state.active_program.take(),
+                state.active_framebuffer.take(),
+            )
+        };
+
+        // Drop separately to avoid a duplicate borrow of `state`.
+        drop(active_texture);
+        drop(active_program);
+        drop(active_framebuffer);
+
+        let retained_textures: Vec<GLTexture> = {
+            let mut state = RefCell::borrow_mut(&self.state);
+            state
+                .retained_textures
+                .retain(|inner| inner.strong_count() > 0);
+            state
+                .retained_textures
+                .iter()
+                .filter_map(Weak::upgrade)
+                .map(|inner| GLTexture { inner })
+                .collect()
+        };
+
+        log::info!("Restoring {} retained textures", retained_textures.len());
+
+        for texture in retained_textures {
+            texture
+                .restore(self)
+                .context("Failed to restore retained texture")?;
+        }
+
+        self.unbind_texture();
+
+        Ok(())
+    }
+
     pub fn new_buffer(
         &self,
         target: GLBufferTarget,
@@ -801,12 +1225,94 @@ impl GLContextManager {
         GLTexture::new(self)
     }
 
+    pub fn new_framebuffer(
+        &self,
+        texture: &GLTexture,
+    ) -> Result<GLFramebuffer, BacktraceError<ErrorMessage>> {
+        self.ensure_valid()?;
+        GLFramebuffer::new(self, texture)
+    }
+
+    /// Reads back the contents of a texture in RGBA format, by temporarily
+    /// attaching it to a framebuffer. Rows are returned in the order they
+    /// were uploaded.
+    pub fn read_texture_rgba(
+        &self,
+        texture: &GLTexture,
+        size: UVec2,
+    ) -> Result<Vec<u8>, BacktraceError<ErrorMessage>> {
+        self.ensure_valid()?;
+
+        let previous_framebuffer = RefCell::borrow(&self.state).active_framebuffer.clone();
+
+        let framebuffer = self.new_framebuffer(texture)?;
+        self.bind_framebuffer(Some(&framebuffer));
+
+        let bytes = size.x as usize * size.y as usize * 4;
+        let mut buf: Vec<u8> = Vec::with_capacity(bytes);
+
+        self.with_gl_backend(|backend| unsafe {
+            backend.gl_read_pixels(
+                0,
+                0,
+                size.x.try_into().unwrap(),
+                size.y.try_into().unwrap(),
+                GL_RGBA,
+                GL_UNSIGNED_BYTE,
+                buf.spare_capacity_mut(),
+            );
+        });
+
+        unsafe {
+            buf.set_len(bytes);
+        }
+
+        self.bind_framebuffer(previous_framebuffer.as_ref());
+
+        Ok(buf)
+    }
+
+    /// Directs rendering into the specified framebuffer, or to the default
+    /// framebuffer (i.e. the window) if `None` is specified.
+    pub fn bind_framebuffer(&self, framebuffer: Option<&GLFramebuffer>) {
+        if !self.is_valid() {
+            log::warn!("Ignoring bind_framebuffer: invalid GL context");
+            return;
+        }
+
+        if RefCell::borrow(&self.state).active_framebuffer.as_ref() == framebuffer {
+            // Already bound
+            return;
+        }
+
+        if let Some(framebuffer) = framebuffer {
+            if RefCell::borrow(&self.state).active_texture.as_ref() == Some(framebuffer.texture()) {
+                // Drawing to a texture while sampling from it is not allowed
+                self.unbind_texture();
+            }
+        }
+
+        // Drop separately to avoid a duplicate borrow of `state`.
+        let old_framebuffer = RefCell::borrow_mut(&self.state).active_framebuffer.take();
+        drop(old_framebuffer);
+
+        RefCell::borrow_mut(&self.state).active_framebuffer = framebuffer.cloned();
+
+        self.with_gl_backend(|backend| unsafe {
+            backend.gl_bind_framebuffer(GL_FRAMEBUFFER, framebuffer.map(|fb| fb.get_handle()));
+        });
+    }
+
     pub fn set_viewport_size(&self, size: UVec2) {
         if !self.is_valid() {
             log::warn!("Ignoring set_viewport_size: invalid GL context");
             return;
         }
 
+        if RefCell::borrow(&self.state).viewport_size == Some(size) {
+            return;
+        }
+
         log::info!("Setting viewport size to {}x{}", size.x, size.y);
 
         self.state.borrow_mut().viewport_size = Some(size);
@@ -822,6 +1328,18 @@ impl GLContextManager {
             return;
         }
 
+        if texture.is_lost(self) {
+            log::debug!("Ignoring bind_texture: texture was lost with the context");
+            self.unbind_texture();
+            return;
+        }
+
+        if texture.share_group() != self.share_group() {
+            log::error!("Ignoring bind_texture: texture belongs to an unshared context");
+            self.unbind_texture();
+            return;
+        }
+
         if RefCell::borrow(&self.state).active_texture.as_ref() == Some(texture) {
             // Already bound
             return;
@@ -831,40 +1349,63 @@ impl GLContextManager {
         let old_active_texture = RefCell::borrow_mut(&self.state).active_texture.take();
         drop(old_active_texture);
 
-        RefCell::borrow_mut(&self.state).active_texture = Some(texture.clone());
+        {
+            let mut state = RefCell::borrow_mut(&self.state);
+            state.active_texture = Some(texture.clone());
+            state.state_change_counts.texture_binds += 1;
+        }
 
         self.with_gl_backend(|backend| unsafe {
             backend.gl_active_texture(GL_TEXTURE0);
-            backend.gl_bind_texture(GL_TEXTURE_2D, texture.get_handle());
+            backend.gl_bind_texture(GL_TEXTURE_2D, Some(texture.get_handle()));
         });
     }
 
     pub fn unbind_texture(&self) {
-        #[cfg(not(target_arch = "wasm32"))]
+        if !self.is_valid() {
+            log::warn!("Ignoring unbind_texture: invalid GL context");
+            return;
+        }
+
+        if RefCell::borrow(&self.state)
+            .active_texture
+            .as_ref()
+            .is_none()
         {
-            if !self.is_valid() {
-                log::warn!("Ignoring unbind_texture: invalid GL context");
-                return;
-            }
+            // Already unbound
+            return;
+        }
 
-            if RefCell::borrow(&self.state)
-                .active_texture
-                .as_ref()
-                .is_none()
-            {
-                // Already unbound
-                return;
-            }
+        self.with_gl_backend(|backend| unsafe {
+            backend.gl_active_texture(GL_TEXTURE0);
+            backend.gl_bind_texture(GL_TEXTURE_2D, None);
+        });
 
-            self.with_gl_backend(|backend| unsafe {
-                backend.gl_active_texture(GL_TEXTURE0);
-                backend.gl_bind_texture(GL_TEXTURE_2D, 0);
-            });
+        // Drop separately to avoid a duplicate borrow of `state`.
+        let old_texture = RefCell::borrow_mut(&self.state).active_texture.take();
+        drop(old_texture);
+    }
 
-            // Drop separately to avoid a duplicate borrow of `state`.
-            let old_texture = RefCell::borrow_mut(&self.state).active_texture.take();
-            drop(old_texture);
+    /// Binds a texture to the second texture unit, for shaders which sample
+    /// from two textures. Unlike [GLContextManager::bind_texture], this
+    /// binding is not tracked, so it should be cleared again after drawing by
+    /// passing `None`.
+    pub fn bind_secondary_texture(&self, texture: Option<&GLTexture>) {
+        if !self.is_valid() {
+            log::warn!("Ignoring bind_secondary_texture: invalid GL context");
+            return;
         }
+
+        self.with_gl_backend(|backend| unsafe {
+            backend.gl_active_texture(GL_TEXTURE1);
+            backend.gl_bind_texture(
+                GL_TEXTURE_2D,
+                texture
+                    .filter(|texture| !texture.is_lost(self))
+                    .map(|texture| texture.get_handle()),
+            );
+            backend.gl_active_texture(GL_TEXTURE0);
+        });
     }
 
     pub fn use_program(&self, program: &Rc<GLProgram>) {
@@ -882,7 +1423,12 @@ impl GLContextManager {
             existing_program.disable(self);
         }
 
-        RefCell::borrow_mut(&self.state).active_program = Some(program.clone());
+        {
+            let mut state = RefCell::borrow_mut(&self.state);
+            state.active_program = Some(program.clone());
+            state.state_change_counts.program_changes += 1;
+        }
+
         program.enable(self);
     }
 
@@ -891,7 +1437,11 @@ impl GLContextManager {
             return;
         }
 
-        RefCell::borrow_mut(&self.state).active_blend_mode = Some(blend_mode.clone());
+        {
+            let mut state = RefCell::borrow_mut(&self.state);
+            state.active_blend_mode = Some(blend_mode.clone());
+            state.state_change_counts.blend_mode_changes += 1;
+        }
 
         match blend_mode {
             GLBlendEnabled::Enabled(mode) => match mode {
@@ -967,10 +1517,17 @@ impl GLContextManager {
         callback(&backend)
     }
 
-    fn is_valid(&self) -> bool {
+    pub fn is_valid(&self) -> bool {
         RefCell::borrow(&self.state).is_valid
     }
 
+    /// Incremented each time the context is restored after a loss.
+    #[inline]
+    #[must_use]
+    fn generation(&self) -> u64 {
+        RefCell::borrow(&self.state).generation
+    }
+
     fn ensure_valid(&self) -> Result<(), BacktraceError<ErrorMessage>> {
         if !self.is_valid() {
             Err(ErrorMessage::msg("GL context no longer valid"))
@@ -979,6 +1536,12 @@ impl GLContextManager {
         }
     }
 
+    /// Returns the number of state changes since the last call, and resets
+    /// the counts to zero.
+    pub fn take_state_change_counts(&self) -> GLStateChangeCounts {
+        std::mem::take(&mut RefCell::borrow_mut(&self.state).state_change_counts)
+    }
+
     pub fn version(&self) -> GLVersion {
         self.state.borrow().gl_version
     }
@@ -1049,6 +1612,5 @@ pub enum GLBlendMode {
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
 pub enum GLBlendEnabled {
     Enabled(GLBlendMode),
-    #[allow(dead_code)]
     Disabled,
 }
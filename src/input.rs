@@ -0,0 +1,1115 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use glam::Vec2;
+
+use crate::error::{BacktraceError, Context, ErrorMessage};
+use crate::window::{
+    KeyScancode, ModifiersState, MouseButton, MouseScrollDistance, VirtualKeyCode,
+};
+
+/// A snapshot of the keyboard and mouse state of a window, which is kept up to
+/// date by the event loop. This allows the input to be polled, rather than
+/// tracked manually using the [crate::window::WindowHandler] callbacks.
+///
+/// Anything described as happening "this frame" happened since the previous
+/// frame finished drawing, and is reset once the current frame has been drawn.
+///
+/// See [crate::window::WindowHelper::input].
+#[derive(Debug, Default, Clone)]
+pub struct InputState {
+    keys_down: HashSet<VirtualKeyCode>,
+    keys_pressed: HashSet<VirtualKeyCode>,
+    keys_released: HashSet<VirtualKeyCode>,
+    scancodes_down: HashSet<KeyScancode>,
+    buttons_down: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+    modifiers: ModifiersState,
+    mouse_position: Vec2,
+    mouse_movement: Vec2,
+    scroll_lines: Vec2,
+    scroll_pixels: Vec2,
+    scroll_pages: Vec2,
+}
+
+impl InputState {
+    /// Returns true if the specified key is currently held down.
+    #[inline]
+    #[must_use]
+    pub fn is_key_down(&self, key: VirtualKeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    /// Returns true if the specified key was pressed this frame. Key repeats
+    /// are not included.
+    #[inline]
+    #[must_use]
+    pub fn was_key_pressed_this_frame(&self, key: VirtualKeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// Returns true if the specified key was released this frame.
+    #[inline]
+    #[must_use]
+    pub fn was_key_released_this_frame(&self, key: VirtualKeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    /// Returns true if the key with the specified scancode is currently held
+    /// down. This is useful for keys which don't have a [VirtualKeyCode].
+    #[inline]
+    #[must_use]
+    pub fn is_scancode_down(&self, scancode: KeyScancode) -> bool {
+        self.scancodes_down.contains(&scancode)
+    }
+
+    /// Returns true if the specified mouse button is currently held down.
+    #[inline]
+    #[must_use]
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    /// Returns true if the specified mouse button was pressed this frame.
+    #[inline]
+    #[must_use]
+    pub fn was_button_pressed_this_frame(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /// Returns true if the specified mouse button was released this frame.
+    #[inline]
+    #[must_use]
+    pub fn was_button_released_this_frame(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// Returns the current state of the modifier keys.
+    #[inline]
+    #[must_use]
+    pub fn modifiers(&self) -> &ModifiersState {
+        &self.modifiers
+    }
+
+    /// Returns the most recent position of the mouse cursor, in the same
+    /// coordinates as [crate::window::WindowHandler::on_mouse_move].
+    ///
+    /// While the cursor is grabbed, this is not updated. See
+    /// [InputState::mouse_movement_this_frame].
+    #[inline]
+    #[must_use]
+    pub fn mouse_position(&self) -> Vec2 {
+        self.mouse_position
+    }
+
+    /// Returns the total relative mouse movement this frame, while the cursor
+    /// is grabbed. See [crate::window::WindowHelper::set_cursor_grab].
+    #[inline]
+    #[must_use]
+    pub fn mouse_movement_this_frame(&self) -> Vec2 {
+        self.mouse_movement
+    }
+
+    /// Returns the total distance scrolled this frame, by devices which
+    /// report scrolling in lines. See [MouseScrollDistance::Lines].
+    #[inline]
+    #[must_use]
+    pub fn scroll_lines_this_frame(&self) -> Vec2 {
+        self.scroll_lines
+    }
+
+    /// Returns the total distance scrolled this frame, by devices which
+    /// report scrolling in pixels. See [MouseScrollDistance::Pixels].
+    #[inline]
+    #[must_use]
+    pub fn scroll_pixels_this_frame(&self) -> Vec2 {
+        self.scroll_pixels
+    }
+
+    /// Returns the total distance scrolled this frame, by devices which
+    /// report scrolling in pages. See [MouseScrollDistance::Pages].
+    #[inline]
+    #[must_use]
+    pub fn scroll_pages_this_frame(&self) -> Vec2 {
+        self.scroll_pages
+    }
+
+    pub(crate) fn on_key_down(
+        &mut self,
+        virtual_key_code: Option<VirtualKeyCode>,
+        scancode: KeyScancode,
+    ) {
+        if let Some(key) = virtual_key_code {
+            if self.keys_down.insert(key) {
+                self.keys_pressed.insert(key);
+            }
+        }
+
+        self.scancodes_down.insert(scancode);
+    }
+
+    pub(crate) fn on_key_up(
+        &mut self,
+        virtual_key_code: Option<VirtualKeyCode>,
+        scancode: KeyScancode,
+    ) {
+        if let Some(key) = virtual_key_code {
+            if self.keys_down.remove(&key) {
+                self.keys_released.insert(key);
+            }
+        }
+
+        self.scancodes_down.remove(&scancode);
+    }
+
+    pub(crate) fn on_mouse_button_down(&mut self, button: MouseButton) {
+        if self.buttons_down.insert(button) {
+            self.buttons_pressed.insert(button);
+        }
+    }
+
+    pub(crate) fn on_mouse_button_up(&mut self, button: MouseButton) {
+        if self.buttons_down.remove(&button) {
+            self.buttons_released.insert(button);
+        }
+    }
+
+    pub(crate) fn on_mouse_move(&mut self, position: Vec2, mouse_grabbed: bool) {
+        if mouse_grabbed {
+            self.mouse_movement += position;
+        } else {
+            self.mouse_position = position;
+        }
+    }
+
+    pub(crate) fn on_mouse_wheel_scroll(&mut self, distance: MouseScrollDistance) {
+        match distance {
+            MouseScrollDistance::Lines { x, y, .. } => {
+                self.scroll_lines += Vec2::new(x as f32, y as f32)
+            }
+            MouseScrollDistance::Pixels { x, y, .. } => {
+                self.scroll_pixels += Vec2::new(x as f32, y as f32)
+            }
+            MouseScrollDistance::Pages { x, y, .. } => {
+                self.scroll_pages += Vec2::new(x as f32, y as f32)
+            }
+        }
+    }
+
+    pub(crate) fn on_keyboard_modifiers_changed(&mut self, state: ModifiersState) {
+        self.modifiers = state;
+    }
+
+    /// Releases every key and mouse button, for when the window loses focus
+    /// and would never receive the events for keys released elsewhere.
+    pub(crate) fn release_all(&mut self) {
+        self.keys_released.extend(self.keys_down.drain());
+        self.buttons_released.extend(self.buttons_down.drain());
+        self.scancodes_down.clear();
+        self.modifiers = ModifiersState::default();
+    }
+
+    /// Clears everything which only applies to the current frame.
+    pub(crate) fn on_frame_end(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+        self.mouse_movement = Vec2::ZERO;
+        self.scroll_lines = Vec2::ZERO;
+        self.scroll_pixels = Vec2::ZERO;
+        self.scroll_pages = Vec2::ZERO;
+    }
+}
+
+/// Something which can trigger an input binding: a key (identified either by
+/// its [VirtualKeyCode], or by its scancode), or a mouse button.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum InputTrigger {
+    /// A key, identified by its virtual key code.
+    Key(VirtualKeyCode),
+    /// A key, identified by its scancode. This is useful for keys which don't
+    /// have a [VirtualKeyCode], or for binding keys by their physical
+    /// location.
+    Scancode(KeyScancode),
+    /// A mouse button.
+    MouseButton(MouseButton),
+}
+
+impl InputTrigger {
+    fn is_down(&self, input: &InputState) -> bool {
+        match self {
+            InputTrigger::Key(key) => input.is_key_down(*key),
+            InputTrigger::Scancode(scancode) => input.is_scancode_down(*scancode),
+            InputTrigger::MouseButton(button) => input.is_button_down(*button),
+        }
+    }
+}
+
+impl From<VirtualKeyCode> for InputTrigger {
+    fn from(key: VirtualKeyCode) -> Self {
+        InputTrigger::Key(key)
+    }
+}
+
+impl From<MouseButton> for InputTrigger {
+    fn from(button: MouseButton) -> Self {
+        InputTrigger::MouseButton(button)
+    }
+}
+
+impl Display for InputTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputTrigger::Key(key) => write!(f, "{key:?}"),
+            InputTrigger::Scancode(scancode) => write!(f, "Scancode{scancode}"),
+            InputTrigger::MouseButton(MouseButton::Other(index)) => write!(f, "Mouse{index}"),
+            InputTrigger::MouseButton(button) => write!(f, "Mouse{button:?}"),
+        }
+    }
+}
+
+impl FromStr for InputTrigger {
+    type Err = BacktraceError<ErrorMessage>;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if let Some(key) = ALL_VIRTUAL_KEY_CODES
+            .iter()
+            .find(|key| format!("{key:?}") == name)
+        {
+            return Ok(InputTrigger::Key(*key));
+        }
+
+        if let Some(scancode) = name.strip_prefix("Scancode") {
+            return scancode
+                .parse()
+                .map(InputTrigger::Scancode)
+                .map_err(|err| ErrorMessage::msg_with_cause("Invalid scancode", err));
+        }
+
+        if let Some(button) = name.strip_prefix("Mouse") {
+            let button = match button {
+                "Left" => MouseButton::Left,
+                "Middle" => MouseButton::Middle,
+                "Right" => MouseButton::Right,
+                "Back" => MouseButton::Back,
+                "Forward" => MouseButton::Forward,
+                index => MouseButton::Other(index.parse().map_err(|err| {
+                    ErrorMessage::msg_with_cause(format!("Unknown mouse button '{name}'"), err)
+                })?),
+            };
+
+            return Ok(InputTrigger::MouseButton(button));
+        }
+
+        Err(ErrorMessage::msg(format!("Unknown key or button '{name}'")))
+    }
+}
+
+/// A binding for an action, consisting of an [InputTrigger], and the modifier
+/// keys which must be held at the same time.
+///
+/// The binding only matches if the modifier keys which are held are exactly
+/// those of the binding, so that `S` and `Ctrl+S` can be bound to different
+/// actions.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct Binding {
+    trigger: InputTrigger,
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    logo: bool,
+}
+
+impl Binding {
+    /// Creates a binding for the specified trigger, with no modifier keys.
+    #[inline]
+    #[must_use]
+    pub fn new<T: Into<InputTrigger>>(trigger: T) -> Self {
+        Binding {
+            trigger: trigger.into(),
+            ctrl: false,
+            alt: false,
+            shift: false,
+            logo: false,
+        }
+    }
+
+    /// Requires the Ctrl key to be held.
+    #[inline]
+    #[must_use]
+    pub fn with_ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    /// Requires the Alt key to be held.
+    #[inline]
+    #[must_use]
+    pub fn with_alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Requires the Shift key to be held.
+    #[inline]
+    #[must_use]
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    /// Requires the logo key (for example, the Windows or Command key) to be
+    /// held.
+    #[inline]
+    #[must_use]
+    pub fn with_logo(mut self) -> Self {
+        self.logo = true;
+        self
+    }
+
+    /// Returns the trigger of this binding.
+    #[inline]
+    #[must_use]
+    pub fn trigger(&self) -> InputTrigger {
+        self.trigger
+    }
+
+    fn matches_modifiers(&self, modifiers: &ModifiersState) -> bool {
+        self.ctrl == modifiers.ctrl()
+            && self.alt == modifiers.alt()
+            && self.shift == modifiers.shift()
+            && self.logo == modifiers.logo()
+    }
+}
+
+impl From<InputTrigger> for Binding {
+    fn from(trigger: InputTrigger) -> Self {
+        Binding::new(trigger)
+    }
+}
+
+impl From<VirtualKeyCode> for Binding {
+    fn from(key: VirtualKeyCode) -> Self {
+        Binding::new(key)
+    }
+}
+
+impl From<MouseButton> for Binding {
+    fn from(button: MouseButton) -> Self {
+        Binding::new(button)
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (held, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.logo, "Logo"),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        Display::fmt(&self.trigger, f)
+    }
+}
+
+impl FromStr for Binding {
+    type Err = BacktraceError<ErrorMessage>;
+
+    /// Parses a binding such as `Ctrl+Shift+S`, `Space`, `MouseLeft` or
+    /// `Scancode57`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+
+        let trigger = parts
+            .pop()
+            .filter(|trigger| !trigger.is_empty())
+            .ok_or_else(|| ErrorMessage::msg(format!("Missing key in binding '{text}'")))?;
+
+        let mut binding = Binding::new(trigger.parse::<InputTrigger>()?);
+
+        for modifier in parts {
+            binding = match modifier {
+                "Ctrl" => binding.with_ctrl(),
+                "Alt" => binding.with_alt(),
+                "Shift" => binding.with_shift(),
+                "Logo" => binding.with_logo(),
+                _ => {
+                    return Err(ErrorMessage::msg(format!(
+                        "Unknown modifier '{modifier}' in binding '{text}'"
+                    )))
+                }
+            };
+        }
+
+        Ok(binding)
+    }
+}
+
+/// A pair of triggers which move an axis in the negative and positive
+/// directions, for example `A` and `D` for horizontal movement.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct AxisBinding {
+    /// The trigger which moves the axis towards `-1.0`.
+    pub negative: InputTrigger,
+    /// The trigger which moves the axis towards `1.0`.
+    pub positive: InputTrigger,
+}
+
+/// Maps named actions and axes to their input bindings.
+///
+/// An action (such as `"jump"` or `"save"`) has any number of [Binding]s.
+/// When one of them is pressed,
+/// [crate::window::WindowHandler::on_action] is invoked with `pressed` set
+/// to `true`, and once the trigger is released, it is invoked again with
+/// `pressed` set to `false`.
+///
+/// An axis (such as `"move_x"`) has any number of [AxisBinding]s, and its
+/// value can be polled using [ActionMap::axis_value]. Modifier keys are
+/// ignored for axes.
+///
+/// The bindings of each window can be changed at runtime using
+/// [crate::window::WindowHelper::action_map_mut].
+///
+/// An action map can be saved and loaded in a simple text format, using
+/// [ActionMap::from_config_str] and the [Display] implementation. Each line
+/// contains one action or axis, and lines starting with `#` are ignored:
+///
+/// ```text
+/// # Actions, followed by a comma-separated list of bindings
+/// action jump = Space, W
+/// action save = Ctrl+S
+/// action fire = MouseLeft
+///
+/// # Axes, followed by a comma-separated list of negative/positive pairs
+/// axis move_x = A / D, Left / Right
+/// ```
+///
+/// Keys are named in the same way as the [VirtualKeyCode] variants. Keys
+/// can also be given as a scancode (for example `Scancode57`), and mouse
+/// buttons as `MouseLeft`, `MouseMiddle`, `MouseRight`, `MouseBack`,
+/// `MouseForward`, or a number (for example `Mouse7`).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ActionMap {
+    actions: BTreeMap<String, Vec<Binding>>,
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+    active_actions: HashMap<String, InputTrigger>,
+}
+
+impl ActionMap {
+    /// Creates an empty action map.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binding for the specified action.
+    pub fn bind_action<S, B>(&mut self, action: S, binding: B)
+    where
+        S: Into<String>,
+        B: Into<Binding>,
+    {
+        let bindings = self.actions.entry(action.into()).or_default();
+        let binding = binding.into();
+
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes a single binding from the specified action.
+    pub fn unbind_action<B: Into<Binding>>(&mut self, action: &str, binding: B) {
+        let binding = binding.into();
+
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|existing| *existing != binding);
+        }
+    }
+
+    /// Removes the specified action, and all its bindings.
+    pub fn remove_action(&mut self, action: &str) {
+        self.actions.remove(action);
+        self.active_actions.remove(action);
+    }
+
+    /// Returns the bindings of the specified action.
+    #[must_use]
+    pub fn action_bindings(&self, action: &str) -> &[Binding] {
+        self.actions
+            .get(action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of all the actions.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(String::as_str)
+    }
+
+    /// Returns true if the specified action is currently held down.
+    #[inline]
+    #[must_use]
+    pub fn is_action_down(&self, action: &str) -> bool {
+        self.active_actions.contains_key(action)
+    }
+
+    /// Adds a negative/positive binding pair for the specified axis.
+    pub fn bind_axis<S, N, P>(&mut self, axis: S, negative: N, positive: P)
+    where
+        S: Into<String>,
+        N: Into<InputTrigger>,
+        P: Into<InputTrigger>,
+    {
+        let bindings = self.axes.entry(axis.into()).or_default();
+
+        let binding = AxisBinding {
+            negative: negative.into(),
+            positive: positive.into(),
+        };
+
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Removes the specified axis, and all its bindings.
+    pub fn remove_axis(&mut self, axis: &str) {
+        self.axes.remove(axis);
+    }
+
+    /// Returns the bindings of the specified axis.
+    #[must_use]
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the names of all the axes.
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(String::as_str)
+    }
+
+    /// Returns the current value of the specified axis, from `-1.0` to `1.0`,
+    /// based on which of its triggers are held down. If both triggers of a
+    /// pair are held, they cancel out.
+    #[must_use]
+    pub fn axis_value(&self, axis: &str, input: &InputState) -> f32 {
+        self.axis_bindings(axis)
+            .iter()
+            .map(|binding| {
+                let negative = if binding.negative.is_down(input) {
+                    -1.0
+                } else {
+                    0.0
+                };
+                let positive = if binding.positive.is_down(input) {
+                    1.0
+                } else {
+                    0.0
+                };
+                negative + positive
+            })
+            .sum::<f32>()
+            .clamp(-1.0, 1.0)
+    }
+
+    /// Parses an action map from the text format described in the
+    /// [ActionMap] documentation.
+    pub fn from_config_str(text: &str) -> Result<Self, BacktraceError<ErrorMessage>> {
+        let mut result = ActionMap::new();
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            result
+                .parse_config_line(line)
+                .context(format!("Failed to parse line {}", line_index + 1))?;
+        }
+
+        Ok(result)
+    }
+
+    fn parse_config_line(&mut self, line: &str) -> Result<(), BacktraceError<ErrorMessage>> {
+        let (declaration, bindings) = line
+            .split_once('=')
+            .ok_or_else(|| ErrorMessage::msg("Expected '='"))?;
+
+        let (kind, name) = declaration
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| ErrorMessage::msg("Expected 'action <name>' or 'axis <name>'"))?;
+
+        let name = name.trim();
+
+        let bindings = bindings
+            .split(',')
+            .map(str::trim)
+            .filter(|binding| !binding.is_empty());
+
+        match kind {
+            "action" => {
+                self.actions.entry(name.to_string()).or_default();
+
+                for binding in bindings {
+                    self.bind_action(name, binding.parse::<Binding>()?);
+                }
+            }
+
+            "axis" => {
+                self.axes.entry(name.to_string()).or_default();
+
+                for binding in bindings {
+                    let (negative, positive) = binding.split_once('/').ok_or_else(|| {
+                        ErrorMessage::msg(format!(
+                            "Expected 'negative / positive', got '{binding}'"
+                        ))
+                    })?;
+
+                    self.bind_axis(
+                        name,
+                        negative.trim().parse::<InputTrigger>()?,
+                        positive.trim().parse::<InputTrigger>()?,
+                    );
+                }
+            }
+
+            _ => return Err(ErrorMessage::msg(format!("Unknown declaration '{kind}'"))),
+        }
+
+        Ok(())
+    }
+
+    /// Activates the actions bound to any of the triggers, returning the
+    /// names of the actions which were not already active.
+    pub(crate) fn on_triggers_down(
+        &mut self,
+        triggers: &[InputTrigger],
+        modifiers: &ModifiersState,
+    ) -> Vec<String> {
+        let mut pressed = Vec::new();
+
+        for (action, bindings) in &self.actions {
+            if self.active_actions.contains_key(action) {
+                continue;
+            }
+
+            let binding = bindings.iter().find(|binding| {
+                triggers.contains(&binding.trigger) && binding.matches_modifiers(modifiers)
+            });
+
+            if let Some(binding) = binding {
+                self.active_actions.insert(action.clone(), binding.trigger);
+                pressed.push(action.clone());
+            }
+        }
+
+        pressed
+    }
+
+    /// Deactivates every active action, returning their names.
+    pub(crate) fn release_all(&mut self) -> Vec<String> {
+        self.active_actions
+            .drain()
+            .map(|(action, _)| action)
+            .collect()
+    }
+
+    /// Deactivates any actions which were activated by one of the triggers,
+    /// returning their names.
+    pub(crate) fn on_triggers_up(&mut self, triggers: &[InputTrigger]) -> Vec<String> {
+        let released: Vec<String> = self
+            .active_actions
+            .iter()
+            .filter(|(_, trigger)| triggers.contains(trigger))
+            .map(|(action, _)| action.clone())
+            .collect();
+
+        for action in &released {
+            self.active_actions.remove(action);
+        }
+
+        released
+    }
+}
+
+impl Display for ActionMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (action, bindings) in &self.actions {
+            let bindings: Vec<String> = bindings.iter().map(Binding::to_string).collect();
+            writeln!(f, "action {action} = {}", bindings.join(", "))?;
+        }
+
+        for (axis, bindings) in &self.axes {
+            let bindings: Vec<String> = bindings
+                .iter()
+                .map(|binding| format!("{} / {}", binding.negative, binding.positive))
+                .collect();
+            writeln!(f, "axis {axis} = {}", bindings.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+const ALL_VIRTUAL_KEY_CODES: [VirtualKeyCode; 163] = [
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::A,
+    VirtualKeyCode::B,
+    VirtualKeyCode::C,
+    VirtualKeyCode::D,
+    VirtualKeyCode::E,
+    VirtualKeyCode::F,
+    VirtualKeyCode::G,
+    VirtualKeyCode::H,
+    VirtualKeyCode::I,
+    VirtualKeyCode::J,
+    VirtualKeyCode::K,
+    VirtualKeyCode::L,
+    VirtualKeyCode::M,
+    VirtualKeyCode::N,
+    VirtualKeyCode::O,
+    VirtualKeyCode::P,
+    VirtualKeyCode::Q,
+    VirtualKeyCode::R,
+    VirtualKeyCode::S,
+    VirtualKeyCode::T,
+    VirtualKeyCode::U,
+    VirtualKeyCode::V,
+    VirtualKeyCode::W,
+    VirtualKeyCode::X,
+    VirtualKeyCode::Y,
+    VirtualKeyCode::Z,
+    VirtualKeyCode::Escape,
+    VirtualKeyCode::F1,
+    VirtualKeyCode::F2,
+    VirtualKeyCode::F3,
+    VirtualKeyCode::F4,
+    VirtualKeyCode::F5,
+    VirtualKeyCode::F6,
+    VirtualKeyCode::F7,
+    VirtualKeyCode::F8,
+    VirtualKeyCode::F9,
+    VirtualKeyCode::F10,
+    VirtualKeyCode::F11,
+    VirtualKeyCode::F12,
+    VirtualKeyCode::F13,
+    VirtualKeyCode::F14,
+    VirtualKeyCode::F15,
+    VirtualKeyCode::F16,
+    VirtualKeyCode::F17,
+    VirtualKeyCode::F18,
+    VirtualKeyCode::F19,
+    VirtualKeyCode::F20,
+    VirtualKeyCode::F21,
+    VirtualKeyCode::F22,
+    VirtualKeyCode::F23,
+    VirtualKeyCode::F24,
+    VirtualKeyCode::PrintScreen,
+    VirtualKeyCode::ScrollLock,
+    VirtualKeyCode::PauseBreak,
+    VirtualKeyCode::Insert,
+    VirtualKeyCode::Home,
+    VirtualKeyCode::Delete,
+    VirtualKeyCode::End,
+    VirtualKeyCode::PageDown,
+    VirtualKeyCode::PageUp,
+    VirtualKeyCode::Left,
+    VirtualKeyCode::Up,
+    VirtualKeyCode::Right,
+    VirtualKeyCode::Down,
+    VirtualKeyCode::Backspace,
+    VirtualKeyCode::Return,
+    VirtualKeyCode::Space,
+    VirtualKeyCode::Compose,
+    VirtualKeyCode::Caret,
+    VirtualKeyCode::Numlock,
+    VirtualKeyCode::Numpad0,
+    VirtualKeyCode::Numpad1,
+    VirtualKeyCode::Numpad2,
+    VirtualKeyCode::Numpad3,
+    VirtualKeyCode::Numpad4,
+    VirtualKeyCode::Numpad5,
+    VirtualKeyCode::Numpad6,
+    VirtualKeyCode::Numpad7,
+    VirtualKeyCode::Numpad8,
+    VirtualKeyCode::Numpad9,
+    VirtualKeyCode::NumpadAdd,
+    VirtualKeyCode::NumpadDivide,
+    VirtualKeyCode::NumpadDecimal,
+    VirtualKeyCode::NumpadComma,
+    VirtualKeyCode::NumpadEnter,
+    VirtualKeyCode::NumpadEquals,
+    VirtualKeyCode::NumpadMultiply,
+    VirtualKeyCode::NumpadSubtract,
+    VirtualKeyCode::AbntC1,
+    VirtualKeyCode::AbntC2,
+    VirtualKeyCode::Apostrophe,
+    VirtualKeyCode::Apps,
+    VirtualKeyCode::Asterisk,
+    VirtualKeyCode::At,
+    VirtualKeyCode::Ax,
+    VirtualKeyCode::Backslash,
+    VirtualKeyCode::Calculator,
+    VirtualKeyCode::Capital,
+    VirtualKeyCode::Colon,
+    VirtualKeyCode::Comma,
+    VirtualKeyCode::Convert,
+    VirtualKeyCode::Equals,
+    VirtualKeyCode::Grave,
+    VirtualKeyCode::Kana,
+    VirtualKeyCode::Kanji,
+    VirtualKeyCode::LAlt,
+    VirtualKeyCode::LBracket,
+    VirtualKeyCode::LControl,
+    VirtualKeyCode::LShift,
+    VirtualKeyCode::LWin,
+    VirtualKeyCode::Mail,
+    VirtualKeyCode::MediaSelect,
+    VirtualKeyCode::MediaStop,
+    VirtualKeyCode::Minus,
+    VirtualKeyCode::Mute,
+    VirtualKeyCode::MyComputer,
+    VirtualKeyCode::NavigateForward,
+    VirtualKeyCode::NavigateBackward,
+    VirtualKeyCode::NextTrack,
+    VirtualKeyCode::NoConvert,
+    VirtualKeyCode::OEM102,
+    VirtualKeyCode::Period,
+    VirtualKeyCode::PlayPause,
+    VirtualKeyCode::Plus,
+    VirtualKeyCode::Power,
+    VirtualKeyCode::PrevTrack,
+    VirtualKeyCode::RAlt,
+    VirtualKeyCode::RBracket,
+    VirtualKeyCode::RControl,
+    VirtualKeyCode::RShift,
+    VirtualKeyCode::RWin,
+    VirtualKeyCode::Semicolon,
+    VirtualKeyCode::Slash,
+    VirtualKeyCode::Sleep,
+    VirtualKeyCode::Stop,
+    VirtualKeyCode::Sysrq,
+    VirtualKeyCode::Tab,
+    VirtualKeyCode::Underline,
+    VirtualKeyCode::Unlabeled,
+    VirtualKeyCode::VolumeDown,
+    VirtualKeyCode::VolumeUp,
+    VirtualKeyCode::Wake,
+    VirtualKeyCode::WebBack,
+    VirtualKeyCode::WebFavorites,
+    VirtualKeyCode::WebForward,
+    VirtualKeyCode::WebHome,
+    VirtualKeyCode::WebRefresh,
+    VirtualKeyCode::WebSearch,
+    VirtualKeyCode::WebStop,
+    VirtualKeyCode::Yen,
+    VirtualKeyCode::Copy,
+    VirtualKeyCode::Paste,
+    VirtualKeyCode::Cut,
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_action_map_config_round_trip() {
+        let mut map = ActionMap::new();
+        map.bind_action("jump", VirtualKeyCode::Space);
+        map.bind_action("jump", VirtualKeyCode::W);
+        map.bind_action("save", Binding::new(VirtualKeyCode::S).with_ctrl());
+        map.bind_action("fire", MouseButton::Left);
+        map.bind_action("use", InputTrigger::Scancode(57));
+        map.bind_axis("move_x", VirtualKeyCode::A, VirtualKeyCode::D);
+
+        let config = map.to_string();
+
+        assert_eq!(
+            "action fire = MouseLeft\n\
+             action jump = Space, W\n\
+             action save = Ctrl+S\n\
+             action use = Scancode57\n\
+             axis move_x = A / D\n",
+            config
+        );
+
+        assert_eq!(map, ActionMap::from_config_str(&config).unwrap());
+    }
+
+    #[test]
+    fn test_action_map_config_errors() {
+        assert!(ActionMap::from_config_str("# comment\n\naction jump = Space").is_ok());
+        assert!(ActionMap::from_config_str("action jump = Spacebar").is_err());
+        assert!(ActionMap::from_config_str("action save = Hyper+S").is_err());
+        assert!(ActionMap::from_config_str("axis move_x = A").is_err());
+        assert!(ActionMap::from_config_str("binding jump = Space").is_err());
+    }
+
+    #[test]
+    fn test_actions_require_exact_modifiers() {
+        let mut map = ActionMap::new();
+        map.bind_action("save", Binding::new(VirtualKeyCode::S).with_ctrl());
+        map.bind_action("down", VirtualKeyCode::S);
+
+        let trigger = InputTrigger::Key(VirtualKeyCode::S);
+
+        let mut modifiers = ModifiersState::default();
+        modifiers.ctrl = true;
+
+        assert_eq!(vec!["save"], map.on_triggers_down(&[trigger], &modifiers));
+        assert!(map.is_action_down("save"));
+        assert!(!map.is_action_down("down"));
+
+        assert_eq!(vec!["save"], map.on_triggers_up(&[trigger]));
+        assert!(!map.is_action_down("save"));
+    }
+
+    #[test]
+    fn test_input_state_key_press_hold_release() {
+        let mut input = InputState::default();
+        let key = VirtualKeyCode::Space;
+
+        input.on_key_down(Some(key), 57);
+        assert!(input.is_key_down(key));
+        assert!(input.is_scancode_down(57));
+        assert!(input.was_key_pressed_this_frame(key));
+        assert!(!input.was_key_released_this_frame(key));
+
+        // Key repeats while held don't count as new presses
+        input.on_frame_end();
+        input.on_key_down(Some(key), 57);
+        assert!(input.is_key_down(key));
+        assert!(!input.was_key_pressed_this_frame(key));
+
+        input.on_frame_end();
+        input.on_key_up(Some(key), 57);
+        assert!(!input.is_key_down(key));
+        assert!(!input.is_scancode_down(57));
+        assert!(input.was_key_released_this_frame(key));
+
+        input.on_frame_end();
+        assert!(!input.was_key_released_this_frame(key));
+    }
+
+    #[test]
+    fn test_input_state_key_tapped_within_frame() {
+        let mut input = InputState::default();
+        let key = VirtualKeyCode::A;
+
+        input.on_key_down(Some(key), 30);
+        input.on_key_up(Some(key), 30);
+
+        assert!(!input.is_key_down(key));
+        assert!(input.was_key_pressed_this_frame(key));
+        assert!(input.was_key_released_this_frame(key));
+
+        input.on_frame_end();
+        assert!(!input.was_key_pressed_this_frame(key));
+        assert!(!input.was_key_released_this_frame(key));
+    }
+
+    #[test]
+    fn test_input_state_mouse_buttons() {
+        let mut input = InputState::default();
+        let button = MouseButton::Left;
+
+        input.on_mouse_button_down(button);
+        assert!(input.is_button_down(button));
+        assert!(input.was_button_pressed_this_frame(button));
+        assert!(!input.is_button_down(MouseButton::Right));
+
+        input.on_frame_end();
+        assert!(input.is_button_down(button));
+        assert!(!input.was_button_pressed_this_frame(button));
+
+        input.on_mouse_button_up(button);
+        assert!(!input.is_button_down(button));
+        assert!(input.was_button_released_this_frame(button));
+
+        input.on_frame_end();
+        assert!(!input.was_button_released_this_frame(button));
+    }
+
+    #[test]
+    fn test_input_state_scroll_accumulates_until_frame_end() {
+        let mut input = InputState::default();
+
+        input.on_mouse_wheel_scroll(MouseScrollDistance::Lines {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        });
+        input.on_mouse_wheel_scroll(MouseScrollDistance::Lines {
+            x: -1.0,
+            y: 2.0,
+            z: 0.0,
+        });
+        input.on_mouse_wheel_scroll(MouseScrollDistance::Pixels {
+            x: 0.0,
+            y: 30.0,
+            z: 0.0,
+        });
+
+        assert_eq!(Vec2::new(-1.0, 3.0), input.scroll_lines_this_frame());
+        assert_eq!(Vec2::new(0.0, 30.0), input.scroll_pixels_this_frame());
+        assert_eq!(Vec2::ZERO, input.scroll_pages_this_frame());
+
+        input.on_frame_end();
+
+        assert_eq!(Vec2::ZERO, input.scroll_lines_this_frame());
+        assert_eq!(Vec2::ZERO, input.scroll_pixels_this_frame());
+    }
+
+    #[test]
+    fn test_input_state_release_all() {
+        let mut input = InputState::default();
+
+        input.on_key_down(Some(VirtualKeyCode::W), 17);
+        input.on_mouse_button_down(MouseButton::Right);
+        input.on_frame_end();
+
+        input.release_all();
+
+        assert!(!input.is_key_down(VirtualKeyCode::W));
+        assert!(!input.is_scancode_down(17));
+        assert!(!input.is_button_down(MouseButton::Right));
+        assert!(input.was_key_released_this_frame(VirtualKeyCode::W));
+        assert!(input.was_button_released_this_frame(MouseButton::Right));
+    }
+}
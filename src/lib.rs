@@ -21,8 +21,8 @@
 //!
 //!  - The simplest Rust API for creating a window, rendering graphics/text, and
 //!    handling input
-//!  - Compatible with any device supporting OpenGL 2.0+ or WebGL 2.0. Support
-//!    for OpenGL ES 2.0+ is planned.
+//!  - Compatible with any device supporting OpenGL 2.0+, OpenGL ES 2.0+, or
+//!    WebGL 1.0+.
 //!  - Very fast
 //!
 //! Supports Windows, Mac, Linux, and WebGL. Support for Android and iOS is in
@@ -300,9 +300,12 @@ use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
 use std::rc::Rc;
 
-use glam::{UVec2, Vec2};
+use glam::{Affine2, UVec2, Vec2};
 pub use glam_rect::{IRect, Rect, URect};
 
+pub use crate::glwrapper::GLVersion;
+pub use crate::renderer2d::FrameStats;
+
 #[cfg(any(feature = "image-loading", doc, doctest))]
 use {
     crate::image::ImageFileFormat,
@@ -326,17 +329,16 @@ use crate::shapes::Polygon;
 use crate::image::{ImageDataType, ImageHandle, ImageSmoothingMode, RawBitmapData};
 
 use crate::color::Color;
+use crate::draw_list::DrawList;
 use crate::error::{BacktraceError, ErrorMessage};
 use crate::glbackend::GLBackend;
-use crate::glwrapper::{GLContextManager, GLVersion};
+use crate::glwrapper::GLContextManager;
+use crate::postprocess::PostProcessPass;
 use crate::renderer2d::Renderer2D;
+use crate::svg::SvgRecorder;
 
 #[cfg(any(doc, doctest, feature = "windowing"))]
 use crate::window::WindowHandler;
-#[cfg(any(doc, doctest, all(feature = "windowing", not(target_arch = "wasm32"))))]
-use crate::window::{
-    UserEventSender, WindowCreationError, WindowCreationOptions, WindowPosition, WindowSize,
-};
 #[cfg(any(doc, doctest))]
 use crate::window_internal_doctest::{WebCanvasImpl, WindowGlutin};
 #[cfg(all(
@@ -347,12 +349,34 @@ use crate::window_internal_doctest::{WebCanvasImpl, WindowGlutin};
 use crate::window_internal_glutin::WindowGlutin;
 #[cfg(all(feature = "windowing", target_arch = "wasm32", not(any(doc, doctest))))]
 use crate::window_internal_web::WebCanvasImpl;
+#[cfg(any(doc, doctest, all(feature = "windowing", not(target_arch = "wasm32"))))]
+use {
+    crate::recording::RecordedEvent,
+    crate::window::{
+        ImeEvent, KeyScancode, ModifiersState, MouseButton, MouseScrollDistance, TouchId,
+        TouchPhase, UserEventSender, VirtualKeyCode, WindowCreationError, WindowCreationOptions,
+        WindowFullscreenMode, WindowHelper, WindowPosition, WindowSize,
+    },
+    crate::window_internal_headless::HeadlessWindowImpl,
+    glam::IVec2,
+    std::path::PathBuf,
+    std::time::Duration,
+};
 
 pub mod color;
+mod debug_overlay;
+pub mod draw_list;
 pub mod error;
-mod glbackend;
+pub mod glbackend;
+#[cfg(not(target_arch = "wasm32"))]
+mod glbackend_recording;
+#[cfg(not(target_arch = "wasm32"))]
+mod glbackend_software;
 mod glwrapper;
+pub mod postprocess;
 mod renderer2d;
+mod svg;
+mod time;
 
 #[cfg(feature = "image-loading")]
 pub mod image;
@@ -364,6 +388,14 @@ pub mod shapes;
 #[cfg(any(doc, doctest, feature = "windowing"))]
 pub mod window;
 
+/// Polled input state, tracked for each window.
+#[cfg(any(doc, doctest, feature = "windowing"))]
+pub mod input;
+
+/// Recording and replaying of the events delivered to a window.
+#[cfg(any(doc, doctest, feature = "windowing"))]
+pub mod recording;
+
 #[cfg(all(
     feature = "windowing",
     not(target_arch = "wasm32"),
@@ -377,6 +409,9 @@ mod window_internal_web;
 #[cfg(any(doc, doctest))]
 mod window_internal_doctest;
 
+#[cfg(any(doc, doctest, feature = "windowing"))]
+mod window_internal_headless;
+
 #[cfg(target_arch = "wasm32")]
 mod web;
 
@@ -440,6 +475,11 @@ impl GLRenderer {
     /// the initial viewport size, however this can be changed later using
     /// [GLRenderer:: set_viewport_size_pixels()].
     ///
+    /// If the context supports OpenGL 3.3 or later, the
+    /// [GLVersion::OpenGL3_3Core] rendering path is used, and for OpenGL ES
+    /// contexts the [GLVersion::OpenGLES2_0] path is used. Otherwise, the
+    /// OpenGL 2.0 path is used.
+    ///
     /// Note: This function must not be called if you are letting Speedy2D
     /// create a window for you.
     ///
@@ -459,9 +499,23 @@ impl GLRenderer {
         V: Into<UVec2>,
         F: FnMut(&str) -> *const std::os::raw::c_void,
     {
-        let backend = GLBackendGlow::new(glow::Context::from_loader_function(loader_function));
+        use glow::HasContext;
+
+        let context = glow::Context::from_loader_function(loader_function);
+
+        let version = context.version();
+
+        let gl_version = if version.is_embedded {
+            GLVersion::OpenGLES2_0
+        } else if (version.major, version.minor) >= (3, 3) {
+            GLVersion::OpenGL3_3Core
+        } else {
+            GLVersion::OpenGL2_0
+        };
 
-        Self::new_with_gl_backend(viewport_size_pixels, Rc::new(backend), GLVersion::OpenGL2_0)
+        let backend = GLBackendGlow::new(context);
+
+        Self::new_with_gl_backend(viewport_size_pixels, Rc::new(backend), gl_version)
     }
 
     /// Creates a `GLRenderer` for the specified HTML canvas. The canvas
@@ -481,10 +535,34 @@ impl GLRenderer {
     {
         WebCanvasElement::new_by_id(element_id.as_ref())
             .map_err(|err| GLRendererCreationError::msg_with_cause("Failed to get canvas", err))?
-            .get_webgl2_context(viewport_size_pixels)
+            .get_webgl_context(viewport_size_pixels)
     }
 
-    fn new_with_gl_backend<V: Into<UVec2>>(
+    /// Creates a `GLRenderer` which draws using a software rasterizer, into
+    /// an in-memory buffer, without requiring a GPU or display. The result
+    /// can be read back using [Graphics2D::capture].
+    ///
+    /// To access the output directly, create a
+    /// [glbackend::SoftwareGLBackend] and pass it to
+    /// [GLRenderer::new_with_gl_backend] instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new_software<V: Into<UVec2>>(
+        viewport_size_pixels: V,
+    ) -> Result<Self, BacktraceError<GLRendererCreationError>> {
+        Self::new_with_gl_backend(
+            viewport_size_pixels,
+            Rc::new(glbackend::SoftwareGLBackend::new()),
+            GLVersion::OpenGL2_0,
+        )
+    }
+
+    /// Creates a `GLRenderer` which makes its GL calls through the specified
+    /// [GLBackend]. `gl_version` determines which shaders are used.
+    ///
+    /// This allows rendering to be tested without a GPU, using
+    /// [glbackend::RecordingGLBackend], or calls to be intercepted or
+    /// forwarded to another GL library.
+    pub fn new_with_gl_backend<V: Into<UVec2>>(
         viewport_size_pixels: V,
         gl_backend: Rc<dyn GLBackend>,
         gl_version: GLVersion,
@@ -499,6 +577,7 @@ impl GLRenderer {
             renderer: Renderer2D::new(&context, viewport_size_pixels).map_err(|err| {
                 GLRendererCreationError::msg_with_cause("Renderer2D creation failed", err)
             })?,
+            svg: None,
         };
 
         Ok(GLRenderer { context, renderer })
@@ -512,6 +591,100 @@ impl GLRenderer {
             .set_viewport_size_pixels(viewport_size_pixels)
     }
 
+    /// Sets the number of physical pixels per `Graphics2D` coordinate unit. By
+    /// default this is `1.0`, meaning that all drawing happens in physical
+    /// pixels.
+    ///
+    /// Setting this to the window's scale factor allows drawing in logical
+    /// (DPI-scaled) pixels. Text will still be rasterized at the physical
+    /// resolution, so that it remains sharp on high DPI displays. Note that
+    /// the viewport size, and the output of [Graphics2D::capture], remain in
+    /// physical pixels.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.renderer.renderer.set_scale_factor(scale_factor as f32)
+    }
+
+    /// Returns the number of physical pixels per `Graphics2D` coordinate unit.
+    /// See [GLRenderer::set_scale_factor].
+    #[inline]
+    #[must_use]
+    pub fn scale_factor(&self) -> f64 {
+        self.renderer.renderer.scale_factor() as f64
+    }
+
+    /// Sets a fixed virtual resolution for drawing, for example `320x180` for
+    /// a pixel-art game. Pass `None` to draw directly to the window again
+    /// (the default).
+    ///
+    /// When a virtual resolution is set, each frame is drawn into an offscreen
+    /// buffer of exactly this size. At the end of the frame, the buffer is
+    /// upscaled to the window by the largest integer factor which fits, using
+    /// nearest-neighbour filtering, and centered with black bars filling the
+    /// remaining space. The scale factor set using
+    /// [GLRenderer::set_scale_factor] has no effect in this mode.
+    ///
+    /// Window positions (such as the mouse position) can be converted into
+    /// virtual coordinates using
+    /// [GLRenderer::convert_window_position_to_virtual].
+    pub fn set_virtual_resolution(
+        &mut self,
+        size: Option<UVec2>,
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        self.renderer.renderer.set_virtual_resolution(size)
+    }
+
+    /// Returns the virtual resolution set using
+    /// [GLRenderer::set_virtual_resolution], if any.
+    #[inline]
+    #[must_use]
+    pub fn virtual_resolution(&self) -> Option<UVec2> {
+        self.renderer.renderer.virtual_resolution()
+    }
+
+    /// Converts a position in the window (in physical pixels) into the
+    /// coordinate space of the virtual resolution. If no virtual resolution
+    /// is set, the position is returned unchanged.
+    ///
+    /// Positions in the black bars around the upscaled image will map to
+    /// coordinates outside the virtual resolution.
+    #[must_use]
+    pub fn convert_window_position_to_virtual(&self, position: Vec2) -> Vec2 {
+        match self.renderer.renderer.virtual_resolution_area() {
+            None => position,
+            Some(area) => (position - area.top_left) / self.virtual_resolution_scale(),
+        }
+    }
+
+    /// Converts a position in the coordinate space of the virtual resolution
+    /// into a position in the window (in physical pixels). This is the
+    /// inverse of [GLRenderer::convert_window_position_to_virtual].
+    #[must_use]
+    pub fn convert_virtual_position_to_window(&self, position: Vec2) -> Vec2 {
+        match self.renderer.renderer.virtual_resolution_area() {
+            None => position,
+            Some(area) => position * self.virtual_resolution_scale() + area.top_left,
+        }
+    }
+
+    /// The integer factor by which the virtual resolution is upscaled, or
+    /// `1.0` if no virtual resolution is set.
+    #[must_use]
+    pub(crate) fn virtual_resolution_scale(&self) -> f32 {
+        match (
+            self.renderer.renderer.virtual_resolution_area(),
+            self.renderer.renderer.virtual_resolution(),
+        ) {
+            (Some(area), Some(size)) => area.width() / size.x as f32,
+            _ => 1.0,
+        }
+    }
+
+    /// Sets the chain of full-screen effects which are applied to each frame.
+    /// See [Graphics2D::set_post_processing].
+    pub fn set_post_processing(&mut self, passes: Vec<PostProcessPass>) {
+        self.renderer.renderer.set_post_processing(passes);
+    }
+
     /// Creates a new [ImageHandle] from the specified raw pixel data.
     ///
     /// The data provided in the `data` parameter must be in the format
@@ -602,11 +775,165 @@ impl GLRenderer {
     /// window context buffers if necessary.
     #[inline]
     pub fn draw_frame<F: FnOnce(&mut Graphics2D) -> R, R>(&mut self, callback: F) -> R {
+        self.renderer.renderer.begin_frame();
         self.renderer.set_clip(None);
         let result = callback(&mut self.renderer);
         self.renderer.renderer.finish_frame();
         result
     }
+
+    /// Returns statistics about the most recent frame drawn using
+    /// [GLRenderer::draw_frame].
+    #[inline]
+    #[must_use]
+    pub fn last_frame_stats(&self) -> FrameStats {
+        self.renderer.last_frame_stats()
+    }
+
+    /// Enables or disables the debug overlay. When enabled, a panel is drawn
+    /// on top of each frame, showing a graph of recent frame times, the
+    /// [FrameStats] for the frame, and the textures used by the glyph cache.
+    ///
+    /// The overlay uses a built-in bitmap font, so it doesn't need any fonts
+    /// to be loaded. It is drawn after any post-processing or upscaling, and
+    /// isn't included in [GLRenderer::last_frame_stats]. The default is
+    /// disabled.
+    ///
+    /// When using a [Window], the overlay can be toggled with a key using
+    /// [window::WindowCreationOptions::with_debug_overlay_hotkey].
+    pub fn set_debug_overlay_enabled(&mut self, enabled: bool) {
+        self.renderer.renderer.set_debug_overlay_enabled(enabled);
+    }
+
+    /// Returns true if the debug overlay is enabled. See
+    /// [GLRenderer::set_debug_overlay_enabled].
+    #[inline]
+    #[must_use]
+    pub fn is_debug_overlay_enabled(&self) -> bool {
+        self.renderer.renderer.is_debug_overlay_enabled()
+    }
+
+    /// Draws a frame in the same way as [GLRenderer::draw_frame], and also
+    /// records the drawing operations as an SVG document, which is returned
+    /// alongside the result of the callback.
+    ///
+    /// See [Graphics2D::begin_svg_export] for details of how each operation
+    /// is converted.
+    pub fn draw_frame_to_svg<F: FnOnce(&mut Graphics2D) -> R, R>(
+        &mut self,
+        callback: F,
+    ) -> (R, String) {
+        self.draw_frame(|graphics| {
+            graphics.begin_svg_export();
+            let result = callback(graphics);
+            let svg = graphics.finish_svg_export().unwrap_or_default();
+            (result, svg)
+        })
+    }
+
+    /// Sets whether images created from now on keep a copy of their pixel
+    /// data in memory. Such images are re-uploaded automatically when the
+    /// GL context is restored after a context loss, at the cost of the extra
+    /// memory. The default is disabled.
+    ///
+    /// See [GLRenderer::notify_context_restored].
+    #[cfg(feature = "image-loading")]
+    pub fn set_retain_image_data(&mut self, retain: bool) {
+        self.renderer.set_retain_image_data(retain);
+    }
+
+    /// Notifies the renderer that the GL context has been lost, for example
+    /// due to a `webglcontextlost` event, or a GPU driver reset. Until
+    /// [GLRenderer::notify_context_restored] is called, drawing has no
+    /// effect.
+    ///
+    /// When using a [Window], this is handled automatically, and
+    /// [window::WindowHandler::on_context_lost] is invoked.
+    pub fn notify_context_lost(&mut self) {
+        self.context.mark_invalid();
+    }
+
+    /// Returns true if [GLRenderer::notify_context_lost] has been called, and
+    /// the context hasn't been restored since.
+    #[inline]
+    #[must_use]
+    pub fn is_context_lost(&self) -> bool {
+        !self.context.is_valid()
+    }
+
+    /// Notifies the renderer that the GL context has been restored after a
+    /// context loss. The renderer's shaders and glyph cache are recreated.
+    ///
+    /// Images created while [GLRenderer::set_retain_image_data] was enabled
+    /// are re-uploaded automatically. Any other [ImageHandle] no longer
+    /// refers to valid image data, and should be recreated.
+    ///
+    /// When using a [Window], this is handled automatically, and
+    /// [window::WindowHandler::on_context_restored] is invoked.
+    pub fn notify_context_restored(
+        &mut self,
+    ) -> Result<(), BacktraceError<GLRendererCreationError>> {
+        if self.context.is_valid() {
+            log::warn!("Ignoring context restore, as the context wasn't lost");
+            return Ok(());
+        }
+
+        self.context.restore().map_err(|err| {
+            GLRendererCreationError::msg_with_cause("Failed to restore GL context", err)
+        })?;
+
+        self.renderer
+            .renderer
+            .recreate_gl_resources()
+            .map_err(|err| {
+                GLRendererCreationError::msg_with_cause("Failed to recreate GL resources", err)
+            })
+    }
+
+    /// Captures the contents of the window, as left by the most recent call
+    /// to [GLRenderer::draw_frame].
+    #[cfg(all(
+        feature = "image-loading",
+        any(doc, doctest, all(feature = "windowing", not(target_arch = "wasm32")))
+    ))]
+    pub(crate) fn capture_last_frame(&mut self, format: ImageDataType) -> RawBitmapData {
+        self.renderer.capture(format)
+    }
+
+    /// Switches to a newly created context after a context loss, for cases
+    /// where the lost context can't be restored itself. This must be followed
+    /// by a call to [GLRenderer::notify_context_restored].
+    #[cfg(all(feature = "windowing", not(target_arch = "wasm32")))]
+    pub(crate) fn replace_gl_backend(
+        &mut self,
+        gl_backend: Rc<dyn GLBackend>,
+        gl_version: GLVersion,
+    ) {
+        self.context.replace_gl_backend(gl_backend, gl_version);
+    }
+
+    /// Returns the share group of the renderer's context. Images can only be
+    /// drawn by renderers in the share group which created them.
+    #[cfg(all(feature = "windowing", not(target_arch = "wasm32")))]
+    #[inline]
+    #[must_use]
+    pub(crate) fn share_group(&self) -> u64 {
+        self.context.share_group()
+    }
+
+    /// Moves the renderer into the specified share group, after its context
+    /// was created to share objects with a context in that group.
+    #[cfg(all(feature = "windowing", not(target_arch = "wasm32")))]
+    pub(crate) fn join_share_group(&self, share_group: u64) {
+        self.context.join_share_group(share_group);
+    }
+
+    /// Must be called whenever the renderer's context is made current, when
+    /// more than one context is in use.
+    #[cfg(all(feature = "windowing", not(target_arch = "wasm32")))]
+    pub(crate) fn mark_context_current(&self) {
+        self.context.mark_current();
+    }
 }
 
 impl Drop for GLRenderer {
@@ -624,9 +951,18 @@ impl Drop for GLRenderer {
 /// [GLRenderer::draw_frame] to obtain an instance.
 pub struct Graphics2D {
     renderer: Renderer2D,
+    svg: Option<SvgRecorder>,
 }
 
 impl Graphics2D {
+    /// Sets whether images created from now on keep a copy of their pixel
+    /// data in memory, so that they survive a loss of the GL context. See
+    /// [GLRenderer::set_retain_image_data].
+    #[cfg(feature = "image-loading")]
+    pub fn set_retain_image_data(&mut self, retain: bool) {
+        self.renderer.set_retain_image_data(retain);
+    }
+
     /// Creates a new [ImageHandle] from the specified raw pixel data.
     ///
     /// The data provided in the `data` parameter must be in the format
@@ -713,6 +1049,10 @@ impl Graphics2D {
 
     /// Fills the screen with the specified color.
     pub fn clear_screen(&mut self, color: Color) {
+        if let Some(svg) = &mut self.svg {
+            svg.clear_screen(color);
+        }
+
         self.renderer.clear_screen(color);
     }
 
@@ -741,6 +1081,12 @@ impl Graphics2D {
         color: Color,
         text: &FormattedTextBlock,
     ) {
+        let position = position.into();
+
+        if let Some(svg) = &mut self.svg {
+            svg.text(position, color, text, None);
+        }
+
         self.renderer.draw_text(position, color, text);
     }
 
@@ -760,6 +1106,12 @@ impl Graphics2D {
         color: Color,
         text: &FormattedTextBlock,
     ) {
+        let position = position.into();
+
+        if let Some(svg) = &mut self.svg {
+            svg.text(position, color, text, Some(&crop_window));
+        }
+
         self.renderer
             .draw_text_cropped(position, crop_window, color, text);
     }
@@ -768,6 +1120,13 @@ impl Graphics2D {
     /// pixels.
     #[cfg(feature = "shapes")]
     pub fn draw_polygon<V: Into<Vec2>>(&mut self, polygon: &Polygon, offset: V, color: Color) {
+        let offset = offset.into();
+
+        if let Some(svg) = &mut self.svg {
+            let outline: Vec<Vec2> = polygon.outline.iter().map(|v| *v + offset).collect();
+            svg.polygon(&outline, &[color]);
+        }
+
         self.renderer.draw_polygon(polygon, offset, color)
     }
 
@@ -780,6 +1139,10 @@ impl Graphics2D {
         vertex_positions_clockwise: [Vec2; 3],
         vertex_colors_clockwise: [Color; 3],
     ) {
+        if let Some(svg) = &mut self.svg {
+            svg.polygon(&vertex_positions_clockwise, &vertex_colors_clockwise);
+        }
+
         self.renderer
             .draw_triangle_three_color(vertex_positions_clockwise, vertex_colors_clockwise);
     }
@@ -806,6 +1169,16 @@ impl Graphics2D {
         image_coords_normalized: [Vec2; 3],
         image: &ImageHandle,
     ) {
+        if let Some(svg) = &mut self.svg {
+            svg.image_polygon(
+                &self.renderer,
+                &vertex_positions_clockwise,
+                &vertex_colors,
+                &image_coords_normalized,
+                image,
+            );
+        }
+
         self.renderer.draw_triangle_image_tinted(
             vertex_positions_clockwise,
             vertex_colors,
@@ -836,9 +1209,15 @@ impl Graphics2D {
         let vp = vertex_positions_clockwise;
         let vc = vertex_colors;
 
-        self.draw_triangle_three_color([vp[0], vp[1], vp[2]], [vc[0], vc[1], vc[2]]);
+        if let Some(svg) = &mut self.svg {
+            svg.polygon(&vp, &vc);
+        }
+
+        self.with_svg_paused(|graphics| {
+            graphics.draw_triangle_three_color([vp[0], vp[1], vp[2]], [vc[0], vc[1], vc[2]]);
 
-        self.draw_triangle_three_color([vp[2], vp[3], vp[0]], [vc[2], vc[3], vc[0]]);
+            graphics.draw_triangle_three_color([vp[2], vp[3], vp[0]], [vc[2], vc[3], vc[0]]);
+        });
     }
 
     /// Draws a quadrilateral with the specified color.
@@ -877,6 +1256,31 @@ impl Graphics2D {
         let vc = vertex_colors;
         let ic = image_coords_normalized;
 
+        // A single image mapping only covers the whole quad if both the
+        // positions and image coordinates form parallelograms
+        let is_parallelogram = |p: [Vec2; 4]| (p[0] + p[2]).abs_diff_eq(p[1] + p[3], 0.001);
+
+        if is_parallelogram(vp) && is_parallelogram(ic) {
+            if let Some(svg) = &mut self.svg {
+                svg.image_polygon(&self.renderer, &vp, &vc, &ic, image);
+            }
+
+            self.with_svg_paused(|graphics| {
+                graphics.draw_quad_image_tinted_triangles(vp, vc, ic, image)
+            });
+        } else {
+            self.draw_quad_image_tinted_triangles(vp, vc, ic, image);
+        }
+    }
+
+    #[cfg(feature = "image-loading")]
+    fn draw_quad_image_tinted_triangles(
+        &mut self,
+        vp: [Vec2; 4],
+        vc: [Color; 4],
+        ic: [Vec2; 4],
+        image: &ImageHandle,
+    ) {
         self.draw_triangle_image_tinted_three_color(
             [vp[0], vp[1], vp[2]],
             [vc[0], vc[1], vc[2]],
@@ -983,15 +1387,21 @@ impl Graphics2D {
     pub fn draw_rectangle(&mut self, rect: impl AsRef<Rect>, color: Color) {
         let rect = rect.as_ref();
 
-        self.draw_quad(
-            [
-                rect.top_left,
-                rect.top_right(),
-                rect.bottom_right,
-                rect.bottom_left(),
-            ],
-            color,
-        );
+        if let Some(svg) = &mut self.svg {
+            svg.rectangle(rect, color);
+        }
+
+        self.with_svg_paused(|graphics| {
+            graphics.draw_quad(
+                [
+                    rect.top_left,
+                    rect.top_right(),
+                    rect.bottom_right,
+                    rect.bottom_left(),
+                ],
+                color,
+            )
+        });
     }
 
     /// Draws a single-color line between the given points, specified in pixels.
@@ -1023,31 +1433,16 @@ impl Graphics2D {
         thickness: f32,
         color: Color,
     ) {
-        let gradient_normalized = match (end_position - start_position).try_normalize() {
+        let quad = match line_quad(start_position, end_position, thickness) {
             None => return,
-            Some(gradient) => gradient,
+            Some(quad) => quad,
         };
 
-        let gradient_thickness = gradient_normalized * (thickness / 2.0);
-
-        let offset_anticlockwise = -gradient_thickness.perp();
-        let offset_clockwise = gradient_thickness.perp();
-
-        let start_anticlockwise = start_position + offset_anticlockwise;
-        let start_clockwise = start_position + offset_clockwise;
-
-        let end_anticlockwise = end_position + offset_anticlockwise;
-        let end_clockwise = end_position + offset_clockwise;
+        if let Some(svg) = &mut self.svg {
+            svg.line(start_position, end_position, thickness, color);
+        }
 
-        self.draw_quad(
-            [
-                start_anticlockwise,
-                end_anticlockwise,
-                end_clockwise,
-                start_clockwise,
-            ],
-            color,
-        );
+        self.with_svg_paused(|graphics| graphics.draw_quad(quad, color));
     }
 
     /// Draws a circle, filled with a single color, at the specified pixel
@@ -1055,6 +1450,10 @@ impl Graphics2D {
     pub fn draw_circle<V: Into<Vec2>>(&mut self, center_position: V, radius: f32, color: Color) {
         let center_position = center_position.into();
 
+        if let Some(svg) = &mut self.svg {
+            svg.circle(center_position, radius, color);
+        }
+
         let top_left = center_position + Vec2::new(-radius, -radius);
         let top_right = center_position + Vec2::new(radius, -radius);
         let bottom_right = center_position + Vec2::new(radius, radius);
@@ -1121,6 +1520,14 @@ impl Graphics2D {
         vertex_colors: [Color; 3],
         vertex_circle_coords_normalized: [Vec2; 3],
     ) {
+        if let Some(svg) = &mut self.svg {
+            svg.circle_section(
+                vertex_positions_clockwise,
+                vertex_colors,
+                vertex_circle_coords_normalized,
+            );
+        }
+
         self.renderer.draw_circle_section(
             vertex_positions_clockwise,
             vertex_colors,
@@ -1132,9 +1539,87 @@ impl Graphics2D {
     /// coordinates. Rendering operations have no effect outside of the
     /// clipping area.
     pub fn set_clip(&mut self, rect: Option<IRect>) {
+        if let Some(svg) = &mut self.svg {
+            svg.set_clip(rect.as_ref());
+        }
+
         self.renderer.set_clip(rect);
     }
 
+    /// Draws the operations recorded in the specified [DrawList], with the
+    /// specified offset in pixels.
+    #[inline]
+    pub fn draw_list<V: Into<Vec2>>(&mut self, list: &DrawList, offset: V) {
+        list.draw(self, Affine2::from_translation(offset.into()));
+    }
+
+    /// Draws the operations recorded in the specified [DrawList], with the
+    /// specified transform applied to each position.
+    ///
+    /// See the documentation for [DrawList] for the limitations when the
+    /// transform includes rotation or scaling.
+    #[inline]
+    pub fn draw_list_transformed(&mut self, list: &DrawList, transform: Affine2) {
+        list.draw(self, transform);
+    }
+
+    /// Starts recording the subsequent drawing operations in this frame as an
+    /// SVG document, which can be retrieved using
+    /// [Graphics2D::finish_svg_export]. Drawing to the screen continues as
+    /// normal while recording. Any recording already in progress is
+    /// discarded.
+    ///
+    /// Shapes are converted to the equivalent SVG elements, text is converted
+    /// to paths using the glyph outlines, and images are embedded as PNG data
+    /// (this requires the `image-png` feature). As SVG has no equivalent of
+    /// per-vertex colors, shapes drawn with several colors are filled with
+    /// the average color. Post-processing effects are not included.
+    ///
+    /// See also [GLRenderer::draw_frame_to_svg].
+    pub fn begin_svg_export(&mut self) {
+        self.svg = Some(SvgRecorder::new(self.renderer.drawing_area_size()));
+    }
+
+    /// Stops recording drawing operations, and returns the SVG document
+    /// containing the operations since [Graphics2D::begin_svg_export] was
+    /// called. Returns `None` if no recording was in progress.
+    pub fn finish_svg_export(&mut self) -> Option<String> {
+        self.svg.take().map(SvgRecorder::finish)
+    }
+
+    /// Runs the callback without recording to the SVG export, for operations
+    /// which have already been recorded in a more suitable form.
+    fn with_svg_paused<R>(&mut self, callback: impl FnOnce(&mut Self) -> R) -> R {
+        let svg = self.svg.take();
+        let result = callback(self);
+        self.svg = svg;
+        result
+    }
+
+    /// Sets the chain of full-screen effects which are applied to each frame
+    /// after it has been drawn, in the order given. Pass an empty `Vec` to
+    /// disable post-processing (the default).
+    ///
+    /// The change takes effect from the start of the next frame. While any
+    /// passes are set, frames are drawn into an offscreen buffer, so
+    /// [Graphics2D::capture] returns the image before post-processing.
+    ///
+    /// If a virtual resolution is set (see
+    /// [GLRenderer::set_virtual_resolution]), the effects are applied at the
+    /// virtual resolution, before the frame is upscaled.
+    pub fn set_post_processing(&mut self, passes: Vec<PostProcessPass>) {
+        self.renderer.set_post_processing(passes);
+    }
+
+    /// Returns statistics about the previous frame. This is the frame before
+    /// the one currently being drawn, as the statistics for the current frame
+    /// aren't complete until it has finished.
+    #[inline]
+    #[must_use]
+    pub fn last_frame_stats(&self) -> FrameStats {
+        self.renderer.last_frame_stats()
+    }
+
     /// Captures a screenshot of the render window. The returned data contains
     /// the color of each pixel. Pixels are represented using a `u8` for each
     /// component (red, green, blue, and alpha). Use the `format` parameter to
@@ -1145,6 +1630,30 @@ impl Graphics2D {
     }
 }
 
+/// Returns the corners of the quadrilateral covered by a line, in clockwise
+/// order, or `None` if the line has zero length.
+fn line_quad(start_position: Vec2, end_position: Vec2, thickness: f32) -> Option<[Vec2; 4]> {
+    let gradient_normalized = (end_position - start_position).try_normalize()?;
+
+    let gradient_thickness = gradient_normalized * (thickness / 2.0);
+
+    let offset_anticlockwise = -gradient_thickness.perp();
+    let offset_clockwise = gradient_thickness.perp();
+
+    let start_anticlockwise = start_position + offset_anticlockwise;
+    let start_clockwise = start_position + offset_clockwise;
+
+    let end_anticlockwise = end_position + offset_anticlockwise;
+    let end_clockwise = end_position + offset_clockwise;
+
+    Some([
+        start_anticlockwise,
+        end_anticlockwise,
+        end_clockwise,
+        start_clockwise,
+    ])
+}
+
 /// Struct representing a window.
 #[cfg(any(doc, doctest, all(feature = "windowing", not(target_arch = "wasm32"))))]
 pub struct Window<UserEventType = ()>
@@ -1208,17 +1717,30 @@ impl<UserEventType: 'static> Window<UserEventType> {
         title: &str,
         options: WindowCreationOptions,
     ) -> Result<Self, BacktraceError<WindowCreationError>> {
+        let virtual_resolution = options.virtual_resolution;
+
         let window_impl = WindowGlutin::new(title, options)?;
 
-        let renderer = GLRenderer::new_with_gl_backend(
+        let mut renderer = GLRenderer::new_with_gl_backend(
             window_impl.get_inner_size_pixels(),
             window_impl.gl_backend().clone(),
-            GLVersion::OpenGL2_0,
+            window_impl.gl_version(),
         )
         .map_err(|err| {
             BacktraceError::new_with_cause(WindowCreationError::RendererCreationFailed, err)
         })?;
 
+        if virtual_resolution.is_some() {
+            renderer
+                .set_virtual_resolution(virtual_resolution)
+                .map_err(|err| {
+                    BacktraceError::new_with_cause(
+                        WindowCreationError::RendererCreationFailed,
+                        err,
+                    )
+                })?;
+        }
+
         Ok(Window {
             window_impl,
             renderer,
@@ -1249,6 +1771,515 @@ impl<UserEventType: 'static> Window<UserEventType> {
     }
 }
 
+/// A window which isn't displayed, for testing a [WindowHandler] without a
+/// display or GPU.
+///
+/// The handler receives the same callbacks as it would from a [Window], with
+/// a [window::WindowHelper] whose requests (such as changing the title, or
+/// grabbing the cursor) are stored so they can be inspected. Events are
+/// delivered by calling methods such as [HeadlessWindow::on_mouse_move] and
+/// [HeadlessWindow::on_key_down], and frames are only drawn when
+/// [HeadlessWindow::step_frame] is called.
+///
+/// Time only advances when a frame is drawn, by a fixed interval each frame,
+/// so [window::WindowHelper::frame_timing] and [WindowHandler::on_update]
+/// behave deterministically.
+///
+/// By default, drawing uses [GLRenderer::new_software], so no GL context is
+/// required. To test with a real GL implementation instead (for example,
+/// using an EGL pbuffer), use [HeadlessWindow::new_with_renderer].
+///
+/// ```rust,no_run
+/// use speedy2d::color::Color;
+/// use speedy2d::window::{VirtualKeyCode, WindowHandler, WindowHelper};
+/// use speedy2d::{Graphics2D, HeadlessWindow};
+///
+/// #[derive(Default)]
+/// struct MyHandler {
+///     paused: bool,
+/// }
+///
+/// impl WindowHandler for MyHandler {
+///     fn on_key_down(
+///         &mut self,
+///         helper: &mut WindowHelper,
+///         virtual_key_code: Option<VirtualKeyCode>,
+///         _scancode: u32,
+///     ) {
+///         if virtual_key_code == Some(VirtualKeyCode::P) {
+///             self.paused = true;
+///             helper.set_title("Paused");
+///         }
+///     }
+///
+///     fn on_draw(&mut self, _helper: &mut WindowHelper, graphics: &mut Graphics2D) {
+///         graphics.clear_screen(Color::BLUE);
+///     }
+/// }
+///
+/// let mut window = HeadlessWindow::new((320, 240), MyHandler::default()).unwrap();
+///
+/// window.on_key_down(Some(VirtualKeyCode::P), 25);
+/// window.step_frame();
+///
+/// assert!(window.handler().paused);
+/// assert_eq!(window.title(), "Paused");
+/// ```
+#[cfg(any(doc, doctest, all(feature = "windowing", not(target_arch = "wasm32"))))]
+pub struct HeadlessWindow<H, UserEventType = ()>
+where
+    UserEventType: 'static,
+    H: WindowHandler<UserEventType>,
+{
+    inner: HeadlessWindowImpl<UserEventType, H>,
+    frame_interval: Duration,
+}
+
+#[cfg(any(doc, doctest, all(feature = "windowing", not(target_arch = "wasm32"))))]
+impl<H: WindowHandler<()>> HeadlessWindow<H, ()> {
+    /// Creates a headless window of the specified size, drawing using a
+    /// software renderer, and invokes [WindowHandler::on_start].
+    pub fn new<S: Into<UVec2>>(
+        size_pixels: S,
+        handler: H,
+    ) -> Result<Self, BacktraceError<WindowCreationError>> {
+        let size_pixels = size_pixels.into();
+
+        Self::new_with_options(
+            size_pixels,
+            WindowCreationOptions::new_windowed(WindowSize::PhysicalPixels(size_pixels), None),
+            handler,
+        )
+    }
+
+    /// Creates a headless window with the specified options. Options which
+    /// only affect a real window (such as its position, or vsync) are
+    /// ignored.
+    pub fn new_with_options<S: Into<UVec2>>(
+        size_pixels: S,
+        options: WindowCreationOptions,
+        handler: H,
+    ) -> Result<Self, BacktraceError<WindowCreationError>> {
+        Self::new_with_user_events(size_pixels, options, handler)
+    }
+}
+
+#[cfg(any(doc, doctest, all(feature = "windowing", not(target_arch = "wasm32"))))]
+impl<H, UserEventType> HeadlessWindow<H, UserEventType>
+where
+    UserEventType: 'static,
+    H: WindowHandler<UserEventType>,
+{
+    /// Creates a headless window with the specified options, with support for
+    /// user events. See [HeadlessWindow::new_with_options].
+    pub fn new_with_user_events<S: Into<UVec2>>(
+        size_pixels: S,
+        options: WindowCreationOptions,
+        handler: H,
+    ) -> Result<Self, BacktraceError<WindowCreationError>> {
+        let size_pixels = size_pixels.into();
+
+        let renderer = GLRenderer::new_software(size_pixels).map_err(|err| {
+            BacktraceError::new_with_cause(WindowCreationError::RendererCreationFailed, err)
+        })?;
+
+        Self::new_with_renderer(renderer, size_pixels, options, handler)
+    }
+
+    /// Creates a headless window which draws using the specified renderer,
+    /// for example one created using [GLRenderer::new_for_gl_context] for an
+    /// offscreen GL context.
+    pub fn new_with_renderer<S: Into<UVec2>>(
+        renderer: GLRenderer,
+        size_pixels: S,
+        options: WindowCreationOptions,
+        handler: H,
+    ) -> Result<Self, BacktraceError<WindowCreationError>> {
+        let inner = HeadlessWindowImpl::new(size_pixels.into(), 1.0, renderer, options, handler)
+            .map_err(|err| {
+                BacktraceError::new_with_cause(WindowCreationError::RendererCreationFailed, err)
+            })?;
+
+        let mut result = HeadlessWindow {
+            inner,
+            frame_interval: Duration::from_secs(1) / 60,
+        };
+
+        result.inner.process_pending_requests();
+
+        Ok(result)
+    }
+
+    /// Sets the amount of time which passes between each frame drawn by
+    /// [HeadlessWindow::step_frame]. The default is 1/60th of a second.
+    pub fn set_frame_interval(&mut self, frame_interval: Duration) {
+        self.frame_interval = frame_interval;
+    }
+
+    /// Advances time by the frame interval, and draws a frame, whether or not
+    /// the handler requested a redraw. Any events resulting from the
+    /// handler's requests (such as user events, or cursor grab
+    /// notifications) are delivered before and after the frame.
+    pub fn step_frame(&mut self) {
+        self.inner.process_pending_requests();
+        self.inner
+            .draw_frame_at(self.inner.elapsed() + self.frame_interval);
+        self.inner.process_pending_requests();
+    }
+
+    /// Calls [HeadlessWindow::step_frame] the specified number of times.
+    pub fn step_frames(&mut self, count: u32) {
+        for _ in 0..count {
+            self.step_frame();
+        }
+    }
+
+    /// The amount of time which has passed since the window was created,
+    /// according to the frames drawn so far.
+    #[inline]
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.inner.elapsed()
+    }
+
+    fn deliver(&mut self, event: RecordedEvent) {
+        self.inner.deliver(event);
+        self.inner.process_pending_requests();
+    }
+
+    /// Delivers a user event to the handler.
+    pub fn on_user_event(&mut self, user_event: UserEventType) {
+        self.inner.deliver_user_event(user_event);
+        self.inner.process_pending_requests();
+    }
+
+    /// Resizes the window, and notifies the handler.
+    pub fn on_resize<S: Into<UVec2>>(&mut self, size_pixels: S) {
+        self.deliver(RecordedEvent::Resize(size_pixels.into()))
+    }
+
+    /// Changes the scale factor of the window, and notifies the handler.
+    pub fn on_scale_factor_changed(&mut self, scale_factor: f64) {
+        self.deliver(RecordedEvent::ScaleFactorChanged(scale_factor))
+    }
+
+    /// Notifies the handler that the mouse grab status has changed. This
+    /// happens automatically when the handler calls
+    /// [window::WindowHelper::set_cursor_grab].
+    pub fn on_mouse_grab_status_changed(&mut self, mouse_grabbed: bool) {
+        self.deliver(RecordedEvent::MouseGrabStatusChanged(mouse_grabbed))
+    }
+
+    /// Notifies the handler that the fullscreen status has changed. This
+    /// happens automatically when the handler calls
+    /// [window::WindowHelper::set_fullscreen_mode].
+    pub fn on_fullscreen_status_changed(&mut self, fullscreen: bool) {
+        self.deliver(RecordedEvent::FullscreenStatusChanged(fullscreen))
+    }
+
+    /// Moves the mouse to the specified position, in physical pixels.
+    pub fn on_mouse_move<P: Into<Vec2>>(&mut self, position: P) {
+        self.deliver(RecordedEvent::MouseMove(position.into()))
+    }
+
+    /// Presses a mouse button.
+    pub fn on_mouse_button_down(&mut self, button: MouseButton) {
+        self.deliver(RecordedEvent::MouseButtonDown(button))
+    }
+
+    /// Releases a mouse button.
+    pub fn on_mouse_button_up(&mut self, button: MouseButton) {
+        self.deliver(RecordedEvent::MouseButtonUp(button))
+    }
+
+    /// Scrolls the mouse wheel.
+    pub fn on_mouse_wheel_scroll(&mut self, distance: MouseScrollDistance) {
+        self.deliver(RecordedEvent::MouseWheelScroll(distance))
+    }
+
+    /// Gives or takes away keyboard focus. See
+    /// [WindowHandler::on_focus_changed].
+    pub fn on_focus_changed(&mut self, focused: bool) {
+        self.deliver(RecordedEvent::FocusChanged(focused))
+    }
+
+    /// Hides or shows the window. See [WindowHandler::on_visibility_changed].
+    /// Frames are still drawn by [HeadlessWindow::step_frame] while the
+    /// window is hidden.
+    pub fn on_visibility_changed(&mut self, visible: bool) {
+        self.deliver(RecordedEvent::VisibilityChanged(visible))
+    }
+
+    /// Performs a pinch gesture. See [WindowHandler::on_pinch].
+    pub fn on_pinch(&mut self, delta: f64) {
+        self.deliver(RecordedEvent::Pinch(delta))
+    }
+
+    /// Performs a rotation gesture. See [WindowHandler::on_rotate_gesture].
+    pub fn on_rotate_gesture(&mut self, delta: f32) {
+        self.deliver(RecordedEvent::RotateGesture(delta))
+    }
+
+    /// Performs a smart zoom gesture. See [WindowHandler::on_smart_zoom].
+    pub fn on_smart_zoom(&mut self) {
+        self.deliver(RecordedEvent::SmartZoom)
+    }
+
+    /// Drags a file over the window. See [WindowHandler::on_file_hover].
+    pub fn on_file_hover<P: Into<PathBuf>>(&mut self, path: P) {
+        self.deliver(RecordedEvent::FileHover(path.into()))
+    }
+
+    /// Stops dragging files over the window. See
+    /// [WindowHandler::on_file_hover_cancelled].
+    pub fn on_file_hover_cancelled(&mut self) {
+        self.deliver(RecordedEvent::FileHoverCancelled)
+    }
+
+    /// Drops a file onto the window. See [WindowHandler::on_file_drop].
+    pub fn on_file_drop<P: Into<PathBuf>>(&mut self, path: P) {
+        self.deliver(RecordedEvent::FileDrop(path.into()))
+    }
+
+    /// Drops a file onto the window, as a [WebCanvas] would. See
+    /// [WindowHandler::on_file_drop_data].
+    pub fn on_file_drop_data<S: Into<String>>(&mut self, name: S, bytes: Vec<u8>) {
+        self.deliver(RecordedEvent::FileDropData(name.into(), bytes))
+    }
+
+    /// Delivers text read from the clipboard, as if it was requested using
+    /// [window::WindowHelper::request_clipboard_text]. See
+    /// [WindowHandler::on_clipboard_text].
+    pub fn on_clipboard_text<S: Into<String>>(&mut self, text: S) {
+        self.deliver(RecordedEvent::ClipboardText(text.into()))
+    }
+
+    /// Delivers an image read from the clipboard, with RGBA pixels, as if it
+    /// was requested using [window::WindowHelper::request_clipboard_image].
+    /// See [WindowHandler::on_clipboard_image].
+    #[cfg(feature = "image-loading")]
+    pub fn on_clipboard_image<S: Into<UVec2>>(&mut self, size: S, pixels: Vec<u8>) {
+        self.deliver(RecordedEvent::ClipboardImage(size.into(), pixels))
+    }
+
+    /// Touches the window with a finger, or moves or lifts the finger. See
+    /// [WindowHandler::on_touch].
+    pub fn on_touch<P: Into<Vec2>>(
+        &mut self,
+        id: TouchId,
+        phase: TouchPhase,
+        position: P,
+        force: Option<f32>,
+    ) {
+        self.deliver(RecordedEvent::Touch(id, phase, position.into(), force))
+    }
+
+    /// Presses a key.
+    pub fn on_key_down(&mut self, virtual_key_code: Option<VirtualKeyCode>, scancode: KeyScancode) {
+        self.deliver(RecordedEvent::KeyDown(virtual_key_code, scancode))
+    }
+
+    /// Releases a key.
+    pub fn on_key_up(&mut self, virtual_key_code: Option<VirtualKeyCode>, scancode: KeyScancode) {
+        self.deliver(RecordedEvent::KeyUp(virtual_key_code, scancode))
+    }
+
+    /// Types a character.
+    pub fn on_keyboard_char(&mut self, unicode_codepoint: char) {
+        self.deliver(RecordedEvent::KeyboardChar(unicode_codepoint))
+    }
+
+    /// Delivers an event from an input method editor. See
+    /// [WindowHandler::on_ime].
+    pub fn on_ime(&mut self, event: ImeEvent) {
+        self.deliver(RecordedEvent::Ime(event))
+    }
+
+    /// Changes the state of the modifier keys.
+    pub fn on_keyboard_modifiers_changed(&mut self, state: ModifiersState) {
+        self.deliver(RecordedEvent::KeyboardModifiersChanged(state))
+    }
+
+    /// Simulates the loss of the graphics context.
+    pub fn on_context_lost(&mut self) {
+        self.deliver(RecordedEvent::ContextLost)
+    }
+
+    /// Simulates the restoration of the graphics context, after
+    /// [HeadlessWindow::on_context_lost].
+    pub fn on_context_restored(&mut self) {
+        self.deliver(RecordedEvent::ContextRestored)
+    }
+
+    /// Creates a [window::UserEventSender], which can be used to post custom
+    /// events to the window. The events are delivered on the next call to
+    /// any method of the `HeadlessWindow` which delivers events.
+    pub fn create_user_event_sender(&self) -> UserEventSender<UserEventType> {
+        self.inner.headless().create_user_event_sender()
+    }
+
+    /// Returns the handler.
+    #[inline]
+    #[must_use]
+    pub fn handler(&self) -> &H {
+        self.inner.handler()
+    }
+
+    /// Returns the handler.
+    #[inline]
+    #[must_use]
+    pub fn handler_mut(&mut self) -> &mut H {
+        self.inner.handler_mut()
+    }
+
+    /// Returns the helper which is passed to the handler.
+    #[inline]
+    #[must_use]
+    pub fn helper(&mut self) -> &mut WindowHelper<UserEventType> {
+        self.inner.helper()
+    }
+
+    /// Returns the renderer which the handler draws to.
+    #[inline]
+    #[must_use]
+    pub fn renderer_mut(&mut self) -> &mut GLRenderer {
+        self.inner.renderer_mut()
+    }
+
+    /// Captures the output of the most recently drawn frame. See
+    /// [Graphics2D::capture].
+    #[cfg(feature = "image-loading")]
+    pub fn capture(&mut self, format: ImageDataType) -> RawBitmapData {
+        self.inner.renderer_mut().capture_last_frame(format)
+    }
+
+    /// Returns true if the handler has requested a redraw since the last
+    /// frame was drawn.
+    #[inline]
+    #[must_use]
+    pub fn is_redraw_requested(&self) -> bool {
+        self.inner.headless().is_redraw_requested()
+    }
+
+    /// Returns true if the handler has called
+    /// [window::WindowHelper::terminate_loop]. No further callbacks are given
+    /// once this has happened.
+    #[inline]
+    #[must_use]
+    pub fn is_terminate_requested(&self) -> bool {
+        self.inner.headless().is_terminate_requested()
+    }
+
+    /// Returns true if the handler has called
+    /// [window::WindowHelper::close_window]. No further callbacks are given
+    /// once this has happened.
+    #[inline]
+    #[must_use]
+    pub fn is_close_requested(&self) -> bool {
+        self.inner.headless().is_close_requested()
+    }
+
+    /// Returns the window title most recently set by the handler.
+    #[inline]
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.inner.headless().title()
+    }
+
+    /// Returns the current size of the window in pixels.
+    #[inline]
+    #[must_use]
+    pub fn size_pixels(&self) -> UVec2 {
+        self.inner.headless().get_size_pixels()
+    }
+
+    /// Returns the current scale factor of the window.
+    #[inline]
+    #[must_use]
+    pub fn scale_factor(&self) -> f64 {
+        self.inner.headless().get_scale_factor()
+    }
+
+    /// Returns the window position most recently set by the handler, in
+    /// pixels.
+    #[inline]
+    #[must_use]
+    pub fn position_pixels(&self) -> Option<IVec2> {
+        self.inner.headless().position_pixels()
+    }
+
+    /// Returns true unless the handler has hidden the cursor.
+    #[inline]
+    #[must_use]
+    pub fn is_cursor_visible(&self) -> bool {
+        self.inner.headless().is_cursor_visible()
+    }
+
+    /// Returns true if the handler has grabbed the cursor.
+    #[inline]
+    #[must_use]
+    pub fn is_cursor_grabbed(&self) -> bool {
+        self.inner.headless().is_cursor_grabbed()
+    }
+
+    /// Returns true unless the handler has made the window non-resizable.
+    #[inline]
+    #[must_use]
+    pub fn is_resizable(&self) -> bool {
+        self.inner.headless().is_resizable()
+    }
+
+    /// Returns true if the handler has allowed input method editors. See
+    /// [window::WindowHelper::set_ime_allowed].
+    #[inline]
+    #[must_use]
+    pub fn is_ime_allowed(&self) -> bool {
+        self.inner.headless().is_ime_allowed()
+    }
+
+    /// Returns true if the handler has enabled treating Ctrl+wheel events as
+    /// pinch gestures. See [window::WindowHelper::set_ctrl_wheel_pinch].
+    #[inline]
+    #[must_use]
+    pub fn is_ctrl_wheel_pinch_enabled(&self) -> bool {
+        self.inner.headless().is_ctrl_wheel_pinch_enabled()
+    }
+
+    /// Returns the IME cursor area most recently set by the handler, if any,
+    /// converted into physical pixels. The area is applied when the next
+    /// frame is drawn. See [window::WindowHelper::set_ime_cursor_area].
+    #[inline]
+    #[must_use]
+    pub fn ime_cursor_area(&self) -> Option<Rect> {
+        self.inner.headless().ime_cursor_area()
+    }
+
+    /// Returns the text most recently placed on the clipboard by the handler,
+    /// if any. Requests for the clipboard contents are answered using this
+    /// text.
+    #[inline]
+    #[must_use]
+    pub fn clipboard_text(&self) -> Option<String> {
+        self.inner.headless().clipboard_text()
+    }
+
+    /// Returns the image most recently placed on the clipboard by the
+    /// handler, with RGBA pixels, if any.
+    #[cfg(feature = "image-loading")]
+    #[inline]
+    #[must_use]
+    pub fn clipboard_image(&self) -> Option<RawBitmapData> {
+        self.inner.headless().clipboard_image()
+    }
+
+    /// Returns the fullscreen mode most recently set by the handler.
+    #[inline]
+    #[must_use]
+    pub fn fullscreen_mode(&self) -> WindowFullscreenMode {
+        self.inner.headless().fullscreen_mode()
+    }
+}
+
 /// Struct representing an HTML canvas.
 #[cfg(any(doc, doctest, all(target_arch = "wasm32", feature = "windowing")))]
 pub struct WebCanvas<UserEventType = ()>
@@ -0,0 +1,766 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+//! Full-screen effects which are applied to each frame after it has been
+//! drawn.
+//!
+//! See [crate::Graphics2D::set_post_processing].
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use glam::UVec2;
+
+use crate::error::{BacktraceError, Context, ErrorMessage};
+use crate::glwrapper::*;
+
+#[cfg(feature = "image-loading")]
+use crate::image::ImageHandle;
+
+/// A single full-screen effect in a post-processing chain.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum PostProcessPass {
+    /// Blurs the image. The radius is specified in pixels.
+    GaussianBlur {
+        /// The blur radius, in pixels.
+        radius: f32,
+    },
+
+    /// Makes bright areas of the image glow.
+    Bloom {
+        /// Parts of the image with a luminance above this value (between
+        /// `0.0` and `1.0`) will glow.
+        threshold: f32,
+        /// How strongly the glow is added back to the image.
+        intensity: f32,
+        /// The radius of the glow, in pixels.
+        radius: f32,
+    },
+
+    /// Darkens the corners and edges of the image.
+    Vignette {
+        /// How dark the corners become, between `0.0` and `1.0`.
+        strength: f32,
+        /// The distance from the center (where `1.0` is the corners) at which
+        /// the darkening starts.
+        radius: f32,
+    },
+
+    /// Remaps the colors of the image using a lookup table.
+    ///
+    /// The lookup table image must be a horizontal strip of `N` square tiles
+    /// of `N`x`N` pixels (for example, `256x16` for `N = 16`). Within each
+    /// tile, red increases from left to right, and green from top to bottom.
+    /// Blue increases from one tile to the next.
+    #[cfg(feature = "image-loading")]
+    ColorGrading {
+        /// The lookup table.
+        lut: ImageHandle,
+        /// How strongly to apply the grading, between `0.0` and `1.0`.
+        strength: f32,
+    },
+
+    /// Simulates the scanlines and shadow mask of a CRT display.
+    CrtScanlines {
+        /// How dark the scanlines are, between `0.0` and `1.0`.
+        intensity: f32,
+        /// The distance between scanlines, in pixels.
+        line_spacing: f32,
+    },
+
+    /// A user-defined effect. See [CustomPostProcessPass].
+    Custom(CustomPostProcessPass),
+}
+
+/// A post-processing pass using a user-defined fragment shader.
+///
+/// The shader source must define a function with the signature
+/// `vec4 effect(vec2 tex_coord)`, which returns the output color for the
+/// specified texture coordinate. The following are available to the
+/// function:
+///
+/// * `SAMPLE(in_Texture, coord)`: samples the input image at the given
+///   texture coordinate.
+/// * `in_TexelSize` (`vec2`): the size of one pixel in texture coordinates.
+/// * `in_Param0` to `in_Param3` (`float`): the values set using
+///   [CustomPostProcessPass::with_params].
+///
+/// The source is compiled for whichever GLSL version the renderer is using
+/// (`330 core` or `110` on desktop, `300 es` on WebGL 2, and `100` on
+/// OpenGL ES 2.0 and WebGL 1), so it should avoid features specific to any
+/// of them.
+///
+/// The compiled shader is cached based on its source code, so it's fine to
+/// recreate this struct every frame (for example to animate the
+/// parameters).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomPostProcessPass {
+    source: Rc<str>,
+    params: [f32; 4],
+}
+
+impl CustomPostProcessPass {
+    /// Creates a new pass using the specified shader source. See the struct
+    /// documentation for details.
+    #[inline]
+    #[must_use]
+    pub fn new<S: AsRef<str>>(source: S) -> Self {
+        CustomPostProcessPass {
+            source: Rc::from(source.as_ref()),
+            params: [0.0; 4],
+        }
+    }
+
+    /// Sets the values of the `in_Param0` to `in_Param3` uniforms.
+    #[inline]
+    #[must_use]
+    pub fn with_params(mut self, params: [f32; 4]) -> Self {
+        self.params = params;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum ProgramKey {
+    Blur,
+    BrightPass,
+    BloomCombine,
+    Vignette,
+    #[cfg(feature = "image-loading")]
+    ColorGrading,
+    Crt,
+    Custom(Rc<str>),
+}
+
+impl ProgramKey {
+    fn fragment_body(&self) -> &str {
+        match self {
+            ProgramKey::Blur => include_str!("shaders/pp_blur.glsl"),
+            ProgramKey::BrightPass => include_str!("shaders/pp_bright_pass.glsl"),
+            ProgramKey::BloomCombine => include_str!("shaders/pp_bloom_combine.glsl"),
+            ProgramKey::Vignette => include_str!("shaders/pp_vignette.glsl"),
+            #[cfg(feature = "image-loading")]
+            ProgramKey::ColorGrading => include_str!("shaders/pp_color_grading.glsl"),
+            ProgramKey::Crt => include_str!("shaders/pp_crt.glsl"),
+            ProgramKey::Custom(source) => source,
+        }
+    }
+}
+
+/// The second texture which a stage samples from, if any.
+enum SecondaryTexture {
+    None,
+    /// The input to the current `PostProcessPass`.
+    PassInput,
+    Texture(GLTexture),
+}
+
+/// A single draw of a full-screen quad. Some passes require several stages.
+struct Stage {
+    program: ProgramKey,
+    params: [f32; 4],
+    secondary: SecondaryTexture,
+}
+
+impl PostProcessPass {
+    fn stages(&self) -> Vec<Stage> {
+        let stage = |program, params| Stage {
+            program,
+            params,
+            secondary: SecondaryTexture::None,
+        };
+
+        match self {
+            PostProcessPass::GaussianBlur { radius } => vec![
+                stage(ProgramKey::Blur, [1.0, 0.0, *radius, 0.0]),
+                stage(ProgramKey::Blur, [0.0, 1.0, *radius, 0.0]),
+            ],
+
+            PostProcessPass::Bloom {
+                threshold,
+                intensity,
+                radius,
+            } => vec![
+                stage(ProgramKey::BrightPass, [*threshold, 0.0, 0.0, 0.0]),
+                stage(ProgramKey::Blur, [1.0, 0.0, *radius, 0.0]),
+                stage(ProgramKey::Blur, [0.0, 1.0, *radius, 0.0]),
+                Stage {
+                    program: ProgramKey::BloomCombine,
+                    params: [*intensity, 0.0, 0.0, 0.0],
+                    secondary: SecondaryTexture::PassInput,
+                },
+            ],
+
+            PostProcessPass::Vignette { strength, radius } => {
+                vec![stage(ProgramKey::Vignette, [*strength, *radius, 0.0, 0.0])]
+            }
+
+            #[cfg(feature = "image-loading")]
+            PostProcessPass::ColorGrading { lut, strength } => vec![Stage {
+                program: ProgramKey::ColorGrading,
+                params: [*strength, lut.size().y as f32, 0.0, 0.0],
+                secondary: SecondaryTexture::Texture(lut.texture.clone()),
+            }],
+
+            PostProcessPass::CrtScanlines {
+                intensity,
+                line_spacing,
+            } => vec![stage(
+                ProgramKey::Crt,
+                [*intensity, line_spacing.max(1.0), 0.0, 0.0],
+            )],
+
+            PostProcessPass::Custom(custom) => vec![stage(
+                ProgramKey::Custom(custom.source.clone()),
+                custom.params,
+            )],
+        }
+    }
+}
+
+struct PostProcessProgram {
+    program: Rc<GLProgram>,
+    position_buffer: GLBuffer,
+    texture: Option<GLUniformHandle>,
+    texture2: Option<GLUniformHandle>,
+    texel_size: Option<GLUniformHandle>,
+    params: [Option<GLUniformHandle>; 4],
+}
+
+impl PostProcessProgram {
+    const ATTR_NAME_POSITION: &'static str = "in_Position";
+
+    const ALL_ATTRIBUTES: [&'static str; 1] = [PostProcessProgram::ATTR_NAME_POSITION];
+
+    /// Two triangles covering the whole viewport, in clip space.
+    const QUAD_VERTICES: [f32; 12] = [
+        -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, 1.0, 1.0, -1.0, 1.0, -1.0, -1.0,
+    ];
+
+    fn new(
+        context: &GLContextManager,
+        key: &ProgramKey,
+    ) -> Result<Self, BacktraceError<ErrorMessage>> {
+        let (vertex_shader_src, prelude, main) = match context.version() {
+            GLVersion::OpenGL2_0 => (
+                include_str!("shaders/pp_vertex_v110.glsl"),
+                include_str!("shaders/pp_fragment_prelude_v110.glsl"),
+                include_str!("shaders/pp_fragment_main_v110.glsl"),
+            ),
+            GLVersion::OpenGL3_3Core => (
+                include_str!("shaders/pp_vertex_v330.glsl"),
+                include_str!("shaders/pp_fragment_prelude_v330.glsl"),
+                include_str!("shaders/pp_fragment_main_v330.glsl"),
+            ),
+            GLVersion::WebGL2_0 => (
+                include_str!("shaders/pp_vertex_v300es.glsl"),
+                include_str!("shaders/pp_fragment_prelude_v300es.glsl"),
+                include_str!("shaders/pp_fragment_main_v300es.glsl"),
+            ),
+            GLVersion::OpenGLES2_0 | GLVersion::WebGL1_0 => (
+                include_str!("shaders/pp_vertex_v100.glsl"),
+                include_str!("shaders/pp_fragment_prelude_v100.glsl"),
+                include_str!("shaders/pp_fragment_main_v100.glsl"),
+            ),
+        };
+
+        let fragment_shader_src = format!("{}\n{}\n{}", prelude, key.fragment_body(), main);
+
+        let vertex_shader = context
+            .new_shader(GLShaderType::Vertex, vertex_shader_src)
+            .context("Failed to create post-processing vertex shader")?;
+
+        let fragment_shader = context
+            .new_shader(GLShaderType::Fragment, &fragment_shader_src)
+            .context("Failed to create post-processing fragment shader")?;
+
+        let program = context
+            .new_program(
+                &vertex_shader,
+                &fragment_shader,
+                &PostProcessProgram::ALL_ATTRIBUTES,
+            )
+            .context("Failed to create post-processing program")?;
+
+        let position_buffer = context
+            .new_buffer(
+                GLBufferTarget::Array,
+                2,
+                program
+                    .get_attribute_handle(PostProcessProgram::ATTR_NAME_POSITION)
+                    .context("Failed to get attribute POSITION")?,
+            )
+            .context("Failed to create buffer for attribute POSITION")?;
+
+        // Uniforms which aren't used by the shader may be optimized away, so
+        // they're all optional.
+        let uniform = |name| program.get_uniform_handle(context, name).ok();
+
+        Ok(PostProcessProgram {
+            texture: uniform("in_Texture"),
+            texture2: uniform("in_Texture2"),
+            texel_size: uniform("in_TexelSize"),
+            params: [
+                uniform("in_Param0"),
+                uniform("in_Param1"),
+                uniform("in_Param2"),
+                uniform("in_Param3"),
+            ],
+            program,
+            position_buffer,
+        })
+    }
+
+    fn draw(
+        &mut self,
+        context: &GLContextManager,
+        size: UVec2,
+        source: &GLTexture,
+        secondary: Option<&GLTexture>,
+        params: &[f32; 4],
+    ) {
+        context.use_program(&self.program);
+
+        context.bind_texture(source);
+
+        if let Some(texture) = &self.texture {
+            texture.set_value_int(context, 0);
+        }
+
+        if let (Some(texture2), Some(secondary)) = (&self.texture2, secondary) {
+            texture2.set_value_int(context, 1);
+            context.bind_secondary_texture(Some(secondary));
+        }
+
+        if let Some(texel_size) = &self.texel_size {
+            texel_size.set_value_vec2(context, 1.0 / size.x as f32, 1.0 / size.y as f32);
+        }
+
+        for (handle, value) in self.params.iter().zip(params.iter()) {
+            if let Some(handle) = handle {
+                handle.set_value_float(context, *value);
+            }
+        }
+
+        self.position_buffer
+            .set_data(context, &PostProcessProgram::QUAD_VERTICES);
+
+        context.draw_triangles(GLBlendEnabled::Disabled, 6);
+
+        if secondary.is_some() {
+            context.bind_secondary_texture(None);
+        }
+    }
+}
+
+/// Runs the chain of post-processing passes at the end of each frame.
+///
+/// While the chain is active, the frame is drawn into an offscreen buffer.
+/// Each stage then draws a full-screen quad into the next buffer, sampling
+/// from the previous one, and the final stage draws into the real output.
+pub(crate) struct PostProcessor {
+    context: GLContextManager,
+    passes: Vec<PostProcessPass>,
+    pending_passes: Option<Vec<PostProcessPass>>,
+    size: UVec2,
+    buffers: Vec<GLFramebuffer>,
+    programs: HashMap<ProgramKey, PostProcessProgram>,
+}
+
+impl PostProcessor {
+    /// Three buffers are needed, as some passes refer back to their input
+    /// while ping-ponging between the other two.
+    const BUFFER_COUNT: usize = 3;
+
+    pub(crate) fn new(context: &GLContextManager) -> Self {
+        PostProcessor {
+            context: context.clone(),
+            passes: Vec::new(),
+            pending_passes: None,
+            size: UVec2::ZERO,
+            buffers: Vec::new(),
+            programs: HashMap::new(),
+        }
+    }
+
+    /// Sets the chain of passes, which will be used from the start of the
+    /// next frame.
+    pub(crate) fn set_passes(&mut self, passes: Vec<PostProcessPass>) {
+        self.pending_passes = Some(passes);
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.passes.is_empty()
+    }
+
+    /// Applies any pending change to the chain. This must only be called
+    /// between frames.
+    pub(crate) fn apply_pending_passes(&mut self) {
+        if let Some(passes) = self.pending_passes.take() {
+            self.passes = passes;
+
+            // Compiling the programs up front means that if one fails, the
+            // frame is drawn without post-processing, rather than being
+            // left half-processed
+            if self.context.is_valid() {
+                if let Err(err) = self.prepare_programs() {
+                    log::error!("Disabling post-processing: {:?}", err);
+                    self.passes.clear();
+                }
+            }
+        }
+
+        if !self.is_enabled() {
+            // Free the buffers
+            self.buffers.clear();
+        }
+    }
+
+    /// Discards the buffers and compiled programs, which are recreated when
+    /// next needed. Used after a context loss, when they no longer exist.
+    pub(crate) fn clear_gl_resources(&mut self) {
+        self.buffers.clear();
+        self.programs.clear();
+        self.size = UVec2::ZERO;
+    }
+
+    /// Ensures the buffers match the size of the render target, and returns
+    /// the framebuffer which the frame should be drawn into, or `None` if
+    /// post-processing is disabled.
+    pub(crate) fn input_framebuffer(&mut self, size: UVec2) -> Option<GLFramebuffer> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        match self.ensure_buffers(size) {
+            Ok(()) => Some(self.buffers[0].clone()),
+            Err(err) => {
+                log::error!("Disabling post-processing: {:?}", err);
+                self.passes.clear();
+                self.buffers.clear();
+                None
+            }
+        }
+    }
+
+    /// Compiles the programs used by the chain which don't yet exist.
+    fn prepare_programs(&mut self) -> Result<(), BacktraceError<ErrorMessage>> {
+        for pass in &self.passes {
+            for stage in pass.stages() {
+                if let Entry::Vacant(entry) = self.programs.entry(stage.program) {
+                    let program = PostProcessProgram::new(&self.context, entry.key())?;
+                    entry.insert(program);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn ensure_buffers(&mut self, size: UVec2) -> Result<(), BacktraceError<ErrorMessage>> {
+        if self.size != size {
+            self.buffers.clear();
+            self.size = size;
+        }
+
+        while self.buffers.len() < PostProcessor::BUFFER_COUNT {
+            let texture = self
+                .context
+                .new_texture()
+                .context("Failed to create post-processing texture")?;
+
+            texture
+                .set_image_data(
+                    &self.context,
+                    GLTextureImageFormatU8::RGBA,
+                    GLTextureSmoothing::Linear,
+                    &size,
+                    &vec![0; size.x as usize * size.y as usize * 4],
+                )
+                .context("Failed to allocate post-processing texture")?;
+
+            self.buffers.push(
+                self.context
+                    .new_framebuffer(&texture)
+                    .context("Failed to create post-processing framebuffer")?,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Runs each pass in turn, starting with the frame drawn into the first
+    /// buffer, and writing the final result into `output` (or the window, if
+    /// `output` is `None`).
+    pub(crate) fn run(
+        &mut self,
+        output: Option<&GLFramebuffer>,
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        if !self.is_enabled() || self.buffers.len() < PostProcessor::BUFFER_COUNT {
+            return Ok(());
+        }
+
+        let stages: Vec<Vec<Stage>> = self.passes.iter().map(PostProcessPass::stages).collect();
+        let stage_count: usize = stages.iter().map(Vec::len).sum();
+
+        let mut current = 0;
+        let mut stage_index = 0;
+
+        for pass_stages in &stages {
+            let pass_input = current;
+
+            for stage in pass_stages {
+                stage_index += 1;
+
+                let destination = if stage_index == stage_count {
+                    None
+                } else {
+                    Some(
+                        (0..PostProcessor::BUFFER_COUNT)
+                            .find(|index| *index != current && *index != pass_input)
+                            .unwrap(),
+                    )
+                };
+
+                match destination {
+                    None => self.context.bind_framebuffer(output),
+                    Some(index) => self.context.bind_framebuffer(Some(&self.buffers[index])),
+                }
+
+                let secondary = match &stage.secondary {
+                    SecondaryTexture::None => None,
+                    SecondaryTexture::PassInput => Some(self.buffers[pass_input].texture()),
+                    SecondaryTexture::Texture(texture) => Some(texture),
+                };
+
+                let program = match self.programs.entry(stage.program.clone()) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(PostProcessProgram::new(&self.context, &stage.program)?)
+                    }
+                };
+
+                program.draw(
+                    &self.context,
+                    self.size,
+                    self.buffers[current].texture(),
+                    secondary,
+                    &stage.params,
+                );
+
+                if let Some(index) = destination {
+                    current = index;
+                }
+            }
+        }
+
+        // Avoid leaving a buffer bound for sampling, as the next frame will
+        // be drawn into it
+        self.context.unbind_texture();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use glam::Vec2;
+    use glam_rect::Rect;
+
+    use super::*;
+    use crate::color::Color;
+    use crate::glbackend::types::GLuint;
+    use crate::glbackend::{create_test_renderer, GLCall, RecordingGLBackend};
+    use crate::GLRenderer;
+
+    fn draw_frame(renderer: &mut GLRenderer) {
+        renderer.draw_frame(|graphics| {
+            graphics.draw_rectangle(
+                Rect::new(Vec2::new(10.0, 20.0), Vec2::new(30.0, 40.0)),
+                Color::RED,
+            );
+        });
+    }
+
+    /// Returns the framebuffer which each draw call rendered into, where
+    /// `None` is the window.
+    fn draw_targets(calls: &[GLCall]) -> Vec<Option<GLuint>> {
+        let mut target = None;
+        let mut targets = Vec::new();
+
+        for call in calls {
+            match call {
+                GLCall::BindFramebuffer { framebuffer, .. } => target = *framebuffer,
+                GLCall::DrawArrays { .. } => targets.push(target),
+                _ => {}
+            }
+        }
+
+        targets
+    }
+
+    fn generated_framebuffers(calls: &[GLCall]) -> Vec<GLuint> {
+        calls
+            .iter()
+            .filter_map(|call| match call {
+                GLCall::GenFramebuffer(handle) => Some(*handle),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Checks that the pass builds its programs, and draws each of its stages
+    /// after the scene.
+    fn check_pass(create_pass: impl FnOnce(&mut GLRenderer) -> PostProcessPass) {
+        let (backend, mut renderer) =
+            create_test_renderer(RecordingGLBackend::new(), (64, 32), GLVersion::OpenGL2_0);
+
+        let pass = create_pass(&mut renderer);
+
+        backend.clear_calls();
+        renderer.set_post_processing(vec![pass.clone()]);
+        draw_frame(&mut renderer);
+
+        let calls = backend.calls();
+
+        for stage in pass.stages() {
+            let body = stage.program.fragment_body();
+
+            assert!(
+                calls.iter().any(|call| matches!(
+                    call,
+                    GLCall::ShaderSource { source, .. } if source.contains(body)
+                )),
+                "No program built for {pass:?}"
+            );
+        }
+
+        // The scene is drawn into the first buffer, and the final stage draws
+        // into the window
+        let buffers = generated_framebuffers(&calls);
+        let targets = draw_targets(&calls);
+
+        assert_eq!(buffers.len(), 3);
+        assert_eq!(targets.len(), pass.stages().len() + 1);
+        assert_eq!(targets.first(), Some(&Some(buffers[0])));
+        assert_eq!(targets.last(), Some(&None));
+    }
+
+    #[test]
+    fn test_builtin_passes() {
+        check_pass(|_| PostProcessPass::GaussianBlur { radius: 2.0 });
+
+        check_pass(|_| PostProcessPass::Bloom {
+            threshold: 0.8,
+            intensity: 1.0,
+            radius: 2.0,
+        });
+
+        check_pass(|_| PostProcessPass::Vignette {
+            strength: 0.5,
+            radius: 0.8,
+        });
+
+        check_pass(|_| PostProcessPass::CrtScanlines {
+            intensity: 0.5,
+            line_spacing: 2.0,
+        });
+
+        check_pass(|_| {
+            PostProcessPass::Custom(CustomPostProcessPass::new(
+                "vec4 effect(vec2 tex_coord) { return SAMPLE(in_Texture, tex_coord); }",
+            ))
+        });
+
+        #[cfg(feature = "image-loading")]
+        check_pass(|renderer| PostProcessPass::ColorGrading {
+            lut: renderer
+                .create_image_from_raw_pixels(
+                    crate::image::ImageDataType::RGBA,
+                    crate::image::ImageSmoothingMode::NearestNeighbor,
+                    UVec2::new(4, 2),
+                    &[0; 32],
+                )
+                .unwrap(),
+            strength: 1.0,
+        });
+    }
+
+    #[test]
+    fn test_bloom_ping_pongs_between_buffers() {
+        let (backend, mut renderer) =
+            create_test_renderer(RecordingGLBackend::new(), (64, 32), GLVersion::OpenGL2_0);
+
+        renderer.set_post_processing(vec![PostProcessPass::Bloom {
+            threshold: 0.8,
+            intensity: 1.0,
+            radius: 2.0,
+        }]);
+
+        backend.clear_calls();
+        draw_frame(&mut renderer);
+
+        let calls = backend.calls();
+        let buffers = generated_framebuffers(&calls);
+
+        // The combine stage samples the pass input from the first buffer, so
+        // the blur stages alternate between the other two
+        assert_eq!(
+            draw_targets(&calls),
+            vec![
+                Some(buffers[0]),
+                Some(buffers[1]),
+                Some(buffers[2]),
+                Some(buffers[1]),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_failing_custom_shader_disables_chain() {
+        let (backend, mut renderer) =
+            create_test_renderer(RecordingGLBackend::new(), (64, 32), GLVersion::OpenGL2_0);
+
+        backend.fail_shaders_containing("BROKEN");
+
+        renderer.set_post_processing(vec![
+            PostProcessPass::Vignette {
+                strength: 0.5,
+                radius: 0.8,
+            },
+            PostProcessPass::Custom(CustomPostProcessPass::new(
+                "// BROKEN\nvec4 effect(vec2 tex_coord) { return vec4(1.0); }",
+            )),
+        ]);
+
+        for _ in 0..2 {
+            backend.clear_calls();
+            draw_frame(&mut renderer);
+
+            let calls = backend.calls();
+
+            // The frame is drawn straight to the window, without any
+            // post-processing buffers
+            assert!(generated_framebuffers(&calls).is_empty());
+            assert_eq!(draw_targets(&calls), vec![None]);
+        }
+    }
+}
@@ -0,0 +1,1069 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use glam::{UVec2, Vec2};
+
+use crate::error::{BacktraceError, Context, ErrorMessage};
+use crate::input::InputTrigger;
+use crate::time::Timestamp;
+use crate::window::{
+    ImeEvent, KeyScancode, ModifiersState, MouseButton, MouseScrollDistance, TouchId, TouchPhase,
+    VirtualKeyCode, WindowCreationOptions, WindowHandler, WindowHelper,
+};
+use crate::window_internal_headless::HeadlessWindowImpl;
+use crate::GLRenderer;
+
+const LOG_HEADER: &str = "speedy2d-event-log 1";
+
+/// A user event type which can be stored in an [EventLog]. This is
+/// implemented for `()` and [String], and must be implemented for any other
+/// user event type before events can be recorded.
+///
+/// See [WindowHelper::start_event_recording].
+pub trait SerializableUserEvent: Sized {
+    /// Converts the event to a string, which may contain any characters.
+    fn to_event_string(&self) -> String;
+
+    /// Converts a string created by [SerializableUserEvent::to_event_string]
+    /// back into an event, or returns `None` if the string is invalid.
+    fn from_event_string(text: &str) -> Option<Self>;
+}
+
+impl SerializableUserEvent for () {
+    fn to_event_string(&self) -> String {
+        String::new()
+    }
+
+    fn from_event_string(_text: &str) -> Option<Self> {
+        Some(())
+    }
+}
+
+impl SerializableUserEvent for String {
+    fn to_event_string(&self) -> String {
+        self.clone()
+    }
+
+    fn from_event_string(text: &str) -> Option<Self> {
+        Some(text.to_string())
+    }
+}
+
+/// An event which was delivered to a window, as stored in an [EventLog].
+///
+/// Each event corresponds to a [WindowHandler] callback. Events are recorded
+/// as they were received from the platform, before any conversion to logical
+/// or virtual coordinates, and callbacks which are derived from other events
+/// (such as [WindowHandler::on_update] and [WindowHandler::on_action]) are
+/// not recorded, as they are invoked again during replay.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum RecordedEvent {
+    /// See [WindowHandler::on_user_event]. The event is stored as the string
+    /// returned by [SerializableUserEvent::to_event_string].
+    UserEvent(String),
+    /// See [WindowHandler::on_resize].
+    Resize(UVec2),
+    /// See [WindowHandler::on_mouse_grab_status_changed].
+    MouseGrabStatusChanged(bool),
+    /// See [WindowHandler::on_fullscreen_status_changed].
+    FullscreenStatusChanged(bool),
+    /// See [WindowHandler::on_scale_factor_changed].
+    ScaleFactorChanged(f64),
+    /// A frame was drawn. See [WindowHandler::on_draw].
+    Draw,
+    /// See [WindowHandler::on_mouse_move].
+    MouseMove(Vec2),
+    /// See [WindowHandler::on_mouse_button_down].
+    MouseButtonDown(MouseButton),
+    /// See [WindowHandler::on_mouse_button_up].
+    MouseButtonUp(MouseButton),
+    /// See [WindowHandler::on_mouse_wheel_scroll].
+    MouseWheelScroll(MouseScrollDistance),
+    /// See [WindowHandler::on_focus_changed].
+    FocusChanged(bool),
+    /// See [WindowHandler::on_visibility_changed].
+    VisibilityChanged(bool),
+    /// See [WindowHandler::on_pinch].
+    Pinch(f64),
+    /// See [WindowHandler::on_rotate_gesture].
+    RotateGesture(f32),
+    /// See [WindowHandler::on_smart_zoom].
+    SmartZoom,
+    /// See [WindowHandler::on_file_hover].
+    FileHover(PathBuf),
+    /// See [WindowHandler::on_file_hover_cancelled].
+    FileHoverCancelled,
+    /// See [WindowHandler::on_file_drop].
+    FileDrop(PathBuf),
+    /// See [WindowHandler::on_file_drop_data]. Stores the name and contents
+    /// of the file.
+    FileDropData(String, Vec<u8>),
+    /// See [WindowHandler::on_clipboard_text].
+    ClipboardText(String),
+    /// See [WindowHandler::on_clipboard_image]. Stores the size and RGBA
+    /// pixels of the image.
+    #[cfg(feature = "image-loading")]
+    ClipboardImage(UVec2, Vec<u8>),
+    /// See [WindowHandler::on_touch].
+    Touch(TouchId, TouchPhase, Vec2, Option<f32>),
+    /// See [WindowHandler::on_key_down].
+    KeyDown(Option<VirtualKeyCode>, KeyScancode),
+    /// See [WindowHandler::on_key_up].
+    KeyUp(Option<VirtualKeyCode>, KeyScancode),
+    /// See [WindowHandler::on_keyboard_char].
+    KeyboardChar(char),
+    /// See [WindowHandler::on_ime].
+    Ime(ImeEvent),
+    /// See [WindowHandler::on_keyboard_modifiers_changed].
+    KeyboardModifiersChanged(ModifiersState),
+    /// See [WindowHandler::on_context_lost].
+    ContextLost,
+    /// See [WindowHandler::on_context_restored].
+    ContextRestored,
+}
+
+/// A list of the events delivered to a window, along with the time at which
+/// each event occurred, relative to the start of the recording.
+///
+/// A log can be recorded using [WindowHelper::start_event_recording], and
+/// replayed using [EventReplayer]. Logs can be saved and loaded as text,
+/// using the [Display] implementation and [EventLog::from_log_str].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventLog {
+    initial_size_pixels: UVec2,
+    initial_scale_factor: f64,
+    events: Vec<(Duration, RecordedEvent)>,
+}
+
+impl EventLog {
+    /// Creates an empty log, for a window with the specified initial size and
+    /// scale factor.
+    #[inline]
+    #[must_use]
+    pub fn new<S: Into<UVec2>>(initial_size_pixels: S, initial_scale_factor: f64) -> Self {
+        EventLog {
+            initial_size_pixels: initial_size_pixels.into(),
+            initial_scale_factor,
+            events: Vec::new(),
+        }
+    }
+
+    /// The size of the window when the recording started.
+    #[inline]
+    #[must_use]
+    pub fn initial_size_pixels(&self) -> UVec2 {
+        self.initial_size_pixels
+    }
+
+    /// The scale factor of the window when the recording started.
+    #[inline]
+    #[must_use]
+    pub fn initial_scale_factor(&self) -> f64 {
+        self.initial_scale_factor
+    }
+
+    /// The events in the log, in the order they occurred.
+    #[inline]
+    #[must_use]
+    pub fn events(&self) -> &[(Duration, RecordedEvent)] {
+        &self.events
+    }
+
+    /// Appends an event to the log. This allows logs to be constructed by
+    /// hand, for use in tests.
+    pub fn push(&mut self, timestamp: Duration, event: RecordedEvent) {
+        self.events.push((timestamp, event));
+    }
+
+    /// Parses a log which was saved using the [Display] implementation.
+    pub fn from_log_str(text: &str) -> Result<Self, BacktraceError<ErrorMessage>> {
+        // Lines aren't trimmed, as user events may start or end with spaces
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let mut next_header_line =
+            |expected: &str| -> Result<String, BacktraceError<ErrorMessage>> {
+                match lines.next() {
+                    Some((_, line)) if line.starts_with(expected) => Ok(line.to_string()),
+                    _ => Err(ErrorMessage::msg(format!("Missing '{expected}' line"))),
+                }
+            };
+
+        if next_header_line("speedy2d-event-log ")? != LOG_HEADER {
+            return Err(ErrorMessage::msg("Unsupported event log version"));
+        }
+
+        let size_line = next_header_line("size ")?;
+        let mut size = size_line.split_whitespace().skip(1);
+        let initial_size_pixels = UVec2::new(parse_next(&mut size)?, parse_next(&mut size)?);
+
+        let scale_factor_line = next_header_line("scale_factor ")?;
+        let initial_scale_factor = parse_next(&mut scale_factor_line.split_whitespace().skip(1))?;
+
+        let mut log = EventLog::new(initial_size_pixels, initial_scale_factor);
+
+        for (line_index, line) in lines {
+            let (timestamp, event) = parse_event_line(line)
+                .context(format!("Failed to parse line {}", line_index + 1))?;
+
+            log.push(timestamp, event);
+        }
+
+        Ok(log)
+    }
+}
+
+impl Display for EventLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{LOG_HEADER}")?;
+        writeln!(
+            f,
+            "size {} {}",
+            self.initial_size_pixels.x, self.initial_size_pixels.y
+        )?;
+        writeln!(f, "scale_factor {}", self.initial_scale_factor)?;
+
+        for (timestamp, event) in &self.events {
+            write!(
+                f,
+                "{}.{:09} ",
+                timestamp.as_secs(),
+                timestamp.subsec_nanos()
+            )?;
+
+            match event {
+                RecordedEvent::UserEvent(event) => {
+                    writeln!(f, "user_event {}", escape(event))
+                }
+                RecordedEvent::Resize(size) => writeln!(f, "resize {} {}", size.x, size.y),
+                RecordedEvent::MouseGrabStatusChanged(grabbed) => {
+                    writeln!(f, "mouse_grab {grabbed}")
+                }
+                RecordedEvent::FullscreenStatusChanged(fullscreen) => {
+                    writeln!(f, "fullscreen {fullscreen}")
+                }
+                RecordedEvent::ScaleFactorChanged(scale_factor) => {
+                    writeln!(f, "scale_factor {scale_factor}")
+                }
+                RecordedEvent::Draw => writeln!(f, "draw"),
+                RecordedEvent::MouseMove(position) => {
+                    writeln!(f, "mouse_move {} {}", position.x, position.y)
+                }
+                RecordedEvent::MouseButtonDown(button) => writeln!(
+                    f,
+                    "mouse_button_down {}",
+                    InputTrigger::MouseButton(*button)
+                ),
+                RecordedEvent::MouseButtonUp(button) => {
+                    writeln!(f, "mouse_button_up {}", InputTrigger::MouseButton(*button))
+                }
+                RecordedEvent::MouseWheelScroll(distance) => {
+                    let (unit, x, y, z) = match distance {
+                        MouseScrollDistance::Lines { x, y, z } => ("lines", x, y, z),
+                        MouseScrollDistance::Pixels { x, y, z } => ("pixels", x, y, z),
+                        MouseScrollDistance::Pages { x, y, z } => ("pages", x, y, z),
+                    };
+                    writeln!(f, "mouse_wheel_scroll {unit} {x} {y} {z}")
+                }
+                RecordedEvent::FocusChanged(focused) => writeln!(f, "focus {focused}"),
+                RecordedEvent::VisibilityChanged(visible) => writeln!(f, "visible {visible}"),
+                RecordedEvent::Pinch(delta) => writeln!(f, "pinch {delta}"),
+                RecordedEvent::RotateGesture(delta) => writeln!(f, "rotate_gesture {delta}"),
+                RecordedEvent::SmartZoom => writeln!(f, "smart_zoom"),
+                RecordedEvent::FileHover(path) => {
+                    writeln!(f, "file_hover {}", escape(&path.to_string_lossy()))
+                }
+                RecordedEvent::FileHoverCancelled => writeln!(f, "file_hover_cancelled"),
+                RecordedEvent::FileDrop(path) => {
+                    writeln!(f, "file_drop {}", escape(&path.to_string_lossy()))
+                }
+                RecordedEvent::FileDropData(name, bytes) => {
+                    write!(f, "file_drop_data ")?;
+                    for byte in bytes {
+                        write!(f, "{byte:02x}")?;
+                    }
+                    writeln!(f, " {}", escape(name))
+                }
+                RecordedEvent::ClipboardText(text) => {
+                    writeln!(f, "clipboard_text {}", escape(text))
+                }
+                #[cfg(feature = "image-loading")]
+                RecordedEvent::ClipboardImage(size, pixels) => {
+                    write!(f, "clipboard_image {} {} ", size.x, size.y)?;
+                    for byte in pixels {
+                        write!(f, "{byte:02x}")?;
+                    }
+                    writeln!(f)
+                }
+                RecordedEvent::Touch(id, phase, position, force) => {
+                    let phase = match phase {
+                        TouchPhase::Started => "started",
+                        TouchPhase::Moved => "moved",
+                        TouchPhase::Ended => "ended",
+                        TouchPhase::Cancelled => "cancelled",
+                    };
+                    let force = match force {
+                        None => "-".to_string(),
+                        Some(force) => force.to_string(),
+                    };
+                    writeln!(
+                        f,
+                        "touch {id} {phase} {} {} {force}",
+                        position.x, position.y
+                    )
+                }
+                RecordedEvent::KeyDown(virtual_key_code, scancode) => {
+                    writeln!(f, "key_down {} {scancode}", key_name(virtual_key_code))
+                }
+                RecordedEvent::KeyUp(virtual_key_code, scancode) => {
+                    writeln!(f, "key_up {} {scancode}", key_name(virtual_key_code))
+                }
+                RecordedEvent::KeyboardChar(codepoint) => {
+                    writeln!(f, "char {}", *codepoint as u32)
+                }
+                RecordedEvent::Ime(ImeEvent::Enabled) => writeln!(f, "ime_enabled"),
+                RecordedEvent::Ime(ImeEvent::Preedit { text, cursor }) => {
+                    let (start, end) = match cursor {
+                        None => ("-".to_string(), "-".to_string()),
+                        Some((start, end)) => (start.to_string(), end.to_string()),
+                    };
+                    writeln!(f, "ime_preedit {start} {end} {}", escape(text))
+                }
+                RecordedEvent::Ime(ImeEvent::Commit(text)) => {
+                    writeln!(f, "ime_commit {}", escape(text))
+                }
+                RecordedEvent::Ime(ImeEvent::Disabled) => writeln!(f, "ime_disabled"),
+                RecordedEvent::KeyboardModifiersChanged(state) => {
+                    write!(f, "modifiers")?;
+                    for (held, name) in [
+                        (state.ctrl(), "Ctrl"),
+                        (state.alt(), "Alt"),
+                        (state.shift(), "Shift"),
+                        (state.logo(), "Logo"),
+                    ] {
+                        if held {
+                            write!(f, " {name}")?;
+                        }
+                    }
+                    writeln!(f)
+                }
+                RecordedEvent::ContextLost => writeln!(f, "context_lost"),
+                RecordedEvent::ContextRestored => writeln!(f, "context_restored"),
+            }?;
+        }
+
+        Ok(())
+    }
+}
+
+fn key_name(virtual_key_code: &Option<VirtualKeyCode>) -> String {
+    match virtual_key_code {
+        None => "-".to_string(),
+        Some(key) => InputTrigger::Key(*key).to_string(),
+    }
+}
+
+fn parse_next<'a, T, I>(parts: &mut I) -> Result<T, BacktraceError<ErrorMessage>>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + 'static,
+    I: Iterator<Item = &'a str>,
+{
+    parts
+        .next()
+        .ok_or_else(|| ErrorMessage::msg("Missing value"))?
+        .parse()
+        .context("Invalid value")
+}
+
+fn parse_mouse_button<'a, I>(parts: &mut I) -> Result<MouseButton, BacktraceError<ErrorMessage>>
+where
+    I: Iterator<Item = &'a str>,
+{
+    match parse_next::<InputTrigger, _>(parts)? {
+        InputTrigger::MouseButton(button) => Ok(button),
+        trigger => Err(ErrorMessage::msg(format!(
+            "Expected a mouse button, got '{trigger}'"
+        ))),
+    }
+}
+
+fn parse_key<'a, I>(
+    parts: &mut I,
+) -> Result<(Option<VirtualKeyCode>, KeyScancode), BacktraceError<ErrorMessage>>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let virtual_key_code = match parse_next::<String, _>(parts)?.as_str() {
+        "-" => None,
+        name => match name.parse::<InputTrigger>()? {
+            InputTrigger::Key(key) => Some(key),
+            trigger => {
+                return Err(ErrorMessage::msg(format!(
+                    "Expected a virtual key code, got '{trigger}'"
+                )))
+            }
+        },
+    };
+
+    Ok((virtual_key_code, parse_next(parts)?))
+}
+
+fn parse_event_line(line: &str) -> Result<(Duration, RecordedEvent), BacktraceError<ErrorMessage>> {
+    let (timestamp, line) = line
+        .split_once(' ')
+        .ok_or_else(|| ErrorMessage::msg("Expected a timestamp and an event"))?;
+
+    let timestamp = parse_timestamp(timestamp).context("Invalid timestamp")?;
+
+    if let Some(event) = line.strip_prefix("user_event ") {
+        return Ok((timestamp, RecordedEvent::UserEvent(unescape(event)?)));
+    }
+
+    if let Some(path) = line.strip_prefix("file_hover ") {
+        return Ok((timestamp, RecordedEvent::FileHover(unescape(path)?.into())));
+    }
+
+    if let Some(path) = line.strip_prefix("file_drop ") {
+        return Ok((timestamp, RecordedEvent::FileDrop(unescape(path)?.into())));
+    }
+
+    if let Some(text) = line.strip_prefix("ime_commit ") {
+        return Ok((
+            timestamp,
+            RecordedEvent::Ime(ImeEvent::Commit(unescape(text)?)),
+        ));
+    }
+
+    if let Some(preedit) = line.strip_prefix("ime_preedit ") {
+        let mut parts = preedit.splitn(3, ' ');
+
+        let cursor = match (parse_next::<String, _>(&mut parts)?.as_str(), parts.next()) {
+            ("-", Some("-")) => None,
+            (start, Some(end)) => Some((
+                start.parse().context("Invalid cursor start")?,
+                end.parse().context("Invalid cursor end")?,
+            )),
+            (_, None) => return Err(ErrorMessage::msg("Missing cursor end")),
+        };
+
+        let text = unescape(
+            parts
+                .next()
+                .ok_or_else(|| ErrorMessage::msg("Missing preedit text"))?,
+        )?;
+
+        return Ok((
+            timestamp,
+            RecordedEvent::Ime(ImeEvent::Preedit { text, cursor }),
+        ));
+    }
+
+    if let Some(data) = line.strip_prefix("file_drop_data ") {
+        let (bytes, name) = data
+            .split_once(' ')
+            .ok_or_else(|| ErrorMessage::msg("Expected file contents and a name"))?;
+
+        return Ok((
+            timestamp,
+            RecordedEvent::FileDropData(unescape(name)?, parse_hex(bytes)?),
+        ));
+    }
+
+    if let Some(text) = line.strip_prefix("clipboard_text ") {
+        return Ok((timestamp, RecordedEvent::ClipboardText(unescape(text)?)));
+    }
+
+    #[cfg(feature = "image-loading")]
+    if let Some(image) = line.strip_prefix("clipboard_image ") {
+        let mut parts = image.splitn(3, ' ');
+        let size = UVec2::new(parse_next(&mut parts)?, parse_next(&mut parts)?);
+
+        let pixels = parse_hex(
+            parts
+                .next()
+                .ok_or_else(|| ErrorMessage::msg("Missing image pixels"))?,
+        )?;
+
+        return Ok((timestamp, RecordedEvent::ClipboardImage(size, pixels)));
+    }
+
+    let mut parts = line.split_whitespace();
+
+    let event = match parts.next().unwrap_or_default() {
+        "user_event" => RecordedEvent::UserEvent(String::new()),
+        "resize" => {
+            RecordedEvent::Resize(UVec2::new(parse_next(&mut parts)?, parse_next(&mut parts)?))
+        }
+        "mouse_grab" => RecordedEvent::MouseGrabStatusChanged(parse_next(&mut parts)?),
+        "fullscreen" => RecordedEvent::FullscreenStatusChanged(parse_next(&mut parts)?),
+        "scale_factor" => RecordedEvent::ScaleFactorChanged(parse_next(&mut parts)?),
+        "draw" => RecordedEvent::Draw,
+        "mouse_move" => {
+            RecordedEvent::MouseMove(Vec2::new(parse_next(&mut parts)?, parse_next(&mut parts)?))
+        }
+        "mouse_button_down" => RecordedEvent::MouseButtonDown(parse_mouse_button(&mut parts)?),
+        "mouse_button_up" => RecordedEvent::MouseButtonUp(parse_mouse_button(&mut parts)?),
+        "mouse_wheel_scroll" => {
+            let unit = parse_next::<String, _>(&mut parts)?;
+            let x = parse_next(&mut parts)?;
+            let y = parse_next(&mut parts)?;
+            let z = parse_next(&mut parts)?;
+
+            RecordedEvent::MouseWheelScroll(match unit.as_str() {
+                "lines" => MouseScrollDistance::Lines { x, y, z },
+                "pixels" => MouseScrollDistance::Pixels { x, y, z },
+                "pages" => MouseScrollDistance::Pages { x, y, z },
+                _ => return Err(ErrorMessage::msg(format!("Unknown scroll unit '{unit}'"))),
+            })
+        }
+        "focus" => RecordedEvent::FocusChanged(parse_next(&mut parts)?),
+        "visible" => RecordedEvent::VisibilityChanged(parse_next(&mut parts)?),
+        "pinch" => RecordedEvent::Pinch(parse_next(&mut parts)?),
+        "rotate_gesture" => RecordedEvent::RotateGesture(parse_next(&mut parts)?),
+        "smart_zoom" => RecordedEvent::SmartZoom,
+        "ime_enabled" => RecordedEvent::Ime(ImeEvent::Enabled),
+        "ime_disabled" => RecordedEvent::Ime(ImeEvent::Disabled),
+        "file_hover_cancelled" => RecordedEvent::FileHoverCancelled,
+        "touch" => {
+            let id = parse_next(&mut parts)?;
+            let phase = match parse_next::<String, _>(&mut parts)?.as_str() {
+                "started" => TouchPhase::Started,
+                "moved" => TouchPhase::Moved,
+                "ended" => TouchPhase::Ended,
+                "cancelled" => TouchPhase::Cancelled,
+                phase => return Err(ErrorMessage::msg(format!("Unknown touch phase '{phase}'"))),
+            };
+            let position = Vec2::new(parse_next(&mut parts)?, parse_next(&mut parts)?);
+            let force = match parse_next::<String, _>(&mut parts)?.as_str() {
+                "-" => None,
+                force => Some(force.parse().context("Invalid touch force")?),
+            };
+
+            RecordedEvent::Touch(id, phase, position, force)
+        }
+        "key_down" => {
+            let (virtual_key_code, scancode) = parse_key(&mut parts)?;
+            RecordedEvent::KeyDown(virtual_key_code, scancode)
+        }
+        "key_up" => {
+            let (virtual_key_code, scancode) = parse_key(&mut parts)?;
+            RecordedEvent::KeyUp(virtual_key_code, scancode)
+        }
+        "char" => RecordedEvent::KeyboardChar(
+            char::from_u32(parse_next(&mut parts)?)
+                .ok_or_else(|| ErrorMessage::msg("Invalid character"))?,
+        ),
+        "modifiers" => {
+            let mut state = ModifiersState::default();
+
+            for name in parts.by_ref() {
+                match name {
+                    "Ctrl" => state.ctrl = true,
+                    "Alt" => state.alt = true,
+                    "Shift" => state.shift = true,
+                    "Logo" => state.logo = true,
+                    _ => return Err(ErrorMessage::msg(format!("Unknown modifier '{name}'"))),
+                }
+            }
+
+            RecordedEvent::KeyboardModifiersChanged(state)
+        }
+        "context_lost" => RecordedEvent::ContextLost,
+        "context_restored" => RecordedEvent::ContextRestored,
+        name => return Err(ErrorMessage::msg(format!("Unknown event '{name}'"))),
+    };
+
+    if parts.next().is_some() {
+        return Err(ErrorMessage::msg("Unexpected trailing values"));
+    }
+
+    Ok((timestamp, event))
+}
+
+/// Parses a number of seconds, with up to nine decimal places.
+fn parse_timestamp(text: &str) -> Result<Duration, BacktraceError<ErrorMessage>> {
+    let (secs, fraction) = text.split_once('.').unwrap_or((text, ""));
+
+    if fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ErrorMessage::msg(format!("Invalid fraction '{fraction}'")));
+    }
+
+    let nanos = match fraction {
+        "" => 0,
+        fraction => format!("{fraction:0<9}")
+            .parse()
+            .context("Invalid fraction")?,
+    };
+
+    Ok(Duration::new(
+        secs.parse().context("Invalid seconds")?,
+        nanos,
+    ))
+}
+
+/// Escapes backslashes and line breaks, so that the text fits on one line.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+fn parse_hex(text: &str) -> Result<Vec<u8>, BacktraceError<ErrorMessage>> {
+    if text.len() % 2 != 0 || !text.is_ascii() {
+        return Err(ErrorMessage::msg("Invalid hex data"));
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).context("Invalid hex data"))
+        .collect()
+}
+
+fn unescape(text: &str) -> Result<String, BacktraceError<ErrorMessage>> {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        result.push(match chars.next() {
+            Some('\\') => '\\',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            _ => return Err(ErrorMessage::msg("Invalid escape sequence")),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Records the events delivered to a window, while a recording is active.
+pub(crate) struct EventRecorder<UserEventType> {
+    start: Timestamp,
+    serialize_user_event: fn(&UserEventType) -> String,
+    log: EventLog,
+}
+
+impl<UserEventType> EventRecorder<UserEventType> {
+    pub(crate) fn new(
+        initial_size_pixels: UVec2,
+        initial_scale_factor: f64,
+        serialize_user_event: fn(&UserEventType) -> String,
+    ) -> Self {
+        EventRecorder {
+            start: Timestamp::now(),
+            serialize_user_event,
+            log: EventLog::new(initial_size_pixels, initial_scale_factor),
+        }
+    }
+
+    pub(crate) fn record(&mut self, event: RecordedEvent) {
+        let timestamp = Duration::from_secs_f64(Timestamp::now().secs_since(&self.start));
+        self.log.push(timestamp, event);
+    }
+
+    pub(crate) fn record_user_event(&mut self, user_event: &UserEventType) {
+        let event = (self.serialize_user_event)(user_event);
+        self.record(RecordedEvent::UserEvent(event));
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn finish(self) -> EventLog {
+        self.log
+    }
+}
+
+enum ReplayEvent<UserEventType> {
+    User(UserEventType),
+    Window(RecordedEvent),
+}
+
+/// Replays an [EventLog] into a [WindowHandler], without requiring a real
+/// window. The handler draws using the provided [GLRenderer], which will
+/// typically be an offscreen renderer such as [GLRenderer::new_software].
+///
+/// Each [RecordedEvent::Draw] is replayed using its recorded timestamp,
+/// rather than the current time, so [WindowHelper::frame_timing] and
+/// [WindowHandler::on_update] behave as they did during the recording.
+///
+/// Replaying finishes once all events have been delivered, or once the
+/// handler calls [WindowHelper::terminate_loop] or
+/// [WindowHelper::close_window].
+///
+/// ```rust,no_run
+/// use speedy2d::recording::{EventLog, EventReplayer};
+/// use speedy2d::window::{WindowCreationOptions, WindowHandler, WindowSize};
+/// use speedy2d::GLRenderer;
+///
+/// struct MyHandler {}
+/// impl WindowHandler for MyHandler {}
+///
+/// let log = EventLog::from_log_str(&std::fs::read_to_string("bug.log").unwrap()).unwrap();
+/// let renderer = GLRenderer::new_software(log.initial_size_pixels()).unwrap();
+///
+/// let options = WindowCreationOptions::new_windowed(
+///     WindowSize::PhysicalPixels(log.initial_size_pixels()),
+///     None,
+/// );
+///
+/// let mut replayer = EventReplayer::new(&log, MyHandler {}, renderer, options).unwrap();
+/// replayer.replay_all();
+/// ```
+pub struct EventReplayer<UserEventType, H>
+where
+    UserEventType: 'static,
+    H: WindowHandler<UserEventType>,
+{
+    window: HeadlessWindowImpl<UserEventType, H>,
+    events: VecDeque<(Duration, ReplayEvent<UserEventType>)>,
+}
+
+impl<UserEventType, H> EventReplayer<UserEventType, H>
+where
+    UserEventType: SerializableUserEvent + 'static,
+    H: WindowHandler<UserEventType>,
+{
+    /// Creates a replayer for the specified log, and invokes
+    /// [WindowHandler::on_start]. The options are used in the same way as
+    /// when creating a window, except that the size, position, and other
+    /// properties of the window itself are ignored.
+    ///
+    /// An error is returned if a user event in the log could not be parsed.
+    pub fn new(
+        log: &EventLog,
+        handler: H,
+        renderer: GLRenderer,
+        options: WindowCreationOptions,
+    ) -> Result<Self, BacktraceError<ErrorMessage>> {
+        let mut events = VecDeque::with_capacity(log.events.len());
+
+        for (timestamp, event) in &log.events {
+            let event = match event {
+                RecordedEvent::UserEvent(text) => {
+                    ReplayEvent::User(UserEventType::from_event_string(text).ok_or_else(|| {
+                        ErrorMessage::msg(format!("Failed to parse user event '{text}'"))
+                    })?)
+                }
+                event => ReplayEvent::Window(event.clone()),
+            };
+
+            events.push_back((*timestamp, event));
+        }
+
+        let window = HeadlessWindowImpl::new(
+            log.initial_size_pixels,
+            log.initial_scale_factor,
+            renderer,
+            options,
+            handler,
+        )?;
+
+        Ok(EventReplayer { window, events })
+    }
+}
+
+impl<UserEventType, H> EventReplayer<UserEventType, H>
+where
+    UserEventType: 'static,
+    H: WindowHandler<UserEventType>,
+{
+    /// Returns true if there are no more events to replay, or if the handler
+    /// has terminated the loop or closed the window.
+    #[inline]
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty() || self.window.is_finished()
+    }
+
+    /// Delivers the next event in the log to the handler. Returns false if
+    /// replaying has already finished (see [EventReplayer::is_finished]).
+    pub fn replay_next(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        match self.events.pop_front() {
+            None => return false,
+            Some((_, ReplayEvent::User(user_event))) => self.window.deliver_user_event(user_event),
+            Some((timestamp, ReplayEvent::Window(RecordedEvent::Draw))) => {
+                self.window.draw_frame_at(timestamp)
+            }
+            Some((_, ReplayEvent::Window(event))) => self.window.deliver(event),
+        }
+
+        // Any events caused by the handler's requests (such as user events,
+        // or resizes) were recorded in the log, and are delivered from there
+        // instead
+        self.window.discard_pending_requests();
+
+        true
+    }
+
+    /// Delivers all remaining events in the log to the handler.
+    pub fn replay_all(&mut self) {
+        while self.replay_next() {}
+    }
+
+    /// Returns the number of events which have not yet been replayed.
+    #[inline]
+    #[must_use]
+    pub fn remaining_events(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns the handler which events are being replayed into.
+    #[inline]
+    #[must_use]
+    pub fn handler(&self) -> &H {
+        self.window.handler()
+    }
+
+    /// Returns the handler which events are being replayed into.
+    #[inline]
+    #[must_use]
+    pub fn handler_mut(&mut self) -> &mut H {
+        self.window.handler_mut()
+    }
+
+    /// Returns the helper which is passed to the handler.
+    #[inline]
+    #[must_use]
+    pub fn helper(&mut self) -> &mut WindowHelper<UserEventType> {
+        self.window.helper()
+    }
+
+    /// Returns the renderer which the handler draws to. This may be used to
+    /// inspect the output, for example using [crate::Graphics2D::capture]
+    /// within [GLRenderer::draw_frame].
+    #[inline]
+    #[must_use]
+    pub fn renderer_mut(&mut self) -> &mut GLRenderer {
+        self.window.renderer_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::color::Color;
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::window::WindowSize;
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::{Graphics2D, HeadlessWindow};
+
+    #[test]
+    fn test_event_log_text_round_trip() {
+        let mut modifiers = ModifiersState::default();
+        modifiers.ctrl = true;
+        modifiers.shift = true;
+
+        let mut log = EventLog::new((640, 480), 1.5);
+
+        let mut events = vec![
+            RecordedEvent::Resize(UVec2::new(800, 600)),
+            RecordedEvent::ScaleFactorChanged(2.0),
+            RecordedEvent::Draw,
+            RecordedEvent::MouseMove(Vec2::new(12.5, -3.25)),
+            RecordedEvent::MouseButtonDown(MouseButton::Left),
+            RecordedEvent::MouseButtonUp(MouseButton::Other(7)),
+            RecordedEvent::MouseWheelScroll(MouseScrollDistance::Lines {
+                x: 0.0,
+                y: -1.0,
+                z: 0.0,
+            }),
+            RecordedEvent::FocusChanged(false),
+            RecordedEvent::VisibilityChanged(true),
+            RecordedEvent::Pinch(-0.125),
+            RecordedEvent::RotateGesture(12.5),
+            RecordedEvent::SmartZoom,
+            RecordedEvent::FileHover(PathBuf::from("/tmp/some image.png")),
+            RecordedEvent::FileHoverCancelled,
+            RecordedEvent::FileDrop(PathBuf::from("C:\\Images\\photo.jpg")),
+            RecordedEvent::FileDropData("notes 1.txt".to_string(), vec![0, 1, 0xab, 0xff]),
+            RecordedEvent::FileDropData(String::new(), Vec::new()),
+            RecordedEvent::ClipboardText("copied\ntext ".to_string()),
+            RecordedEvent::ClipboardText(String::new()),
+            RecordedEvent::Touch(3, TouchPhase::Started, Vec2::new(40.0, 60.5), Some(0.25)),
+            RecordedEvent::Touch(3, TouchPhase::Cancelled, Vec2::new(41.0, 60.5), None),
+            RecordedEvent::KeyDown(Some(VirtualKeyCode::Space), 57),
+            RecordedEvent::KeyUp(None, 1234),
+            RecordedEvent::KeyboardChar('\n'),
+            RecordedEvent::Ime(ImeEvent::Enabled),
+            RecordedEvent::Ime(ImeEvent::Preedit {
+                text: "にほん ご".to_string(),
+                cursor: Some((3, 9)),
+            }),
+            RecordedEvent::Ime(ImeEvent::Preedit {
+                text: String::new(),
+                cursor: None,
+            }),
+            RecordedEvent::Ime(ImeEvent::Commit("日本語".to_string())),
+            RecordedEvent::Ime(ImeEvent::Disabled),
+            RecordedEvent::KeyboardModifiersChanged(modifiers),
+            RecordedEvent::KeyboardModifiersChanged(ModifiersState::default()),
+            RecordedEvent::MouseGrabStatusChanged(true),
+            RecordedEvent::FullscreenStatusChanged(false),
+            RecordedEvent::UserEvent("line one\nline two \\ end".to_string()),
+            RecordedEvent::UserEvent(String::new()),
+            RecordedEvent::ContextLost,
+            RecordedEvent::ContextRestored,
+        ];
+
+        #[cfg(feature = "image-loading")]
+        events.extend([
+            RecordedEvent::ClipboardImage(UVec2::new(1, 2), vec![255, 0, 0, 255, 0, 0, 0, 0]),
+            RecordedEvent::ClipboardImage(UVec2::ZERO, Vec::new()),
+        ]);
+
+        for (index, event) in events.into_iter().enumerate() {
+            log.push(Duration::from_millis(index as u64 * 16), event);
+        }
+
+        assert_eq!(log, EventLog::from_log_str(&log.to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_event_log_parse_errors() {
+        let header = "speedy2d-event-log 1\nsize 640 480\nscale_factor 1\n";
+
+        assert!(EventLog::from_log_str(header).is_ok());
+        assert!(EventLog::from_log_str("size 640 480\nscale_factor 1\n").is_err());
+        assert!(EventLog::from_log_str(&format!("{header}0.5 explode\n")).is_err());
+        assert!(EventLog::from_log_str(&format!("{header}0.5 resize 10\n")).is_err());
+        assert!(EventLog::from_log_str(&format!("{header}-1 draw\n")).is_err());
+        assert!(EventLog::from_log_str(&format!("{header}0.5 key_down Space\n")).is_err());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[derive(Default)]
+    struct ReplayHandler {
+        keys: Vec<VirtualKeyCode>,
+        grab_notifications: Vec<bool>,
+        frames: Vec<(u64, Duration)>,
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    impl WindowHandler for ReplayHandler {
+        fn on_mouse_grab_status_changed(&mut self, _helper: &mut WindowHelper, grabbed: bool) {
+            self.grab_notifications.push(grabbed);
+        }
+
+        fn on_draw(&mut self, helper: &mut WindowHelper, graphics: &mut Graphics2D) {
+            let timing = helper.frame_timing();
+            self.frames.push((timing.frame_index, timing.timestamp));
+            graphics.clear_screen(Color::BLUE);
+        }
+
+        fn on_mouse_button_down(&mut self, helper: &mut WindowHelper, _button: MouseButton) {
+            helper.set_cursor_grab(true).unwrap();
+        }
+
+        fn on_key_down(
+            &mut self,
+            _helper: &mut WindowHelper,
+            virtual_key_code: Option<VirtualKeyCode>,
+            _scancode: KeyScancode,
+        ) {
+            self.keys.extend(virtual_key_code);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_record_then_replay() {
+        let mut window = HeadlessWindow::new((20, 10), ReplayHandler::default()).unwrap();
+        window.helper().start_event_recording();
+
+        window.step_frame();
+        window.on_key_down(Some(VirtualKeyCode::A), 30);
+        window.on_mouse_button_down(MouseButton::Left);
+        window.step_frames(2);
+        window.on_key_down(Some(VirtualKeyCode::B), 48);
+        window.step_frame();
+
+        let log = window.helper().stop_event_recording().unwrap();
+        let log = EventLog::from_log_str(&log.to_string()).unwrap();
+
+        let timestamps: Vec<Duration> = log.events().iter().map(|(time, _)| *time).collect();
+        assert!(timestamps.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let draw_timestamps: Vec<Duration> = log
+            .events()
+            .iter()
+            .filter(|(_, event)| matches!(event, RecordedEvent::Draw))
+            .map(|(time, _)| *time)
+            .collect();
+        assert_eq!(draw_timestamps.len(), 4);
+
+        let renderer = GLRenderer::new_software(log.initial_size_pixels()).unwrap();
+        let options = WindowCreationOptions::new_windowed(
+            WindowSize::PhysicalPixels(log.initial_size_pixels()),
+            None,
+        );
+
+        let mut replayer =
+            EventReplayer::new(&log, ReplayHandler::default(), renderer, options).unwrap();
+        replayer.replay_all();
+        assert!(replayer.is_finished());
+        assert_eq!(replayer.remaining_events(), 0);
+
+        let recorded = window.handler();
+        let replayed = replayer.handler();
+
+        assert_eq!(replayed.keys, vec![VirtualKeyCode::A, VirtualKeyCode::B]);
+        assert_eq!(replayed.keys, recorded.keys);
+
+        // The notification was caused by the handler's request, and must only
+        // be delivered once, from the log
+        assert_eq!(replayed.grab_notifications, vec![true]);
+        assert_eq!(replayed.grab_notifications, recorded.grab_notifications);
+
+        // Frames are replayed in order, at the times they were recorded
+        assert_eq!(replayed.frames.len(), draw_timestamps.len());
+
+        for (index, ((frame_index, timestamp), draw_timestamp)) in
+            replayed.frames.iter().zip(&draw_timestamps).enumerate()
+        {
+            let expected = *draw_timestamp - draw_timestamps[0];
+
+            assert_eq!(*frame_index, index as u64);
+            assert!((timestamp.as_secs_f64() - expected.as_secs_f64()).abs() < 1e-6);
+        }
+    }
+}
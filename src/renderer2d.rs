@@ -16,8 +16,6 @@
 
 use std::rc::Rc;
 
-#[cfg(feature = "text")]
-use glam_rect::Rect;
 #[cfg(any(feature = "image-loading", doc, doctest))]
 use {
     crate::image::ImageFileFormat,
@@ -28,8 +26,10 @@ use {
 };
 
 use crate::color::Color;
+use crate::debug_overlay::DebugOverlay;
 use crate::error::{BacktraceError, Context, ErrorMessage};
 use crate::glwrapper::*;
+use crate::postprocess::{PostProcessPass, PostProcessor};
 
 #[cfg(feature = "text")]
 use crate::font::{FormattedGlyph, FormattedTextBlock};
@@ -42,7 +42,7 @@ use crate::image::RawBitmapData;
 use crate::image::{ImageDataType, ImageHandle, ImageSmoothingMode};
 
 use glam::{UVec2, Vec2};
-use glam_rect::IRect;
+use glam_rect::{IRect, Rect};
 
 struct AttributeBuffers {
     position: Vec<f32>,
@@ -200,11 +200,16 @@ impl Uniforms {
         })
     }
 
-    fn set_viewport_size_pixels(&self, context: &GLContextManager, viewport_size_pixels: UVec2) {
+    fn set_viewport_size_pixels(
+        &self,
+        context: &GLContextManager,
+        viewport_size_pixels: UVec2,
+        scale_factor: f32,
+    ) {
         self.scale_x
-            .set_value_float(context, 2.0 / viewport_size_pixels.x as f32);
+            .set_value_float(context, 2.0 * scale_factor / viewport_size_pixels.x as f32);
         self.scale_y
-            .set_value_float(context, -2.0 / viewport_size_pixels.y as f32);
+            .set_value_float(context, -2.0 * scale_factor / viewport_size_pixels.y as f32);
     }
 
     fn set_texture_unit(&self, context: &GLContextManager, texture_unit: i32) {
@@ -560,6 +565,60 @@ impl RenderQueueItem {
     }
 }
 
+/// Statistics about the work done by the renderer while drawing a frame. These
+/// are useful for finding out why a frame was slow to draw.
+///
+/// See [crate::Graphics2D::last_frame_stats] and
+/// [crate::GLRenderer::last_frame_stats].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FrameStats {
+    /// The number of batches of vertices drawn. Batches are broken up when
+    /// the texture changes, when the clip changes, or when the screen is
+    /// cleared.
+    pub draw_calls: u32,
+
+    /// The number of vertices uploaded to the GPU.
+    pub vertices_uploaded: usize,
+
+    /// The number of times a different texture was bound.
+    pub texture_binds: u32,
+
+    /// The number of times the blend mode was changed.
+    pub blend_mode_changes: u32,
+
+    /// The number of times a different shader program was selected, for
+    /// example when switching to and from post-processing.
+    pub program_changes: u32,
+
+    /// The number of items (such as triangles and blocks of text) added to the
+    /// render queue, and then drawn.
+    pub render_queue_items: usize,
+
+    /// The number of times the render queue was flushed.
+    pub render_queue_flushes: u32,
+
+    /// The number of glyphs drawn which were already in the glyph cache.
+    pub glyph_cache_hits: u32,
+
+    /// The number of glyphs drawn which needed to be rasterized.
+    pub glyph_cache_misses: u32,
+
+    /// The number of textures used by the glyph cache at the end of the frame.
+    pub glyph_texture_count: usize,
+
+    /// The number of times the glyph cache ran out of space, and all the
+    /// glyphs were repacked. This is expensive, and if it happens often, it
+    /// may be caused by drawing text at many different sizes or subpixel
+    /// offsets.
+    pub glyph_cache_rearrangements: u32,
+}
+
+struct VirtualResolution {
+    size: UVec2,
+    framebuffer: GLFramebuffer,
+}
+
 pub struct Renderer2D {
     context: GLContextManager,
 
@@ -575,6 +634,27 @@ pub struct Renderer2D {
 
     #[allow(dead_code)]
     uniforms: Uniforms,
+
+    viewport_size_pixels: UVec2,
+
+    /// The number of physical pixels per drawing unit
+    scale_factor: f32,
+
+    /// If set, frames are drawn into an offscreen framebuffer of a fixed size,
+    /// which is then upscaled to the window
+    virtual_resolution: Option<VirtualResolution>,
+
+    post_processor: PostProcessor,
+
+    frame_stats: FrameStats,
+    last_frame_stats: FrameStats,
+
+    debug_overlay: DebugOverlay,
+
+    /// If set, images keep a copy of their pixel data, so that they survive
+    /// a context loss
+    #[cfg(feature = "image-loading")]
+    retain_image_data: bool,
 }
 
 impl Renderer2D {
@@ -610,6 +690,13 @@ impl Renderer2D {
                     include_str!("shaders/r2d_fragment_v110.glsl"),
                 )
             }
+            GLVersion::OpenGL3_3Core => {
+                log::info!("Using OpenGL 3.3 core shaders");
+                (
+                    include_str!("shaders/r2d_vertex_v330.glsl"),
+                    include_str!("shaders/r2d_fragment_v330.glsl"),
+                )
+            }
             GLVersion::WebGL2_0 => {
                 log::info!("Using WebGL 2.0 shaders");
                 (
@@ -617,6 +704,13 @@ impl Renderer2D {
                     include_str!("shaders/r2d_fragment_v300es.glsl"),
                 )
             }
+            GLVersion::OpenGLES2_0 | GLVersion::WebGL1_0 => {
+                log::info!("Using GLSL 100 shaders");
+                (
+                    include_str!("shaders/r2d_vertex_v100.glsl"),
+                    include_str!("shaders/r2d_fragment_v100.glsl"),
+                )
+            }
         };
 
         let vertex_shader = context
@@ -646,7 +740,7 @@ impl Renderer2D {
 
         uniforms.set_texture_unit(context, 0);
 
-        uniforms.set_viewport_size_pixels(context, viewport_size_pixels);
+        uniforms.set_viewport_size_pixels(context, viewport_size_pixels, 1.0);
 
         context.set_viewport_size(viewport_size_pixels);
 
@@ -661,20 +755,322 @@ impl Renderer2D {
             attribute_buffers,
             current_texture: None,
             uniforms,
+            viewport_size_pixels,
+            scale_factor: 1.0,
+            virtual_resolution: None,
+            post_processor: PostProcessor::new(context),
+            frame_stats: FrameStats::default(),
+            last_frame_stats: FrameStats::default(),
+            debug_overlay: DebugOverlay::default(),
+            #[cfg(feature = "image-loading")]
+            retain_image_data: false,
         })
     }
 
-    pub fn set_viewport_size_pixels(&self, viewport_size_pixels: UVec2) {
+    pub fn set_viewport_size_pixels(&mut self, viewport_size_pixels: UVec2) {
+        self.viewport_size_pixels = viewport_size_pixels;
+
+        if self.virtual_resolution.is_none() {
+            self.apply_render_target();
+        }
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        if scale_factor == self.scale_factor {
+            return;
+        }
+
+        // Anything already queued was positioned using the old scale
+        self.flush_render_queue();
+
+        self.scale_factor = scale_factor;
+
+        if self.virtual_resolution.is_none() {
+            self.apply_render_target();
+        }
+    }
+
+    pub fn set_virtual_resolution(
+        &mut self,
+        size: Option<UVec2>,
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        self.flush_render_queue();
+
+        self.virtual_resolution = match size {
+            None => None,
+            Some(size) => {
+                let texture = self
+                    .context
+                    .new_texture()
+                    .context("Failed to create virtual resolution texture")?;
+
+                texture
+                    .set_image_data(
+                        &self.context,
+                        GLTextureImageFormatU8::RGBA,
+                        GLTextureSmoothing::NearestNeighbour,
+                        &size,
+                        &vec![0; size.x as usize * size.y as usize * 4],
+                    )
+                    .context("Failed to allocate virtual resolution texture")?;
+
+                let framebuffer = self
+                    .context
+                    .new_framebuffer(&texture)
+                    .context("Failed to create virtual resolution framebuffer")?;
+
+                Some(VirtualResolution { size, framebuffer })
+            }
+        };
+
+        self.apply_render_target();
+
+        Ok(())
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn virtual_resolution(&self) -> Option<UVec2> {
+        self.virtual_resolution.as_ref().map(|res| res.size)
+    }
+
+    /// The area of the window (in physical pixels) which the virtual
+    /// resolution framebuffer is upscaled into. The scale is always an
+    /// integer, and the area is centered in the window.
+    #[must_use]
+    pub fn virtual_resolution_area(&self) -> Option<Rect> {
+        let size = self.virtual_resolution.as_ref()?.size;
+        let window_size = self.viewport_size_pixels;
+
+        let scale = (window_size.x / size.x.max(1))
+            .min(window_size.y / size.y.max(1))
+            .max(1);
+
+        let scaled_size = (size * scale).as_ivec2();
+        let top_left = (window_size.as_ivec2() - scaled_size) / 2;
+
+        Some(Rect::new(
+            top_left.as_vec2(),
+            (top_left + scaled_size).as_vec2(),
+        ))
+    }
+
+    /// Binds the framebuffer which should be drawn into, and sets up the
+    /// projection for it.
+    fn apply_render_target(&mut self) {
+        let (size, scale_factor, output) = match &self.virtual_resolution {
+            None => (self.viewport_size_pixels, self.scale_factor, None),
+            Some(virtual_resolution) => (
+                virtual_resolution.size,
+                1.0,
+                Some(virtual_resolution.framebuffer.clone()),
+            ),
+        };
+
+        let target = self.post_processor.input_framebuffer(size).or(output);
+
+        self.context.bind_framebuffer(target.as_ref());
+        self.set_projection(size, scale_factor);
+    }
+
+    /// Sets the chain of post-processing passes, which takes effect from the
+    /// next frame.
+    pub fn set_post_processing(&mut self, passes: Vec<PostProcessPass>) {
+        self.post_processor.set_passes(passes);
+    }
+
+    fn set_projection(&mut self, target_size_pixels: UVec2, scale_factor: f32) {
+        self.context.use_program(&self.program);
         self.uniforms
-            .set_viewport_size_pixels(&self.context, viewport_size_pixels);
+            .set_viewport_size_pixels(&self.context, target_size_pixels, scale_factor);
 
-        self.context.set_viewport_size(viewport_size_pixels);
+        self.context.set_viewport_size(target_size_pixels);
+
+        #[cfg(feature = "text")]
+        self.glyph_cache.set_scale_factor(scale_factor);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    pub fn begin_frame(&mut self) {
+        // Exclude any work done between frames, such as creating images
+        self.frame_stats = FrameStats::default();
+        self.context.take_state_change_counts();
+
+        #[cfg(feature = "text")]
+        self.glyph_cache.take_stats();
+
+        self.debug_overlay.on_frame_start();
+
+        self.post_processor.apply_pending_passes();
+
+        // The previous frame may have been presented to a different target
+        self.apply_render_target();
     }
 
     pub fn finish_frame(&mut self) {
         self.flush_render_queue();
+
+        if self.post_processor.is_enabled() {
+            self.run_post_processing();
+        }
+
+        if self.virtual_resolution.is_some() {
+            self.present_virtual_resolution();
+        }
+
         #[cfg(feature = "text")]
         self.glyph_cache.on_new_frame_start();
+
+        self.update_frame_stats();
+        self.last_frame_stats = self.frame_stats;
+
+        if self.debug_overlay.is_enabled() {
+            self.draw_debug_overlay();
+        }
+    }
+
+    /// Draws the debug overlay directly to the window, on top of the
+    /// finished frame.
+    fn draw_debug_overlay(&mut self) {
+        self.context.bind_framebuffer(None);
+        self.context.set_enable_scissor(false);
+        self.set_projection(self.viewport_size_pixels, 1.0);
+
+        let debug_overlay = std::mem::take(&mut self.debug_overlay);
+        let stats = self.last_frame_stats;
+        debug_overlay.draw(self, &stats);
+        self.debug_overlay = debug_overlay;
+
+        self.flush_render_queue();
+    }
+
+    #[cfg(feature = "image-loading")]
+    pub fn set_retain_image_data(&mut self, retain: bool) {
+        self.retain_image_data = retain;
+    }
+
+    /// Recreates the GL resources owned by the renderer, after the context
+    /// has been restored following a context loss. The settings (such as
+    /// the virtual resolution and post-processing passes) are kept.
+    pub fn recreate_gl_resources(&mut self) -> Result<(), BacktraceError<ErrorMessage>> {
+        let restored = Renderer2D::new(&self.context, self.viewport_size_pixels)?;
+
+        self.program = restored.program;
+        self.attribute_buffers = restored.attribute_buffers;
+        self.uniforms = restored.uniforms;
+
+        #[cfg(feature = "text")]
+        {
+            self.glyph_cache = restored.glyph_cache;
+        }
+
+        self.render_queue.clear();
+        self.current_texture = None;
+        self.post_processor.clear_gl_resources();
+
+        // Also applies the render target and projection
+        self.set_virtual_resolution(self.virtual_resolution())
+    }
+
+    pub fn set_debug_overlay_enabled(&mut self, enabled: bool) {
+        self.debug_overlay.set_enabled(enabled);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_debug_overlay_enabled(&self) -> bool {
+        self.debug_overlay.is_enabled()
+    }
+
+    #[cfg(feature = "text")]
+    pub(crate) fn glyph_cache_textures(&self) -> Vec<GLTexture> {
+        self.glyph_cache.textures().cloned().collect()
+    }
+
+    fn update_frame_stats(&mut self) {
+        let state_changes = self.context.take_state_change_counts();
+
+        self.frame_stats.texture_binds += state_changes.texture_binds;
+        self.frame_stats.blend_mode_changes += state_changes.blend_mode_changes;
+        self.frame_stats.program_changes += state_changes.program_changes;
+
+        #[cfg(feature = "text")]
+        {
+            let glyph_stats = self.glyph_cache.take_stats();
+
+            self.frame_stats.glyph_cache_hits += glyph_stats.hits;
+            self.frame_stats.glyph_cache_misses += glyph_stats.misses;
+            self.frame_stats.glyph_cache_rearrangements += glyph_stats.rearrangements;
+            self.frame_stats.glyph_texture_count = self.glyph_cache.texture_count();
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn last_frame_stats(&self) -> FrameStats {
+        self.last_frame_stats
+    }
+
+    fn run_post_processing(&mut self) {
+        let output = self
+            .virtual_resolution
+            .as_ref()
+            .map(|res| res.framebuffer.clone());
+
+        self.context.set_enable_scissor(false);
+
+        if let Err(err) = self.post_processor.run(output.as_ref()) {
+            log::error!("Post-processing failed, disabling: {:?}", err);
+            self.post_processor.set_passes(Vec::new());
+        }
+    }
+
+    /// Upscales the virtual resolution framebuffer into the window, using
+    /// nearest-neighbour filtering, and leaving black bars around the edges.
+    fn present_virtual_resolution(&mut self) {
+        let (texture, area) = match (&self.virtual_resolution, self.virtual_resolution_area()) {
+            (Some(virtual_resolution), Some(area)) => {
+                (virtual_resolution.framebuffer.texture().clone(), area)
+            }
+            _ => return,
+        };
+
+        self.context.bind_framebuffer(None);
+        self.context.set_enable_scissor(false);
+        self.set_projection(self.viewport_size_pixels, 1.0);
+        self.context.clear_screen(Color::BLACK);
+
+        let [top_left, top_right, bottom_right, bottom_left] = area.corners();
+
+        // Framebuffer contents are stored with the origin at the bottom left
+        self.add_to_render_queue(RenderQueueItem::TriangleTextured {
+            vertex_positions_clockwise: [top_left, top_right, bottom_right],
+            vertex_colors_clockwise: [Color::WHITE; 3],
+            vertex_texture_coords_clockwise: [
+                Vec2::new(0.0, 1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(1.0, 0.0),
+            ],
+            texture: texture.clone(),
+        });
+
+        self.add_to_render_queue(RenderQueueItem::TriangleTextured {
+            vertex_positions_clockwise: [bottom_right, bottom_left, top_left],
+            vertex_colors_clockwise: [Color::WHITE; 3],
+            vertex_texture_coords_clockwise: [
+                Vec2::new(1.0, 0.0),
+                Vec2::new(0.0, 0.0),
+                Vec2::new(0.0, 1.0),
+            ],
+            texture,
+        });
+
+        self.flush_render_queue();
     }
 
     fn flush_render_queue(&mut self) {
@@ -684,6 +1080,9 @@ impl Renderer2D {
 
         self.attribute_buffers.clear();
 
+        self.frame_stats.render_queue_items += self.render_queue.len();
+        self.frame_stats.render_queue_flushes += 1;
+
         let mut has_text = false;
 
         for item in &self.render_queue {
@@ -728,6 +1127,7 @@ impl Renderer2D {
             let context = &self.context;
             let program = &self.program;
             let attribute_buffers = &mut self.attribute_buffers;
+            let frame_stats = &mut self.frame_stats;
 
             for item in &self.render_queue {
                 #[cfg(feature = "text")]
@@ -738,6 +1138,7 @@ impl Renderer2D {
                             program,
                             attribute_buffers,
                             current_texture,
+                            frame_stats,
                         );
 
                         *current_texture = action.texture.clone();
@@ -754,6 +1155,7 @@ impl Renderer2D {
                             program,
                             attribute_buffers,
                             current_texture,
+                            frame_stats,
                         );
 
                         current_texture.clone_from(&action.texture);
@@ -771,6 +1173,7 @@ impl Renderer2D {
             &self.program,
             &mut self.attribute_buffers,
             &mut self.current_texture,
+            &mut self.frame_stats,
         );
     }
 
@@ -779,6 +1182,7 @@ impl Renderer2D {
         program: &Rc<GLProgram>,
         attribute_buffers: &mut AttributeBuffers,
         current_texture: &mut Option<GLTexture>,
+        frame_stats: &mut FrameStats,
     ) {
         let vertex_count = attribute_buffers.get_vertex_count();
 
@@ -786,6 +1190,9 @@ impl Renderer2D {
             return;
         }
 
+        frame_stats.draw_calls += 1;
+        frame_stats.vertices_uploaded += vertex_count;
+
         context.use_program(program);
 
         attribute_buffers.upload_and_clear(context);
@@ -803,6 +1210,24 @@ impl Renderer2D {
         );
     }
 
+    /// Reads back the pixels of an image, in RGBA format.
+    #[cfg(feature = "image-loading")]
+    pub(crate) fn read_image_rgba(
+        &self,
+        image: &ImageHandle,
+    ) -> Result<Vec<u8>, BacktraceError<ErrorMessage>> {
+        self.context.read_texture_rgba(&image.texture, image.size)
+    }
+
+    /// The size of the area being drawn to, in drawing units.
+    #[must_use]
+    pub(crate) fn drawing_area_size(&self) -> Vec2 {
+        match &self.virtual_resolution {
+            None => self.viewport_size_pixels.as_vec2() / self.scale_factor,
+            Some(virtual_resolution) => virtual_resolution.size.as_vec2(),
+        }
+    }
+
     #[cfg(feature = "image-loading")]
     pub(crate) fn create_image_from_raw_pixels<S: Into<UVec2>>(
         &self,
@@ -845,9 +1270,13 @@ impl Renderer2D {
             .new_texture()
             .context("Failed to create GPU texture")?;
 
-        texture
-            .set_image_data(&self.context, gl_format, gl_smoothing, &size, data)
-            .context("Failed to upload image data")?;
+        let upload_result = if self.retain_image_data {
+            texture.set_image_data_retained(&self.context, gl_format, gl_smoothing, &size, data)
+        } else {
+            texture.set_image_data(&self.context, gl_format, gl_smoothing, &size, data)
+        };
+
+        upload_result.context("Failed to upload image data")?;
 
         Ok(ImageHandle { size, texture })
     }
@@ -944,6 +1373,34 @@ impl Renderer2D {
         })
     }
 
+    /// Draws the whole of a texture, stretched to fill the rectangle.
+    #[cfg(feature = "text")]
+    pub(crate) fn draw_texture_rect(&mut self, rect: Rect, texture: GLTexture) {
+        let [top_left, top_right, bottom_right, bottom_left] = rect.corners();
+
+        self.add_to_render_queue(RenderQueueItem::TriangleTextured {
+            vertex_positions_clockwise: [top_left, top_right, bottom_right],
+            vertex_colors_clockwise: [Color::WHITE; 3],
+            vertex_texture_coords_clockwise: [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(1.0, 1.0),
+            ],
+            texture: texture.clone(),
+        });
+
+        self.add_to_render_queue(RenderQueueItem::TriangleTextured {
+            vertex_positions_clockwise: [bottom_right, bottom_left, top_left],
+            vertex_colors_clockwise: [Color::WHITE; 3],
+            vertex_texture_coords_clockwise: [
+                Vec2::new(1.0, 1.0),
+                Vec2::new(0.0, 1.0),
+                Vec2::new(0.0, 0.0),
+            ],
+            texture,
+        });
+    }
+
     #[cfg(feature = "image-loading")]
     #[inline]
     pub(crate) fn draw_triangle_image_tinted(
@@ -1026,6 +1483,20 @@ impl Renderer2D {
         match rect {
             None => self.context.set_enable_scissor(false),
             Some(rect) => {
+                let scale_factor = match self.virtual_resolution {
+                    None => self.scale_factor,
+                    Some(_) => 1.0,
+                };
+                let rect = if scale_factor == 1.0 {
+                    rect
+                } else {
+                    IRect::new(
+                        (rect.top_left.as_vec2() * scale_factor).round().as_ivec2(),
+                        (rect.bottom_right.as_vec2() * scale_factor)
+                            .round()
+                            .as_ivec2(),
+                    )
+                };
                 let IRect { top_left, .. } = rect;
                 let (width, height) = rect.size().into();
                 self.context.set_enable_scissor(true);
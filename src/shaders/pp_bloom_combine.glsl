@@ -0,0 +1,29 @@
+
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+// Adds the blurred highlights (in_Texture) to the original image
+// (in_Texture2), scaled by the intensity in in_Param0.
+
+uniform sampler2D in_Texture2;
+
+vec4 effect(vec2 tex_coord) {
+
+    vec4 original = SAMPLE(in_Texture2, tex_coord);
+    vec4 highlights = SAMPLE(in_Texture, tex_coord);
+
+    return vec4(original.rgb + highlights.rgb * in_Param0, original.a);
+}
@@ -0,0 +1,40 @@
+
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+// Separable gaussian blur. in_Param0/in_Param1 give the direction, and
+// in_Param2 the radius in pixels.
+
+vec4 effect(vec2 tex_coord) {
+
+    vec2 offset = vec2(in_Param0, in_Param1) * in_TexelSize * (in_Param2 / 4.0);
+
+    vec4 sum = SAMPLE(in_Texture, tex_coord) * 0.2270270;
+
+    sum += (SAMPLE(in_Texture, tex_coord + offset)
+            + SAMPLE(in_Texture, tex_coord - offset)) * 0.1945946;
+
+    sum += (SAMPLE(in_Texture, tex_coord + offset * 2.0)
+            + SAMPLE(in_Texture, tex_coord - offset * 2.0)) * 0.1216216;
+
+    sum += (SAMPLE(in_Texture, tex_coord + offset * 3.0)
+            + SAMPLE(in_Texture, tex_coord - offset * 3.0)) * 0.0540540;
+
+    sum += (SAMPLE(in_Texture, tex_coord + offset * 4.0)
+            + SAMPLE(in_Texture, tex_coord - offset * 4.0)) * 0.0162162;
+
+    return sum;
+}
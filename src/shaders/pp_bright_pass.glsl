@@ -0,0 +1,29 @@
+
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+// Keeps only the parts of the image brighter than the threshold in in_Param0.
+
+vec4 effect(vec2 tex_coord) {
+
+    vec4 color = SAMPLE(in_Texture, tex_coord);
+
+    float luminance = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
+
+    float factor = max(luminance - in_Param0, 0.0) / max(luminance, 0.0001);
+
+    return vec4(color.rgb * factor, 1.0);
+}
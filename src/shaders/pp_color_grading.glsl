@@ -0,0 +1,44 @@
+
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+// Color grading using a lookup table in in_Texture2. The table is a strip of
+// N tiles of NxN pixels, where N is in_Param1. Red increases along the x axis
+// of each tile, green along the y axis, and blue from tile to tile.
+// in_Param0 is the strength of the effect.
+
+uniform sampler2D in_Texture2;
+
+vec4 effect(vec2 tex_coord) {
+
+    vec4 color = SAMPLE(in_Texture, tex_coord);
+
+    float size = in_Param1;
+
+    float blue = color.b * (size - 1.0);
+    float slice_low = floor(blue);
+    float slice_high = min(slice_low + 1.0, size - 1.0);
+
+    float x = (color.r * (size - 1.0) + 0.5) / (size * size);
+    float y = (color.g * (size - 1.0) + 0.5) / size;
+
+    vec3 graded_low = SAMPLE(in_Texture2, vec2(x + slice_low / size, y)).rgb;
+    vec3 graded_high = SAMPLE(in_Texture2, vec2(x + slice_high / size, y)).rgb;
+
+    vec3 graded = mix(graded_low, graded_high, blue - slice_low);
+
+    return vec4(mix(color.rgb, graded, in_Param0), color.a);
+}
@@ -0,0 +1,38 @@
+
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+// CRT-style scanlines. in_Param0 is the intensity, and in_Param1 the distance
+// between scanlines in pixels.
+
+vec4 effect(vec2 tex_coord) {
+
+    vec4 color = SAMPLE(in_Texture, tex_coord);
+
+    float row = tex_coord.y / in_TexelSize.y;
+
+    float scanline = 0.5 + 0.5 * cos(6.2831853 * row / in_Param1);
+
+    float column = floor(tex_coord.x / in_TexelSize.x);
+    float phase = column - 3.0 * floor(column / 3.0);
+
+    vec3 mask = vec3(
+            1.0 - 0.2 * in_Param0 * step(0.5, abs(phase - 0.0)),
+            1.0 - 0.2 * in_Param0 * step(0.5, abs(phase - 1.0)),
+            1.0 - 0.2 * in_Param0 * step(0.5, abs(phase - 2.0)));
+
+    return vec4(color.rgb * mask * (1.0 - in_Param0 * scanline), color.a);
+}
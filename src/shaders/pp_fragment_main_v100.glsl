@@ -0,0 +1,4 @@
+
+void main(void) {
+    gl_FragColor = effect(pass_TexCoord);
+}
@@ -0,0 +1,6 @@
+
+out vec4 out_FragColor;
+
+void main(void) {
+    out_FragColor = effect(pass_TexCoord);
+}
@@ -0,0 +1,28 @@
+#version 330 core
+
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+#define SAMPLE(tex, coord) texture(tex, coord)
+
+uniform sampler2D in_Texture;
+uniform vec2 in_TexelSize;
+uniform float in_Param0;
+uniform float in_Param1;
+uniform float in_Param2;
+uniform float in_Param3;
+
+in vec2 pass_TexCoord;
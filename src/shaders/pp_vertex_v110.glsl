@@ -0,0 +1,28 @@
+#version 110
+
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+attribute vec2 in_Position;
+
+varying vec2 pass_TexCoord;
+
+void main(void) {
+
+    gl_Position = vec4(in_Position, 0.0, 1.0);
+
+    pass_TexCoord = in_Position * 0.5 + 0.5;
+}
@@ -0,0 +1,30 @@
+
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+// Darkens the edges of the screen. in_Param0 is the strength, and in_Param1
+// the radius (relative to the screen corners) at which darkening starts.
+
+vec4 effect(vec2 tex_coord) {
+
+    vec4 color = SAMPLE(in_Texture, tex_coord);
+
+    float dist = distance(tex_coord, vec2(0.5)) * 1.4142136;
+
+    float factor = 1.0 - in_Param0 * smoothstep(in_Param1, 1.0, dist);
+
+    return vec4(color.rgb * factor, color.a);
+}
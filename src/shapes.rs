@@ -40,6 +40,7 @@ impl Renderer2D {
 #[derive(Debug, Clone)]
 pub struct Polygon {
     pub(crate) triangles: Vec<[Vec2; 3]>,
+    pub(crate) outline: Vec<Vec2>,
 }
 
 impl Polygon {
@@ -71,6 +72,9 @@ impl Polygon {
             ])
         }
 
-        Polygon { triangles }
+        Polygon {
+            triangles,
+            outline: vertices.iter().map(|vertex| (*vertex).into()).collect(),
+        }
     }
 }
@@ -0,0 +1,598 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+use std::collections::HashMap;
+
+use glam::{Mat2, Vec2};
+use glam_rect::{IRect, Rect};
+
+use crate::color::Color;
+
+#[cfg(feature = "image-loading")]
+use crate::glwrapper::GLTexture;
+#[cfg(feature = "image-loading")]
+use crate::image::ImageHandle;
+#[cfg(feature = "image-loading")]
+use crate::renderer2d::Renderer2D;
+
+#[cfg(feature = "text")]
+use crate::font::FormattedTextBlock;
+
+/// Records drawing operations as SVG elements. See
+/// [crate::Graphics2D::begin_svg_export].
+pub(crate) struct SvgRecorder {
+    size: Vec2,
+    defs: String,
+    body: String,
+    next_id: usize,
+    clip_group_open: bool,
+
+    #[cfg(feature = "image-loading")]
+    image_ids: HashMap<GLTexture, String>,
+    tint_filter_ids: HashMap<String, String>,
+}
+
+impl SvgRecorder {
+    pub(crate) fn new(size: Vec2) -> Self {
+        SvgRecorder {
+            size,
+            defs: String::new(),
+            body: String::new(),
+            next_id: 0,
+            clip_group_open: false,
+            #[cfg(feature = "image-loading")]
+            image_ids: HashMap::new(),
+            tint_filter_ids: HashMap::new(),
+        }
+    }
+
+    fn new_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}{}", prefix, self.next_id)
+    }
+
+    /// Adds a `clipPath` containing the specified polygon, and returns its
+    /// ID.
+    fn polygon_clip_path(&mut self, points: &[Vec2]) -> String {
+        let id = self.new_id("clip");
+        self.defs.push_str(&format!(
+            "<clipPath id=\"{}\"><polygon points=\"{}\"/></clipPath>\n",
+            id,
+            format_points(points)
+        ));
+        id
+    }
+
+    fn rect_clip_path(&mut self, rect: &Rect) -> String {
+        let id = self.new_id("clip");
+        self.defs.push_str(&format!(
+            "<clipPath id=\"{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/></clipPath>\n",
+            id,
+            rect.top_left.x,
+            rect.top_left.y,
+            rect.width(),
+            rect.height()
+        ));
+        id
+    }
+
+    pub(crate) fn clear_screen(&mut self, color: Color) {
+        // An opaque clear hides everything drawn before it, unless it's
+        // restricted by a clip
+        if color.a() >= 1.0 && !self.clip_group_open {
+            self.body.clear();
+        }
+
+        self.body.push_str(&format!(
+            "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" {}/>\n",
+            self.size.x,
+            self.size.y,
+            fill_attributes(color)
+        ));
+    }
+
+    pub(crate) fn set_clip(&mut self, rect: Option<&IRect>) {
+        if self.clip_group_open {
+            self.body.push_str("</g>\n");
+            self.clip_group_open = false;
+        }
+
+        if let Some(rect) = rect {
+            let rect = Rect::new(rect.top_left.as_vec2(), rect.bottom_right.as_vec2());
+            let id = self.rect_clip_path(&rect);
+            self.body
+                .push_str(&format!("<g clip-path=\"url(#{id})\">\n"));
+            self.clip_group_open = true;
+        }
+    }
+
+    pub(crate) fn polygon(&mut self, points: &[Vec2], colors: &[Color]) {
+        self.body.push_str(&format!(
+            "<polygon points=\"{}\" {}/>\n",
+            format_points(points),
+            fill_attributes(average_color(colors))
+        ));
+    }
+
+    pub(crate) fn rectangle(&mut self, rect: &Rect, color: Color) {
+        self.body.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>\n",
+            rect.top_left.x,
+            rect.top_left.y,
+            rect.width(),
+            rect.height(),
+            fill_attributes(color)
+        ));
+    }
+
+    pub(crate) fn line(&mut self, start: Vec2, end: Vec2, thickness: f32, color: Color) {
+        self.body.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke-width=\"{}\" {}/>\n",
+            start.x,
+            start.y,
+            end.x,
+            end.y,
+            thickness,
+            color_attributes("stroke", color)
+        ));
+    }
+
+    pub(crate) fn circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.body.push_str(&format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>\n",
+            center.x,
+            center.y,
+            radius,
+            fill_attributes(color)
+        ));
+    }
+
+    /// A triangle cut from the unit circle, as drawn by
+    /// [crate::Graphics2D::draw_circle_section_triangular_three_color].
+    pub(crate) fn circle_section(
+        &mut self,
+        positions: [Vec2; 3],
+        colors: [Color; 3],
+        circle_coords: [Vec2; 3],
+    ) {
+        let transform = match affine_transform(circle_coords, positions) {
+            None => return,
+            Some(transform) => transform,
+        };
+
+        let clip = self.polygon_clip_path(&positions);
+
+        self.body.push_str(&format!(
+            "<g clip-path=\"url(#{})\"><circle cx=\"0\" cy=\"0\" r=\"1\" transform=\"{}\" {}/></g>\n",
+            clip,
+            transform,
+            fill_attributes(average_color(&colors))
+        ));
+    }
+
+    /// An image mapped onto a polygon. The mapping from image coordinates to
+    /// positions is taken from the first three points, so the polygon must
+    /// be a triangle or a parallelogram.
+    #[cfg(feature = "image-loading")]
+    pub(crate) fn image_polygon(
+        &mut self,
+        renderer: &Renderer2D,
+        positions: &[Vec2],
+        colors: &[Color],
+        image_coords_normalized: &[Vec2],
+        image: &ImageHandle,
+    ) {
+        let size = image.size().as_vec2();
+
+        let transform = match affine_transform(
+            [
+                image_coords_normalized[0] * size,
+                image_coords_normalized[1] * size,
+                image_coords_normalized[2] * size,
+            ],
+            [positions[0], positions[1], positions[2]],
+        ) {
+            None => return,
+            Some(transform) => transform,
+        };
+
+        let image_id = match self.image_id(renderer, image) {
+            None => return,
+            Some(id) => id,
+        };
+
+        let clip = self.polygon_clip_path(positions);
+
+        let color = average_color(colors);
+        let filter = if color == Color::WHITE {
+            String::new()
+        } else {
+            format!(" filter=\"url(#{})\"", self.tint_filter_id(color))
+        };
+
+        self.body.push_str(&format!(
+            "<g clip-path=\"url(#{clip})\"><use xlink:href=\"#{image_id}\" transform=\"{transform}\"{filter}/></g>\n"
+        ));
+    }
+
+    /// Returns the ID of the definition for the specified image, embedding
+    /// it the first time it is used.
+    #[cfg(feature = "image-loading")]
+    fn image_id(&mut self, renderer: &Renderer2D, image: &ImageHandle) -> Option<String> {
+        if let Some(id) = self.image_ids.get(&image.texture) {
+            return Some(id.clone());
+        }
+
+        let data_uri = match image_data_uri(renderer, image) {
+            Some(data_uri) => data_uri,
+            None => {
+                log::warn!("Unable to embed image in SVG export");
+                return None;
+            }
+        };
+
+        let id = self.new_id("image");
+
+        self.defs.push_str(&format!(
+            "<image id=\"{}\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\" xlink:href=\"{}\"/>\n",
+            id,
+            image.size().x,
+            image.size().y,
+            data_uri
+        ));
+
+        self.image_ids.insert(image.texture.clone(), id.clone());
+
+        Some(id)
+    }
+
+    /// Returns the ID of a filter which multiplies each pixel by the
+    /// specified color, in the same way that images are tinted.
+    fn tint_filter_id(&mut self, color: Color) -> String {
+        let values = format!(
+            "{} 0 0 0 0 0 {} 0 0 0 0 0 {} 0 0 0 0 0 {} 0",
+            color.r(),
+            color.g(),
+            color.b(),
+            color.a()
+        );
+
+        if let Some(id) = self.tint_filter_ids.get(&values) {
+            return id.clone();
+        }
+
+        let id = self.new_id("tint");
+
+        self.defs.push_str(&format!(
+            "<filter id=\"{id}\" color-interpolation-filters=\"sRGB\"><feColorMatrix type=\"matrix\" values=\"{values}\"/></filter>\n"
+        ));
+
+        self.tint_filter_ids.insert(values, id.clone());
+
+        id
+    }
+
+    /// Text is converted into paths using the glyph outlines, so that the
+    /// output doesn't depend on the fonts installed on the viewer's system.
+    #[cfg(feature = "text")]
+    pub(crate) fn text(
+        &mut self,
+        position: Vec2,
+        color: Color,
+        text: &FormattedTextBlock,
+        crop_window: Option<&Rect>,
+    ) {
+        let mut builder = SvgPathBuilder {
+            data: String::new(),
+            offset: position,
+        };
+
+        for line in text.iter_lines() {
+            for glyph in line.iter_glyphs() {
+                glyph.glyph().build_outline(&mut builder);
+            }
+        }
+
+        if builder.data.is_empty() {
+            return;
+        }
+
+        let path = format!(
+            "<path d=\"{}\" {}/>",
+            builder.data.trim_end(),
+            fill_attributes(color)
+        );
+
+        match crop_window {
+            None => {
+                self.body.push_str(&path);
+                self.body.push('\n');
+            }
+            Some(crop_window) => {
+                let clip = self.rect_clip_path(crop_window);
+                self.body
+                    .push_str(&format!("<g clip-path=\"url(#{clip})\">{path}</g>\n"));
+            }
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        if self.clip_group_open {
+            self.body.push_str("</g>\n");
+        }
+
+        format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" ",
+                "xmlns:xlink=\"http://www.w3.org/1999/xlink\" ",
+                "width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+                "<defs>\n{defs}</defs>\n",
+                "{body}",
+                "</svg>\n"
+            ),
+            w = self.size.x,
+            h = self.size.y,
+            defs = self.defs,
+            body = self.body
+        )
+    }
+}
+
+#[cfg(feature = "text")]
+struct SvgPathBuilder {
+    data: String,
+    offset: Vec2,
+}
+
+#[cfg(feature = "text")]
+impl glam_rusttype::OutlineBuilder for SvgPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let p = self.offset + Vec2::new(x, y);
+        self.data.push_str(&format!("M{} {} ", p.x, p.y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.offset + Vec2::new(x, y);
+        self.data.push_str(&format!("L{} {} ", p.x, p.y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let c = self.offset + Vec2::new(x1, y1);
+        let p = self.offset + Vec2::new(x, y);
+        self.data
+            .push_str(&format!("Q{} {} {} {} ", c.x, c.y, p.x, p.y));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let c1 = self.offset + Vec2::new(x1, y1);
+        let c2 = self.offset + Vec2::new(x2, y2);
+        let p = self.offset + Vec2::new(x, y);
+        self.data.push_str(&format!(
+            "C{} {} {} {} {} {} ",
+            c1.x, c1.y, c2.x, c2.y, p.x, p.y
+        ));
+    }
+
+    fn close(&mut self) {
+        self.data.push_str("Z ");
+    }
+}
+
+fn format_points(points: &[Vec2]) -> String {
+    points
+        .iter()
+        .map(|point| format!("{},{}", point.x, point.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn color_attributes(attribute: &str, color: Color) -> String {
+    let to_u8 = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    let mut result = format!(
+        "{}=\"#{:02x}{:02x}{:02x}\"",
+        attribute,
+        to_u8(color.r()),
+        to_u8(color.g()),
+        to_u8(color.b())
+    );
+
+    if color.a() < 1.0 {
+        result.push_str(&format!(
+            " {}-opacity=\"{}\"",
+            attribute,
+            color.a().max(0.0)
+        ));
+    }
+
+    result
+}
+
+fn fill_attributes(color: Color) -> String {
+    color_attributes("fill", color)
+}
+
+/// SVG has no equivalent of per-vertex colors, so shapes with several colors
+/// are filled with their average.
+fn average_color(colors: &[Color]) -> Color {
+    let count = colors.len().max(1) as f32;
+
+    let (r, g, b, a) = colors.iter().fold((0.0, 0.0, 0.0, 0.0), |sum, color| {
+        (
+            sum.0 + color.r(),
+            sum.1 + color.g(),
+            sum.2 + color.b(),
+            sum.3 + color.a(),
+        )
+    });
+
+    Color::from_rgba(r / count, g / count, b / count, a / count)
+}
+
+/// Returns an SVG `transform` attribute value which maps each of the `from`
+/// points onto the corresponding `to` point, or `None` if the `from` points
+/// are collinear.
+fn affine_transform(from: [Vec2; 3], to: [Vec2; 3]) -> Option<String> {
+    let from_basis = Mat2::from_cols(from[1] - from[0], from[2] - from[0]);
+
+    if from_basis.determinant().abs() < f32::EPSILON {
+        return None;
+    }
+
+    let to_basis = Mat2::from_cols(to[1] - to[0], to[2] - to[0]);
+
+    let matrix = to_basis * from_basis.inverse();
+    let translation = to[0] - matrix * from[0];
+
+    Some(format!(
+        "matrix({} {} {} {} {} {})",
+        matrix.x_axis.x,
+        matrix.x_axis.y,
+        matrix.y_axis.x,
+        matrix.y_axis.y,
+        translation.x,
+        translation.y
+    ))
+}
+
+#[cfg(feature = "image-loading")]
+fn image_data_uri(renderer: &Renderer2D, image: &ImageHandle) -> Option<String> {
+    let pixels = match renderer.read_image_rgba(image) {
+        Ok(pixels) => pixels,
+        Err(err) => {
+            log::error!("Failed to read back image: {:?}", err);
+            return None;
+        }
+    };
+
+    encode_png_data_uri(image.size().x, image.size().y, &pixels)
+}
+
+#[cfg(all(feature = "image-loading", feature = "image-png"))]
+fn encode_png_data_uri(width: u32, height: u32, pixels: &[u8]) -> Option<String> {
+    use image::ImageEncoder;
+
+    let mut png = Vec::new();
+
+    if let Err(err) = image::codecs::png::PngEncoder::new(&mut png).write_image(
+        pixels,
+        width,
+        height,
+        image::ExtendedColorType::Rgba8,
+    ) {
+        log::error!("Failed to encode PNG: {:?}", err);
+        return None;
+    }
+
+    Some(format!("data:image/png;base64,{}", encode_base64(&png)))
+}
+
+#[cfg(all(feature = "image-loading", not(feature = "image-png")))]
+fn encode_png_data_uri(_width: u32, _height: u32, _pixels: &[u8]) -> Option<String> {
+    log::warn!("The \"image-png\" feature is required to embed images in SVG exports");
+    None
+}
+
+#[cfg(all(feature = "image-loading", feature = "image-png"))]
+fn encode_base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+
+        let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (value >> (18 - index * 6)) & 0x3f;
+                result.push(ALPHABET[sextet as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use glam::Vec2;
+
+    use super::*;
+
+    #[test]
+    fn test_affine_transform() {
+        let transform = affine_transform(
+            [
+                Vec2::new(0.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(0.0, 1.0),
+            ],
+            [
+                Vec2::new(10.0, 20.0),
+                Vec2::new(12.0, 20.0),
+                Vec2::new(10.0, 23.0),
+            ],
+        );
+
+        assert_eq!(transform.as_deref(), Some("matrix(2 0 0 3 10 20)"));
+
+        assert_eq!(
+            affine_transform([Vec2::ZERO, Vec2::ONE, Vec2::splat(2.0)], [Vec2::ZERO; 3]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_clear_replaces_earlier_elements() {
+        let mut svg = SvgRecorder::new(Vec2::new(100.0, 50.0));
+
+        svg.circle(Vec2::new(10.0, 10.0), 5.0, Color::RED);
+        svg.clear_screen(Color::WHITE);
+        svg.rectangle(
+            &Rect::new(Vec2::new(1.0, 2.0), Vec2::new(4.0, 6.0)),
+            Color::from_rgba(0.0, 0.0, 1.0, 0.5),
+        );
+
+        let output = svg.finish();
+
+        assert!(!output.contains("<circle"));
+        assert!(
+            output.contains("<rect x=\"0\" y=\"0\" width=\"100\" height=\"50\" fill=\"#ffffff\"/>")
+        );
+        assert!(output.contains(
+            "<rect x=\"1\" y=\"2\" width=\"3\" height=\"4\" fill=\"#0000ff\" fill-opacity=\"0.5\"/>"
+        ));
+    }
+
+    #[cfg(all(feature = "image-loading", feature = "image-png"))]
+    #[test]
+    fn test_base64() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg==");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(encode_base64(b"foo"), "Zm9v");
+        assert_eq!(encode_base64(b"foobar"), "Zm9vYmFy");
+    }
+}
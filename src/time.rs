@@ -0,0 +1,85 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+/// A point in time, used for measuring frame durations. On WebAssembly this
+/// uses `performance.now()`, as `std::time::Instant` is unavailable.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub(crate) struct Timestamp {
+    #[cfg(not(target_arch = "wasm32"))]
+    instant: std::time::Instant,
+
+    #[cfg(target_arch = "wasm32")]
+    millis: f64,
+}
+
+impl Timestamp {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn now() -> Self {
+        Timestamp {
+            instant: std::time::Instant::now(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn now() -> Self {
+        let millis = crate::web::WebWindow::new()
+            .and_then(|window| window.performance())
+            .map(|performance| performance.now())
+            .unwrap_or_else(|err| {
+                log::error!("Failed to get current time: {:?}", err);
+                0.0
+            });
+
+        Timestamp { millis }
+    }
+
+    /// Returns the number of seconds between `earlier` and this timestamp.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn secs_since(&self, earlier: &Timestamp) -> f64 {
+        self.instant
+            .saturating_duration_since(earlier.instant)
+            .as_secs_f64()
+    }
+
+    /// Returns the number of seconds between `earlier` and this timestamp.
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn secs_since(&self, earlier: &Timestamp) -> f64 {
+        ((self.millis - earlier.millis) / 1000.0).max(0.0)
+    }
+
+    /// Returns the timestamp `duration` after this one.
+    #[cfg(all(feature = "windowing", not(target_arch = "wasm32")))]
+    pub(crate) fn plus(&self, duration: std::time::Duration) -> Timestamp {
+        Timestamp {
+            instant: self.instant + duration,
+        }
+    }
+
+    /// Returns the timestamp `duration` after this one.
+    #[cfg(all(feature = "windowing", target_arch = "wasm32"))]
+    pub(crate) fn plus(&self, duration: std::time::Duration) -> Timestamp {
+        Timestamp {
+            millis: self.millis + duration.as_secs_f64() * 1000.0,
+        }
+    }
+
+    #[cfg(all(feature = "windowing", not(target_arch = "wasm32")))]
+    #[inline]
+    #[must_use]
+    pub(crate) fn instant(&self) -> std::time::Instant {
+        self.instant
+    }
+}
@@ -23,8 +23,11 @@ use std::rc::Rc;
 use wasm_bindgen::closure::{Closure, WasmClosure};
 use wasm_bindgen::JsCast;
 #[cfg(feature = "windowing")]
+use wasm_bindgen::JsValue;
+#[cfg(feature = "windowing")]
 use web_sys::{
-    AddEventListenerOptions, EventTarget, KeyboardEvent, MediaQueryListEvent, MouseEvent,
+    AddEventListenerOptions, DragEvent, Event, EventTarget, KeyboardEvent, MediaQueryListEvent,
+    MouseEvent, TouchEvent,
 };
 use web_sys::{Document, Element, HtmlCanvasElement, HtmlElement, Performance, Window};
 
@@ -190,6 +193,59 @@ impl WebWindow {
         }))
     }
 
+    #[cfg(feature = "windowing")]
+    pub fn set_timeout<T: ?Sized + 'static>(
+        &self,
+        callback: &RefCell<Closure<T>>,
+        timeout_millis: i32,
+    ) -> Result<WebPending, BacktraceError<ErrorMessage>> {
+        let timeout_id: i32 = self
+            .window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.borrow_mut().as_ref().unchecked_ref(),
+                timeout_millis,
+            )
+            .map_err(|err| ErrorMessage::msg(format!("Failed to set timeout: {err:?}")))?;
+
+        let window = self.window.clone();
+
+        Ok(WebPending::new_with_status(move |status| {
+            if status == Active {
+                window.clear_timeout_with_handle(timeout_id);
+                log::info!("Cancelled timeout {}", timeout_id);
+            }
+        }))
+    }
+
+    /// Writes the text to the clipboard asynchronously. The browser may
+    /// refuse to write it if the page doesn't have focus.
+    #[cfg(feature = "windowing")]
+    pub fn write_clipboard_text(&self, text: &str) {
+        let _ = self.window.navigator().clipboard().write_text(text);
+    }
+
+    /// Reads the text on the clipboard asynchronously, and passes it to the
+    /// callback. The callback isn't invoked if the browser refuses to read
+    /// the clipboard.
+    #[cfg(feature = "windowing")]
+    pub fn read_clipboard_text<F: FnOnce(String) + 'static>(&self, callback: F) {
+        let callback = Closure::once(move |text: JsValue| {
+            if let Some(text) = text.as_string() {
+                callback(text);
+            }
+        });
+
+        // The text is read asynchronously, so the callback must outlive this
+        // function
+        let _ = self
+            .window
+            .navigator()
+            .clipboard()
+            .read_text()
+            .then(&callback);
+        callback.forget();
+    }
+
     #[cfg(feature = "windowing")]
     pub fn device_pixel_ratio(&self) -> f64 {
         self.window.device_pixel_ratio()
@@ -225,6 +281,11 @@ impl WebDocument {
         })
     }
 
+    #[cfg(feature = "windowing")]
+    pub fn is_hidden(&self) -> bool {
+        self.document.hidden()
+    }
+
     #[cfg(feature = "windowing")]
     pub fn pointer_lock_element(&self) -> Option<WebElement> {
         self.document
@@ -314,6 +375,13 @@ impl WebElement {
         )
     }
 
+    #[cfg(feature = "windowing")]
+    pub fn top_left(&self) -> Vector2<f64> {
+        let bounding_rect = self.element.get_bounding_client_rect();
+
+        Vector2::new(bounding_rect.left(), bounding_rect.top())
+    }
+
     #[cfg(feature = "windowing")]
     #[inline]
     pub fn document(&self) -> &WebDocument {
@@ -396,7 +464,7 @@ impl WebCanvasElement {
         UVec2::new(width, height)
     }
 
-    pub fn get_webgl2_context<V>(
+    pub fn get_webgl_context<V>(
         &self,
         viewport_size_pixels: V,
     ) -> Result<GLRenderer, BacktraceError<GLRendererCreationError>>
@@ -406,10 +474,29 @@ impl WebCanvasElement {
         let viewport_size_pixels = viewport_size_pixels.into();
 
         log::info!(
-            "Getting WebGL2 context for viewport size {:?}",
+            "Getting WebGL context for viewport size {:?}",
             viewport_size_pixels
         );
 
+        match self.get_webgl2_context() {
+            Ok(gl_context) => GLRenderer::new_with_gl_backend(
+                viewport_size_pixels,
+                Rc::new(GLBackendGlow::new(gl_context)),
+                GLVersion::WebGL2_0,
+            ),
+            Err(err) => {
+                log::warn!("Falling back to WebGL1: {err:?}");
+
+                GLRenderer::new_with_gl_backend(
+                    viewport_size_pixels,
+                    Rc::new(GLBackendGlow::new(self.get_webgl1_context()?)),
+                    GLVersion::WebGL1_0,
+                )
+            }
+        }
+    }
+
+    fn get_webgl2_context(&self) -> Result<glow::Context, BacktraceError<GLRendererCreationError>> {
         let context = self
             .canvas
             .get_context("webgl2")
@@ -424,13 +511,25 @@ impl WebCanvasElement {
                 ))
             })?;
 
-        let gl_context = glow::Context::from_webgl2_context(context);
+        Ok(glow::Context::from_webgl2_context(context))
+    }
 
-        GLRenderer::new_with_gl_backend(
-            viewport_size_pixels,
-            Rc::new(GLBackendGlow::new(gl_context)),
-            GLVersion::WebGL2_0,
-        )
+    fn get_webgl1_context(&self) -> Result<glow::Context, BacktraceError<GLRendererCreationError>> {
+        let context = self
+            .canvas
+            .get_context("webgl")
+            .map_err(|err| {
+                GLRendererCreationError::msg(format!("Failed to get WebGL1 context: '{err:?}'"))
+            })?
+            .ok_or_else(|| GLRendererCreationError::msg("WebGL1 context not available"))?
+            .dyn_into::<web_sys::WebGlRenderingContext>()
+            .map_err(|err| {
+                GLRendererCreationError::msg(format!(
+                    "Failed to convert object to rendering context: '{err:?}'"
+                ))
+            })?;
+
+        Ok(glow::Context::from_webgl1_context(context))
     }
 
     #[cfg(feature = "windowing")]
@@ -548,6 +647,18 @@ impl WebEventTarget {
         self.register_event_listener(listener_type, Box::new(callback) as Box<dyn FnMut()>, false)
     }
 
+    pub fn register_event_listener_event<F: FnMut(Event) + 'static>(
+        &self,
+        listener_type: &str,
+        callback: F,
+    ) -> Result<WebPending, BacktraceError<ErrorMessage>> {
+        self.register_event_listener(
+            listener_type,
+            Box::new(callback) as Box<dyn FnMut(_)>,
+            false,
+        )
+    }
+
     pub fn register_event_listener_mouse<F: FnMut(MouseEvent) + 'static>(
         &self,
         listener_type: &str,
@@ -560,6 +671,30 @@ impl WebEventTarget {
         )
     }
 
+    pub fn register_event_listener_drag<F: FnMut(DragEvent) + 'static>(
+        &self,
+        listener_type: &str,
+        callback: F,
+    ) -> Result<WebPending, BacktraceError<ErrorMessage>> {
+        self.register_event_listener(
+            listener_type,
+            Box::new(callback) as Box<dyn FnMut(_)>,
+            false,
+        )
+    }
+
+    pub fn register_event_listener_touch<F: FnMut(TouchEvent) + 'static>(
+        &self,
+        listener_type: &str,
+        callback: F,
+    ) -> Result<WebPending, BacktraceError<ErrorMessage>> {
+        self.register_event_listener(
+            listener_type,
+            Box::new(callback) as Box<dyn FnMut(_)>,
+            false,
+        )
+    }
+
     pub fn register_event_listener_keyboard<F: FnMut(KeyboardEvent) + 'static>(
         &self,
         listener_type: &str,
@@ -14,13 +14,23 @@
  *  limitations under the License.
  */
 
+use std::cell::Cell;
 use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
+use std::path::Path;
+use std::time::Duration;
 
 use crate::error::{BacktraceError, ErrorMessage};
-use crate::{GLRenderer, Graphics2D};
+use crate::input::{ActionMap, InputState, InputTrigger};
+use crate::recording::{EventLog, EventRecorder, RecordedEvent, SerializableUserEvent};
+use crate::time::Timestamp;
+use crate::window_internal_headless::{UserEventSenderHeadless, WindowHelperHeadless};
+use crate::{GLRenderer, Graphics2D, Rect};
 use glam::{IVec2, UVec2, Vec2};
 
+#[cfg(feature = "image-loading")]
+use crate::image::{ImageDataType, RawBitmapData};
+
 #[cfg(all(not(target_arch = "wasm32"), not(any(doc, doctest))))]
 type WindowHelperInnerType<UserEventType> =
     crate::window_internal_glutin::WindowHelperGlutin<UserEventType>;
@@ -43,6 +53,27 @@ type WindowHelperInnerType<UserEventType> = PhantomData<UserEventType>;
 #[cfg(any(doc, doctest))]
 type UserEventSenderInnerType<UserEventType> = PhantomData<UserEventType>;
 
+/// The implementation behind a [WindowHelper]: either a real window, or a
+/// headless window used for replaying events.
+enum WindowHelperInner<UserEventType: 'static> {
+    Platform(WindowHelperInnerType<UserEventType>),
+    Headless(WindowHelperHeadless<UserEventType>),
+}
+
+enum UserEventSenderInner<UserEventType: 'static> {
+    Platform(UserEventSenderInnerType<UserEventType>),
+    Headless(UserEventSenderHeadless<UserEventType>),
+}
+
+impl<UserEventType> Clone for UserEventSenderInner<UserEventType> {
+    fn clone(&self) -> Self {
+        match self {
+            UserEventSenderInner::Platform(inner) => UserEventSenderInner::Platform(inner.clone()),
+            UserEventSenderInner::Headless(inner) => UserEventSenderInner::Headless(inner.clone()),
+        }
+    }
+}
+
 /// Error occurring when sending a user event.
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Copy)]
 pub enum EventLoopSendError {
@@ -52,7 +83,7 @@ pub enum EventLoopSendError {
 
 /// Allows user events to be sent to the event loop from other threads.
 pub struct UserEventSender<UserEventType: 'static> {
-    inner: UserEventSenderInnerType<UserEventType>,
+    inner: UserEventSenderInner<UserEventType>,
 }
 
 impl<UserEventType> Clone for UserEventSender<UserEventType> {
@@ -65,7 +96,15 @@ impl<UserEventType> Clone for UserEventSender<UserEventType> {
 
 impl<UserEventType> UserEventSender<UserEventType> {
     pub(crate) fn new(inner: UserEventSenderInnerType<UserEventType>) -> Self {
-        Self { inner }
+        Self {
+            inner: UserEventSenderInner::Platform(inner),
+        }
+    }
+
+    pub(crate) fn new_headless(inner: UserEventSenderHeadless<UserEventType>) -> Self {
+        Self {
+            inner: UserEventSenderInner::Headless(inner),
+        }
     }
 
     /// Sends a user-defined event to the event loop. This will cause
@@ -76,7 +115,10 @@ impl<UserEventType> UserEventSender<UserEventType> {
     /// loop.
     #[inline]
     pub fn send_event(&self, event: UserEventType) -> Result<(), EventLoopSendError> {
-        self.inner.send_event(event)
+        match &self.inner {
+            UserEventSenderInner::Platform(inner) => inner.send_event(event),
+            UserEventSenderInner::Headless(inner) => inner.send_event(event),
+        }
     }
 }
 
@@ -180,14 +222,69 @@ pub trait WindowHandler<UserEventType = ()> {
     ) {
     }
 
+    /// Invoked once per update timestep, if an update timestep has been set
+    /// using [WindowCreationOptions::with_update_timestep] or
+    /// [WindowHelper::set_update_timestep].
+    ///
+    /// Updates run immediately before each frame is drawn, as many times as
+    /// needed to catch up with the time elapsed since the previous frame, so
+    /// `timestep` is always the configured timestep. This allows simulations
+    /// to advance deterministically, regardless of the frame rate.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_update(&mut self, helper: &mut WindowHelper<UserEventType>, timestep: Duration) {}
+
     /// Invoked when the contents of the window needs to be redrawn.
     ///
     /// It is possible to request a redraw from any callback using
     /// [WindowHelper::request_redraw].
+    ///
+    /// Timing information for the frame, including the interpolation alpha
+    /// between the previous and next update, is available using
+    /// [WindowHelper::frame_timing].
     #[allow(unused_variables)]
     #[inline]
     fn on_draw(&mut self, helper: &mut WindowHelper<UserEventType>, graphics: &mut Graphics2D) {}
 
+    /// Invoked when the graphics context has been lost, for example due to a
+    /// GPU driver reset, or the browser discarding the WebGL context. No
+    /// drawing takes place until [WindowHandler::on_context_restored] is
+    /// invoked.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_context_lost(&mut self, helper: &mut WindowHelper<UserEventType>) {}
+
+    /// Invoked when the graphics context has been restored after a context
+    /// loss, and a redraw has been requested.
+    ///
+    /// Images created while [Graphics2D::set_retain_image_data] was enabled
+    /// are restored automatically. Any other images must be recreated, for
+    /// example during the next call to [WindowHandler::on_draw].
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_context_restored(&mut self, helper: &mut WindowHelper<UserEventType>) {}
+
+    /// Invoked when the window gains or loses keyboard focus.
+    ///
+    /// When focus is lost, every key and mouse button is treated as released:
+    /// [WindowHelper::input] is updated, and [WindowHandler::on_action] is
+    /// invoked for any active actions. However, [WindowHandler::on_key_up] is
+    /// not invoked, so handlers which track keys themselves should release
+    /// them here.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_focus_changed(&mut self, helper: &mut WindowHelper<UserEventType>, focused: bool) {}
+
+    /// Invoked when the window is hidden (for example, minimized, completely
+    /// covered by other windows, or in a background browser tab), or shown
+    /// again.
+    ///
+    /// While the window is hidden, no frames are drawn, unless this was
+    /// disabled using [WindowCreationOptions::with_pause_when_hidden].
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_visibility_changed(&mut self, helper: &mut WindowHelper<UserEventType>, visible: bool) {}
+
     /// Invoked when the mouse changes position.
     ///
     /// Normally, this provides the absolute  position of the mouse in the
@@ -230,6 +327,126 @@ pub trait WindowHandler<UserEventType = ()> {
     ) {
     }
 
+    /// Invoked when the user performs a pinch gesture on a touchpad.
+    ///
+    /// The `delta` is the relative change in magnification: the current zoom
+    /// level should be multiplied by `1.0 + delta`. Positive values indicate
+    /// zooming in.
+    ///
+    /// Browsers don't report pinch gestures directly, and instead send them
+    /// as wheel events with Ctrl held, scrolling by a number of pixels. For a
+    /// `WebCanvas`, if enabled using [WindowHelper::set_ctrl_wheel_pinch],
+    /// any such wheel event is delivered here instead of to
+    /// [WindowHandler::on_mouse_wheel_scroll], with a scale factor of
+    /// `exp(-delta_y / 100)`. As a mouse wheel held with Ctrl can't be told
+    /// apart from a pinch, this is disabled by default.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_pinch(&mut self, helper: &mut WindowHelper<UserEventType>, delta: f64) {}
+
+    /// Invoked when the user performs a two-finger rotation gesture on a
+    /// touchpad. The `delta` is the angle of rotation in degrees, where
+    /// positive values are counterclockwise.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_rotate_gesture(&mut self, helper: &mut WindowHelper<UserEventType>, delta: f32) {}
+
+    /// Invoked when the user performs a "smart zoom" gesture, such as a
+    /// two-finger double tap on a macOS touchpad. Applications typically
+    /// toggle between the default zoom level and a zoomed-in view of the
+    /// content under the cursor.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_smart_zoom(&mut self, helper: &mut WindowHelper<UserEventType>) {}
+
+    /// Invoked when a file is dragged over the window. If several files are
+    /// being dragged, this is invoked once for each file.
+    ///
+    /// This isn't invoked for a [crate::WebCanvas], as browsers don't reveal
+    /// which files are being dragged until they are dropped.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_file_hover(&mut self, helper: &mut WindowHelper<UserEventType>, path: &Path) {}
+
+    /// Invoked when the files being dragged over the window leave the window,
+    /// or the drag is cancelled, after [WindowHandler::on_file_hover].
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_file_hover_cancelled(&mut self, helper: &mut WindowHelper<UserEventType>) {}
+
+    /// Invoked when a file is dropped onto the window. If several files are
+    /// dropped, this is invoked once for each file.
+    ///
+    /// The file can be loaded using
+    /// [Graphics2D::create_image_from_file_path], for example. For a
+    /// [crate::WebCanvas], [WindowHandler::on_file_drop_data] is invoked
+    /// instead.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_file_drop(&mut self, helper: &mut WindowHelper<UserEventType>, path: &Path) {}
+
+    /// Invoked when a file is dropped onto a [crate::WebCanvas], once the
+    /// contents of the file have been read. If several files are dropped,
+    /// this is invoked once for each file.
+    ///
+    /// The `name` is the name of the file, without its path. Images can be
+    /// loaded from the `bytes` using
+    /// [Graphics2D::create_image_from_file_bytes], for example.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_file_drop_data(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        name: &str,
+        bytes: &[u8],
+    ) {
+    }
+
+    /// Invoked with the text on the clipboard, after
+    /// [WindowHelper::request_clipboard_text] is called. This isn't invoked
+    /// if the clipboard doesn't contain text, or can't be read.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_clipboard_text(&mut self, helper: &mut WindowHelper<UserEventType>, text: &str) {}
+
+    /// Invoked with the image on the clipboard, in [ImageDataType::RGBA]
+    /// format, after [WindowHelper::request_clipboard_image] is called. This
+    /// isn't invoked if the clipboard doesn't contain an image, or can't be
+    /// read.
+    #[cfg(feature = "image-loading")]
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_clipboard_image(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        image: &RawBitmapData,
+    ) {
+    }
+
+    /// Invoked when a finger touches, moves on, or is lifted from a
+    /// touchscreen.
+    ///
+    /// Each finger is given an `id` which stays the same from
+    /// [TouchPhase::Started] until [TouchPhase::Ended] or
+    /// [TouchPhase::Cancelled], and may be reused afterwards. The `position`
+    /// is given in the same coordinates as [WindowHandler::on_mouse_move].
+    /// The `force` is between `0.0` and `1.0`, or `None` if the device
+    /// doesn't report pressure.
+    ///
+    /// To receive mouse events for the first finger instead, see
+    /// [WindowCreationOptions::with_touch_mouse_emulation].
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_touch(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        id: TouchId,
+        phase: TouchPhase,
+        position: Vec2,
+        force: Option<f32>,
+    ) {
+    }
+
     /// Invoked when a keyboard key is pressed.
     ///
     /// To detect when a character is typed, see the
@@ -268,6 +485,16 @@ pub trait WindowHandler<UserEventType = ()> {
     ) {
     }
 
+    /// Invoked when an input method editor (IME) is used to compose text, for
+    /// example when typing Chinese, Japanese, or Korean characters. IME events
+    /// are only delivered after [WindowHelper::set_ime_allowed] is called.
+    ///
+    /// Text committed by the IME is also delivered to
+    /// [WindowHandler::on_keyboard_char], after this callback.
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_ime(&mut self, helper: &mut WindowHelper<UserEventType>, event: ImeEvent) {}
+
     /// Invoked when the state of the modifier keys has changed.
     #[allow(unused_variables)]
     #[inline]
@@ -277,182 +504,979 @@ pub trait WindowHandler<UserEventType = ()> {
         state: ModifiersState,
     ) {
     }
-}
 
-pub(crate) struct DrawingWindowHandler<UserEventType, H>
-where
-    UserEventType: 'static,
-    H: WindowHandler<UserEventType>,
-{
-    window_handler: H,
-    renderer: GLRenderer,
-    phantom: PhantomData<UserEventType>,
+    /// Invoked when one of the bindings of an action in the window's
+    /// [ActionMap] is pressed or released. This is invoked in addition to
+    /// the key and mouse button callbacks.
+    ///
+    /// See [WindowHelper::action_map_mut].
+    #[allow(unused_variables)]
+    #[inline]
+    fn on_action(&mut self, helper: &mut WindowHelper<UserEventType>, action: &str, pressed: bool) {
+    }
 }
 
-impl<UserEventType, H> DrawingWindowHandler<UserEventType, H>
+impl<UserEventType, H> WindowHandler<UserEventType> for Box<H>
 where
-    H: WindowHandler<UserEventType>,
-    UserEventType: 'static,
+    H: WindowHandler<UserEventType> + ?Sized,
 {
-    pub fn new(window_handler: H, renderer: GLRenderer) -> Self {
-        DrawingWindowHandler {
-            window_handler,
-            renderer,
-            phantom: PhantomData,
-        }
-    }
-
     #[inline]
-    pub fn on_start(&mut self, helper: &mut WindowHelper<UserEventType>, info: WindowStartupInfo) {
-        self.window_handler.on_start(helper, info);
+    fn on_start(&mut self, helper: &mut WindowHelper<UserEventType>, info: WindowStartupInfo) {
+        (**self).on_start(helper, info)
     }
 
     #[inline]
-    pub fn on_user_event(
+    fn on_user_event(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         user_event: UserEventType,
     ) {
-        self.window_handler.on_user_event(helper, user_event)
+        (**self).on_user_event(helper, user_event)
     }
 
     #[inline]
-    pub fn on_resize(&mut self, helper: &mut WindowHelper<UserEventType>, size_pixels: UVec2) {
-        self.renderer.set_viewport_size_pixels(size_pixels);
-        self.window_handler.on_resize(helper, size_pixels)
+    fn on_resize(&mut self, helper: &mut WindowHelper<UserEventType>, size_pixels: UVec2) {
+        (**self).on_resize(helper, size_pixels)
     }
 
     #[inline]
-    pub fn on_mouse_grab_status_changed(
+    fn on_mouse_grab_status_changed(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         mouse_grabbed: bool,
     ) {
-        self.window_handler
-            .on_mouse_grab_status_changed(helper, mouse_grabbed)
+        (**self).on_mouse_grab_status_changed(helper, mouse_grabbed)
     }
 
     #[inline]
-    pub fn on_fullscreen_status_changed(
+    fn on_fullscreen_status_changed(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         fullscreen: bool,
     ) {
-        self.window_handler
-            .on_fullscreen_status_changed(helper, fullscreen)
+        (**self).on_fullscreen_status_changed(helper, fullscreen)
     }
 
     #[inline]
-    pub fn on_scale_factor_changed(
+    fn on_scale_factor_changed(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         scale_factor: f64,
     ) {
-        self.window_handler
-            .on_scale_factor_changed(helper, scale_factor)
+        (**self).on_scale_factor_changed(helper, scale_factor)
     }
 
     #[inline]
-    pub fn on_draw(&mut self, helper: &mut WindowHelper<UserEventType>) {
-        let renderer = &mut self.renderer;
-        let window_handler = &mut self.window_handler;
+    fn on_update(&mut self, helper: &mut WindowHelper<UserEventType>, timestep: Duration) {
+        (**self).on_update(helper, timestep)
+    }
 
-        renderer.draw_frame(|graphics| window_handler.on_draw(helper, graphics))
+    #[inline]
+    fn on_draw(&mut self, helper: &mut WindowHelper<UserEventType>, graphics: &mut Graphics2D) {
+        (**self).on_draw(helper, graphics)
     }
 
     #[inline]
-    pub fn on_mouse_move(&mut self, helper: &mut WindowHelper<UserEventType>, position: Vec2) {
-        self.window_handler.on_mouse_move(helper, position)
+    fn on_context_lost(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        (**self).on_context_lost(helper)
     }
 
     #[inline]
-    pub fn on_mouse_button_down(
+    fn on_context_restored(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        (**self).on_context_restored(helper)
+    }
+
+    #[inline]
+    fn on_mouse_move(&mut self, helper: &mut WindowHelper<UserEventType>, position: Vec2) {
+        (**self).on_mouse_move(helper, position)
+    }
+
+    #[inline]
+    fn on_mouse_button_down(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         button: MouseButton,
     ) {
-        self.window_handler.on_mouse_button_down(helper, button)
+        (**self).on_mouse_button_down(helper, button)
     }
 
     #[inline]
-    pub fn on_mouse_button_up(
+    fn on_mouse_button_up(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         button: MouseButton,
     ) {
-        self.window_handler.on_mouse_button_up(helper, button)
+        (**self).on_mouse_button_up(helper, button)
     }
 
     #[inline]
-    pub fn on_mouse_wheel_scroll(
+    fn on_mouse_wheel_scroll(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         distance: MouseScrollDistance,
     ) {
-        self.window_handler.on_mouse_wheel_scroll(helper, distance)
+        (**self).on_mouse_wheel_scroll(helper, distance)
     }
 
     #[inline]
-    pub fn on_key_down(
+    fn on_ime(&mut self, helper: &mut WindowHelper<UserEventType>, event: ImeEvent) {
+        (**self).on_ime(helper, event)
+    }
+
+    #[inline]
+    fn on_focus_changed(&mut self, helper: &mut WindowHelper<UserEventType>, focused: bool) {
+        (**self).on_focus_changed(helper, focused)
+    }
+
+    #[inline]
+    fn on_visibility_changed(&mut self, helper: &mut WindowHelper<UserEventType>, visible: bool) {
+        (**self).on_visibility_changed(helper, visible)
+    }
+
+    #[inline]
+    fn on_pinch(&mut self, helper: &mut WindowHelper<UserEventType>, delta: f64) {
+        (**self).on_pinch(helper, delta)
+    }
+
+    #[inline]
+    fn on_rotate_gesture(&mut self, helper: &mut WindowHelper<UserEventType>, delta: f32) {
+        (**self).on_rotate_gesture(helper, delta)
+    }
+
+    #[inline]
+    fn on_smart_zoom(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        (**self).on_smart_zoom(helper)
+    }
+
+    #[inline]
+    fn on_file_hover(&mut self, helper: &mut WindowHelper<UserEventType>, path: &Path) {
+        (**self).on_file_hover(helper, path)
+    }
+
+    #[inline]
+    fn on_file_hover_cancelled(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        (**self).on_file_hover_cancelled(helper)
+    }
+
+    #[inline]
+    fn on_file_drop(&mut self, helper: &mut WindowHelper<UserEventType>, path: &Path) {
+        (**self).on_file_drop(helper, path)
+    }
+
+    #[inline]
+    fn on_file_drop_data(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        name: &str,
+        bytes: &[u8],
+    ) {
+        (**self).on_file_drop_data(helper, name, bytes)
+    }
+
+    #[inline]
+    fn on_clipboard_text(&mut self, helper: &mut WindowHelper<UserEventType>, text: &str) {
+        (**self).on_clipboard_text(helper, text)
+    }
+
+    #[cfg(feature = "image-loading")]
+    #[inline]
+    fn on_clipboard_image(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        image: &RawBitmapData,
+    ) {
+        (**self).on_clipboard_image(helper, image)
+    }
+
+    #[inline]
+    fn on_touch(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        id: TouchId,
+        phase: TouchPhase,
+        position: Vec2,
+        force: Option<f32>,
+    ) {
+        (**self).on_touch(helper, id, phase, position, force)
+    }
+
+    #[inline]
+    fn on_key_down(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         virtual_key_code: Option<VirtualKeyCode>,
         scancode: KeyScancode,
     ) {
-        self.window_handler
-            .on_key_down(helper, virtual_key_code, scancode)
+        (**self).on_key_down(helper, virtual_key_code, scancode)
     }
 
     #[inline]
-    pub fn on_key_up(
+    fn on_key_up(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         virtual_key_code: Option<VirtualKeyCode>,
         scancode: KeyScancode,
     ) {
-        self.window_handler
-            .on_key_up(helper, virtual_key_code, scancode)
+        (**self).on_key_up(helper, virtual_key_code, scancode)
     }
 
     #[inline]
-    pub fn on_keyboard_char(
+    fn on_keyboard_char(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         unicode_codepoint: char,
     ) {
-        self.window_handler
-            .on_keyboard_char(helper, unicode_codepoint)
+        (**self).on_keyboard_char(helper, unicode_codepoint)
     }
 
     #[inline]
-    pub fn on_keyboard_modifiers_changed(
+    fn on_keyboard_modifiers_changed(
         &mut self,
         helper: &mut WindowHelper<UserEventType>,
         state: ModifiersState,
     ) {
-        self.window_handler
-            .on_keyboard_modifiers_changed(helper, state)
+        (**self).on_keyboard_modifiers_changed(helper, state)
+    }
+
+    #[inline]
+    fn on_action(&mut self, helper: &mut WindowHelper<UserEventType>, action: &str, pressed: bool) {
+        (**self).on_action(helper, action, pressed)
     }
 }
 
-/// A set of helper methods to perform actions on a [crate::Window].
-pub struct WindowHelper<UserEventType = ()>
+/// The maximum amount of time which fixed timestep updates will catch up on
+/// in a single frame, so that a long stall (such as the window being dragged)
+/// doesn't cause a burst of updates.
+const MAX_UPDATE_CATCH_UP: Duration = Duration::from_millis(250);
+
+/// Measures the time between frames, and works out how many fixed timestep
+/// updates are due before each frame.
+#[derive(Default)]
+struct FrameClock {
+    update_timestep: Option<Duration>,
+    update_accumulator: Duration,
+    first_frame_start: Option<Timestamp>,
+    last_frame_start: Option<Timestamp>,
+    frame_index: u64,
+}
+
+impl FrameClock {
+    fn set_update_timestep(&mut self, update_timestep: Option<Duration>) {
+        self.update_timestep = update_timestep.filter(|timestep| !timestep.is_zero());
+        self.update_accumulator = Duration::ZERO;
+    }
+
+    /// Starts a new frame, returning the timing of the frame, and the number
+    /// of updates to run before drawing it.
+    fn begin_frame(&mut self, now: Timestamp) -> (FrameTiming, u32) {
+        let first_frame_start = *self.first_frame_start.get_or_insert(now);
+
+        let delta = match &self.last_frame_start {
+            None => Duration::ZERO,
+            Some(last_frame_start) => Duration::from_secs_f64(now.secs_since(last_frame_start)),
+        };
+
+        self.last_frame_start = Some(now);
+
+        let mut update_count = 0;
+
+        let interpolation_alpha = match self.update_timestep {
+            None => 1.0,
+            Some(timestep) => {
+                self.update_accumulator += delta.min(MAX_UPDATE_CATCH_UP);
+
+                while self.update_accumulator >= timestep {
+                    self.update_accumulator -= timestep;
+                    update_count += 1;
+                }
+
+                self.update_accumulator.as_secs_f64() / timestep.as_secs_f64()
+            }
+        };
+
+        let timing = FrameTiming {
+            delta,
+            frame_index: self.frame_index,
+            timestamp: Duration::from_secs_f64(now.secs_since(&first_frame_start)),
+            interpolation_alpha,
+        };
+
+        self.frame_index += 1;
+
+        (timing, update_count)
+    }
+}
+
+pub(crate) struct DrawingWindowHandler<UserEventType, H>
 where
     UserEventType: 'static,
+    H: WindowHandler<UserEventType>,
 {
-    inner: WindowHelperInnerType<UserEventType>,
+    window_handler: H,
+    renderer: GLRenderer,
+    logical_coordinates: bool,
+    debug_overlay_hotkey: Option<VirtualKeyCode>,
+    mouse_grabbed: bool,
+    touch_mouse_emulation: bool,
+    primary_touch: Option<TouchId>,
+    frame_clock: FrameClock,
+    phantom: PhantomData<UserEventType>,
 }
 
-impl<UserEventType> WindowHelper<UserEventType> {
-    pub(crate) fn new(inner: WindowHelperInnerType<UserEventType>) -> Self {
-        WindowHelper { inner }
+impl<UserEventType, H> DrawingWindowHandler<UserEventType, H>
+where
+    H: WindowHandler<UserEventType>,
+    UserEventType: 'static,
+{
+    pub fn new(window_handler: H, renderer: GLRenderer) -> Self {
+        DrawingWindowHandler {
+            window_handler,
+            renderer,
+            logical_coordinates: false,
+            debug_overlay_hotkey: None,
+            mouse_grabbed: false,
+            touch_mouse_emulation: false,
+            primary_touch: None,
+            frame_clock: FrameClock::default(),
+            phantom: PhantomData,
+        }
     }
 
-    #[inline]
-    #[must_use]
-    pub(crate) fn inner(&mut self) -> &mut WindowHelperInnerType<UserEventType> {
-        &mut self.inner
-    }
+    fn apply_pending_renderer_changes(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        if let Some(virtual_resolution) = helper.pending_virtual_resolution.take() {
+            if let Err(err) = self.renderer.set_virtual_resolution(virtual_resolution) {
+                log::error!("Failed to set virtual resolution: {:?}", err);
+            }
+        }
+
+        if let Some(enabled) = helper.pending_debug_overlay_enabled.take() {
+            self.renderer.set_debug_overlay_enabled(enabled);
+        }
+
+        // Converted after any change to the virtual resolution, above
+        if let Some(area) = helper.pending_ime_cursor_area.take() {
+            helper.set_ime_cursor_area_pixels(Rect::new(
+                self.convert_position_to_window(area.top_left),
+                self.convert_position_to_window(area.bottom_right),
+            ));
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_logical_coordinates(mut self, logical_coordinates: bool) -> Self {
+        self.logical_coordinates = logical_coordinates;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_debug_overlay_hotkey(mut self, hotkey: Option<VirtualKeyCode>) -> Self {
+        self.debug_overlay_hotkey = hotkey;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_update_timestep(mut self, update_timestep: Option<Duration>) -> Self {
+        self.frame_clock.set_update_timestep(update_timestep);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_touch_mouse_emulation(mut self, touch_mouse_emulation: bool) -> Self {
+        self.touch_mouse_emulation = touch_mouse_emulation;
+        self
+    }
+
+    #[inline]
+    pub fn on_start(&mut self, helper: &mut WindowHelper<UserEventType>, info: WindowStartupInfo) {
+        if self.logical_coordinates {
+            self.renderer.set_scale_factor(info.scale_factor());
+        }
+        if self.frame_clock.update_timestep.is_some() {
+            helper.request_redraw();
+        }
+        self.window_handler.on_start(helper, info);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn window_handler(&self) -> &H {
+        &self.window_handler
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn window_handler_mut(&mut self) -> &mut H {
+        &mut self.window_handler
+    }
+
+    #[inline]
+    pub fn on_user_event(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        user_event: UserEventType,
+    ) {
+        helper.record_user_event(&user_event);
+        self.window_handler.on_user_event(helper, user_event)
+    }
+
+    #[inline]
+    pub fn on_resize(&mut self, helper: &mut WindowHelper<UserEventType>, size_pixels: UVec2) {
+        helper.record_event(RecordedEvent::Resize(size_pixels));
+        self.renderer.set_viewport_size_pixels(size_pixels);
+        self.window_handler.on_resize(helper, size_pixels)
+    }
+
+    #[inline]
+    pub fn on_mouse_grab_status_changed(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        mouse_grabbed: bool,
+    ) {
+        helper.record_event(RecordedEvent::MouseGrabStatusChanged(mouse_grabbed));
+        self.mouse_grabbed = mouse_grabbed;
+        self.window_handler
+            .on_mouse_grab_status_changed(helper, mouse_grabbed)
+    }
+
+    #[inline]
+    pub fn on_fullscreen_status_changed(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        fullscreen: bool,
+    ) {
+        helper.record_event(RecordedEvent::FullscreenStatusChanged(fullscreen));
+        self.window_handler
+            .on_fullscreen_status_changed(helper, fullscreen)
+    }
+
+    #[inline]
+    pub fn on_scale_factor_changed(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        scale_factor: f64,
+    ) {
+        helper.record_event(RecordedEvent::ScaleFactorChanged(scale_factor));
+        if self.logical_coordinates {
+            self.renderer.set_scale_factor(scale_factor);
+        }
+        self.window_handler
+            .on_scale_factor_changed(helper, scale_factor)
+    }
+
+    pub fn on_focus_changed(&mut self, helper: &mut WindowHelper<UserEventType>, focused: bool) {
+        helper.record_event(RecordedEvent::FocusChanged(focused));
+
+        if !focused {
+            helper.input.release_all();
+        }
+
+        self.window_handler.on_focus_changed(helper, focused);
+
+        if !focused {
+            for action in helper.action_map.release_all() {
+                self.window_handler.on_action(helper, &action, false);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn on_visibility_changed(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        visible: bool,
+    ) {
+        helper.record_event(RecordedEvent::VisibilityChanged(visible));
+        self.window_handler.on_visibility_changed(helper, visible)
+    }
+
+    pub fn on_context_lost(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        helper.record_event(RecordedEvent::ContextLost);
+        log::warn!("Graphics context lost");
+        self.renderer.notify_context_lost();
+        self.window_handler.on_context_lost(helper)
+    }
+
+    /// Restores the renderer, after the lost context has been restored (or
+    /// replaced, in which case the renderer must already have been given the
+    /// new backend).
+    pub fn on_context_restored(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        helper.record_event(RecordedEvent::ContextRestored);
+        log::info!("Graphics context restored");
+
+        if let Err(err) = self.renderer.notify_context_restored() {
+            log::error!("Failed to restore renderer after context loss: {:?}", err);
+            return;
+        }
+
+        helper.request_redraw();
+        self.window_handler.on_context_restored(helper)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    #[must_use]
+    pub fn renderer(&self) -> &GLRenderer {
+        &self.renderer
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn renderer_mut(&mut self) -> &mut GLRenderer {
+        &mut self.renderer
+    }
+
+    #[inline]
+    pub fn on_draw(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        self.on_draw_at(helper, Timestamp::now())
+    }
+
+    /// Draws a frame, timed as if it started at the specified time.
+    pub fn on_draw_at(&mut self, helper: &mut WindowHelper<UserEventType>, now: Timestamp) {
+        helper.record_event(RecordedEvent::Draw);
+
+        if let Some(update_timestep) = helper.pending_update_timestep.take() {
+            self.frame_clock.set_update_timestep(update_timestep);
+        }
+
+        let (timing, update_count) = self.frame_clock.begin_frame(now);
+
+        if let Some(update_timestep) = self.frame_clock.update_timestep {
+            for _ in 0..update_count {
+                self.window_handler.on_update(helper, update_timestep);
+            }
+
+            // Keep drawing frames, so that the updates continue to run
+            helper.request_redraw();
+        }
+
+        helper.frame_timing.set(timing);
+
+        if self.renderer.is_context_lost() {
+            // Nothing can be drawn until the context is restored
+        } else {
+            self.apply_pending_renderer_changes(helper);
+
+            let renderer = &mut self.renderer;
+            let window_handler = &mut self.window_handler;
+
+            renderer.draw_frame(|graphics| window_handler.on_draw(helper, graphics));
+        }
+
+        helper.input.on_frame_end();
+    }
+
+    /// Converts a position in physical pixels to the coordinates given to the
+    /// handler. Relative movements (while the mouse is grabbed) are only
+    /// scaled.
+    fn convert_window_position(&self, position: Vec2, relative: bool) -> Vec2 {
+        if self.renderer.virtual_resolution().is_some() {
+            if relative {
+                // Relative movement, so there's no offset to remove
+                position / self.renderer.virtual_resolution_scale()
+            } else {
+                self.renderer.convert_window_position_to_virtual(position)
+            }
+        } else if self.logical_coordinates {
+            position / self.renderer.scale_factor() as f32
+        } else {
+            position
+        }
+    }
+
+    /// The inverse of [DrawingWindowHandler::convert_window_position], for
+    /// absolute positions.
+    fn convert_position_to_window(&self, position: Vec2) -> Vec2 {
+        if self.renderer.virtual_resolution().is_some() {
+            self.renderer.convert_virtual_position_to_window(position)
+        } else if self.logical_coordinates {
+            position * self.renderer.scale_factor() as f32
+        } else {
+            position
+        }
+    }
+
+    #[inline]
+    pub fn on_mouse_move(&mut self, helper: &mut WindowHelper<UserEventType>, position: Vec2) {
+        helper.record_event(RecordedEvent::MouseMove(position));
+        self.apply_pending_renderer_changes(helper);
+
+        let position = self.convert_window_position(position, self.mouse_grabbed);
+        self.dispatch_mouse_move(helper, position);
+    }
+
+    fn dispatch_mouse_move(&mut self, helper: &mut WindowHelper<UserEventType>, position: Vec2) {
+        helper.input.on_mouse_move(position, self.mouse_grabbed);
+        self.window_handler.on_mouse_move(helper, position)
+    }
+
+    #[inline]
+    pub fn on_mouse_button_down(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        button: MouseButton,
+    ) {
+        helper.record_event(RecordedEvent::MouseButtonDown(button));
+        self.dispatch_mouse_button_down(helper, button);
+    }
+
+    fn dispatch_mouse_button_down(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        button: MouseButton,
+    ) {
+        helper.input.on_mouse_button_down(button);
+        self.window_handler.on_mouse_button_down(helper, button);
+        self.on_triggers_down(helper, &[InputTrigger::MouseButton(button)]);
+    }
+
+    #[inline]
+    pub fn on_mouse_button_up(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        button: MouseButton,
+    ) {
+        helper.record_event(RecordedEvent::MouseButtonUp(button));
+        self.dispatch_mouse_button_up(helper, button);
+    }
+
+    fn dispatch_mouse_button_up(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        button: MouseButton,
+    ) {
+        helper.input.on_mouse_button_up(button);
+        self.window_handler.on_mouse_button_up(helper, button);
+        self.on_triggers_up(helper, &[InputTrigger::MouseButton(button)]);
+    }
+
+    #[inline]
+    pub fn on_mouse_wheel_scroll(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        distance: MouseScrollDistance,
+    ) {
+        helper.record_event(RecordedEvent::MouseWheelScroll(distance));
+        helper.input.on_mouse_wheel_scroll(distance);
+        self.window_handler.on_mouse_wheel_scroll(helper, distance)
+    }
+
+    #[inline]
+    pub fn on_pinch(&mut self, helper: &mut WindowHelper<UserEventType>, delta: f64) {
+        helper.record_event(RecordedEvent::Pinch(delta));
+        self.window_handler.on_pinch(helper, delta)
+    }
+
+    #[inline]
+    pub fn on_rotate_gesture(&mut self, helper: &mut WindowHelper<UserEventType>, delta: f32) {
+        helper.record_event(RecordedEvent::RotateGesture(delta));
+        self.window_handler.on_rotate_gesture(helper, delta)
+    }
+
+    #[inline]
+    pub fn on_smart_zoom(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        helper.record_event(RecordedEvent::SmartZoom);
+        self.window_handler.on_smart_zoom(helper)
+    }
+
+    #[inline]
+    pub fn on_file_hover(&mut self, helper: &mut WindowHelper<UserEventType>, path: &Path) {
+        helper.record_event(RecordedEvent::FileHover(path.to_path_buf()));
+        self.window_handler.on_file_hover(helper, path)
+    }
+
+    #[inline]
+    pub fn on_file_hover_cancelled(&mut self, helper: &mut WindowHelper<UserEventType>) {
+        helper.record_event(RecordedEvent::FileHoverCancelled);
+        self.window_handler.on_file_hover_cancelled(helper)
+    }
+
+    #[inline]
+    pub fn on_file_drop(&mut self, helper: &mut WindowHelper<UserEventType>, path: &Path) {
+        helper.record_event(RecordedEvent::FileDrop(path.to_path_buf()));
+        self.window_handler.on_file_drop(helper, path)
+    }
+
+    pub fn on_file_drop_data(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        name: &str,
+        bytes: &[u8],
+    ) {
+        // Avoids copying the file unless it's needed
+        if helper.is_event_recording() {
+            helper.record_event(RecordedEvent::FileDropData(
+                name.to_string(),
+                bytes.to_vec(),
+            ));
+        }
+
+        self.window_handler.on_file_drop_data(helper, name, bytes)
+    }
+
+    pub fn on_clipboard_text(&mut self, helper: &mut WindowHelper<UserEventType>, text: &str) {
+        helper.record_event(RecordedEvent::ClipboardText(text.to_string()));
+        self.window_handler.on_clipboard_text(helper, text)
+    }
+
+    #[cfg(feature = "image-loading")]
+    pub fn on_clipboard_image(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        image: &RawBitmapData,
+    ) {
+        // Avoids copying the image unless it's needed
+        if helper.is_event_recording() {
+            helper.record_event(RecordedEvent::ClipboardImage(
+                image.size(),
+                image.data().clone(),
+            ));
+        }
+
+        self.window_handler.on_clipboard_image(helper, image)
+    }
+
+    /// Returns true if mouse events are currently emulated from touches.
+    #[cfg(target_arch = "wasm32")]
+    #[inline]
+    #[must_use]
+    pub fn is_touch_mouse_emulation_enabled(&self) -> bool {
+        self.touch_mouse_emulation
+    }
+
+    /// Delivers a touch event, followed by any mouse events emulated from the
+    /// primary touch. The emulated events aren't recorded, as they're
+    /// generated again when the touch event is replayed.
+    pub fn on_touch(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        id: TouchId,
+        phase: TouchPhase,
+        position: Vec2,
+        force: Option<f32>,
+    ) {
+        helper.record_event(RecordedEvent::Touch(id, phase, position, force));
+        self.apply_pending_renderer_changes(helper);
+
+        if let Some(enabled) = helper.pending_touch_mouse_emulation.take() {
+            self.touch_mouse_emulation = enabled;
+        }
+
+        let position = self.convert_window_position(position, false);
+
+        self.window_handler
+            .on_touch(helper, id, phase, position, force);
+
+        if !self.touch_mouse_emulation || self.mouse_grabbed {
+            return;
+        }
+
+        match phase {
+            TouchPhase::Started => {
+                if self.primary_touch.is_none() {
+                    self.primary_touch = Some(id);
+                    self.dispatch_mouse_move(helper, position);
+                    self.dispatch_mouse_button_down(helper, MouseButton::Left);
+                }
+            }
+            TouchPhase::Moved => {
+                if self.primary_touch == Some(id) {
+                    self.dispatch_mouse_move(helper, position);
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                if self.primary_touch == Some(id) {
+                    self.primary_touch = None;
+                    self.dispatch_mouse_move(helper, position);
+                    self.dispatch_mouse_button_up(helper, MouseButton::Left);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub fn on_key_down(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        virtual_key_code: Option<VirtualKeyCode>,
+        scancode: KeyScancode,
+    ) {
+        helper.record_event(RecordedEvent::KeyDown(virtual_key_code, scancode));
+
+        if virtual_key_code.is_some() && virtual_key_code == self.debug_overlay_hotkey {
+            let enabled = !self.renderer.is_debug_overlay_enabled();
+            self.renderer.set_debug_overlay_enabled(enabled);
+            helper.request_redraw();
+        }
+
+        helper.input.on_key_down(virtual_key_code, scancode);
+        self.window_handler
+            .on_key_down(helper, virtual_key_code, scancode);
+        self.on_triggers_down(helper, &key_triggers(virtual_key_code, scancode));
+    }
+
+    #[inline]
+    pub fn on_key_up(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        virtual_key_code: Option<VirtualKeyCode>,
+        scancode: KeyScancode,
+    ) {
+        helper.record_event(RecordedEvent::KeyUp(virtual_key_code, scancode));
+        helper.input.on_key_up(virtual_key_code, scancode);
+        self.window_handler
+            .on_key_up(helper, virtual_key_code, scancode);
+        self.on_triggers_up(helper, &key_triggers(virtual_key_code, scancode));
+    }
+
+    #[inline]
+    pub fn on_keyboard_char(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        unicode_codepoint: char,
+    ) {
+        helper.record_event(RecordedEvent::KeyboardChar(unicode_codepoint));
+        self.window_handler
+            .on_keyboard_char(helper, unicode_codepoint)
+    }
+
+    /// Delivers an IME event, followed by the committed characters (if any)
+    /// to [WindowHandler::on_keyboard_char]. The characters aren't recorded,
+    /// as they're delivered again when the IME event is replayed.
+    pub fn on_ime(&mut self, helper: &mut WindowHelper<UserEventType>, event: ImeEvent) {
+        helper.record_event(RecordedEvent::Ime(event.clone()));
+
+        let committed = match &event {
+            ImeEvent::Commit(text) => Some(text.clone()),
+            _ => None,
+        };
+
+        self.window_handler.on_ime(helper, event);
+
+        for unicode_codepoint in committed.iter().flat_map(|text| text.chars()) {
+            self.window_handler
+                .on_keyboard_char(helper, unicode_codepoint);
+        }
+    }
+
+    #[inline]
+    pub fn on_keyboard_modifiers_changed(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        state: ModifiersState,
+    ) {
+        helper.record_event(RecordedEvent::KeyboardModifiersChanged(state.clone()));
+        helper.input.on_keyboard_modifiers_changed(state.clone());
+        self.window_handler
+            .on_keyboard_modifiers_changed(helper, state)
+    }
+
+    fn on_triggers_down(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        triggers: &[InputTrigger],
+    ) {
+        let modifiers = helper.input.modifiers().clone();
+
+        for action in helper.action_map.on_triggers_down(triggers, &modifiers) {
+            self.window_handler.on_action(helper, &action, true);
+        }
+    }
+
+    fn on_triggers_up(
+        &mut self,
+        helper: &mut WindowHelper<UserEventType>,
+        triggers: &[InputTrigger],
+    ) {
+        for action in helper.action_map.on_triggers_up(triggers) {
+            self.window_handler.on_action(helper, &action, false);
+        }
+    }
+}
+
+fn key_triggers(
+    virtual_key_code: Option<VirtualKeyCode>,
+    scancode: KeyScancode,
+) -> Vec<InputTrigger> {
+    let mut triggers = vec![InputTrigger::Scancode(scancode)];
+
+    if let Some(virtual_key_code) = virtual_key_code {
+        triggers.push(InputTrigger::Key(virtual_key_code));
+    }
+
+    triggers
+}
+
+/// A set of helper methods to perform actions on a [crate::Window].
+pub struct WindowHelper<UserEventType = ()>
+where
+    UserEventType: 'static,
+{
+    inner: WindowHelperInner<UserEventType>,
+    pending_virtual_resolution: Cell<Option<Option<UVec2>>>,
+    pending_debug_overlay_enabled: Cell<Option<bool>>,
+    pending_touch_mouse_emulation: Cell<Option<bool>>,
+    pending_ime_cursor_area: Cell<Option<Rect>>,
+    pending_update_timestep: Cell<Option<Option<Duration>>>,
+    frame_timing: Cell<FrameTiming>,
+    input: InputState,
+    action_map: ActionMap,
+    event_recorder: Option<EventRecorder<UserEventType>>,
+}
+
+impl<UserEventType> WindowHelper<UserEventType> {
+    pub(crate) fn new(inner: WindowHelperInnerType<UserEventType>) -> Self {
+        Self::new_with_inner(WindowHelperInner::Platform(inner))
+    }
+
+    pub(crate) fn new_headless(inner: WindowHelperHeadless<UserEventType>) -> Self {
+        Self::new_with_inner(WindowHelperInner::Headless(inner))
+    }
+
+    fn new_with_inner(inner: WindowHelperInner<UserEventType>) -> Self {
+        WindowHelper {
+            inner,
+            pending_virtual_resolution: Cell::new(None),
+            pending_debug_overlay_enabled: Cell::new(None),
+            pending_touch_mouse_emulation: Cell::new(None),
+            pending_ime_cursor_area: Cell::new(None),
+            pending_update_timestep: Cell::new(None),
+            frame_timing: Cell::new(FrameTiming::default()),
+            input: InputState::default(),
+            action_map: ActionMap::default(),
+            event_recorder: None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn inner(&mut self) -> &mut WindowHelperInnerType<UserEventType> {
+        match &mut self.inner {
+            WindowHelperInner::Platform(inner) => inner,
+            WindowHelperInner::Headless(_) => panic!("Expected a platform window helper"),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn headless(&self) -> &WindowHelperHeadless<UserEventType> {
+        match &self.inner {
+            WindowHelperInner::Headless(inner) => inner,
+            WindowHelperInner::Platform(_) => panic!("Expected a headless window helper"),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn headless_mut(&mut self) -> &mut WindowHelperHeadless<UserEventType> {
+        match &mut self.inner {
+            WindowHelperInner::Headless(inner) => inner,
+            WindowHelperInner::Platform(_) => panic!("Expected a headless window helper"),
+        }
+    }
 
     /// Causes the event loop to stop processing events, and terminate the
     /// application.
@@ -460,49 +1484,229 @@ impl<UserEventType> WindowHelper<UserEventType> {
     /// Note: The event loop will stop only once the current callback has
     /// returned, rather than terminating immediately.
     ///
-    /// Once the event loop has stopped, the entire process will end with error
-    /// code 0, even if other threads are running.
+    /// Once the event loop has stopped, the entire process will end with error
+    /// code 0, even if other threads are running.
+    ///
+    /// If your `WindowHandler` struct implements `Drop`, it will be safely
+    /// destructed before exiting.
+    ///
+    /// No further callbacks will be given once this function has been called.
+    pub fn terminate_loop(&mut self) {
+        match &mut self.inner {
+            WindowHelperInner::Platform(inner) => inner.terminate_loop(),
+            WindowHelperInner::Headless(inner) => inner.terminate_loop(),
+        }
+    }
+
+    /// Closes this window once the current callback has returned, and drops
+    /// its handler.
+    ///
+    /// For the window passed to [crate::Window::run_loop], and for
+    /// `WebCanvas`, this is equivalent to [WindowHelper::terminate_loop].
+    pub fn close_window(&mut self) {
+        match &mut self.inner {
+            WindowHelperInner::Platform(inner) => inner.close_window(),
+            WindowHelperInner::Headless(inner) => inner.close_window(),
+        }
+    }
+
+    /// Opens an additional window, with its own handler and renderer. The
+    /// window is created once the current callback has returned, at which
+    /// point [WindowHandler::on_start] is invoked on the new handler. If the
+    /// window could not be created, an error is logged.
+    ///
+    /// All windows run on the same event loop. Each window receives the
+    /// events relating to it, while user events (see
+    /// [WindowHelper::create_user_event_sender]) are only delivered to the
+    /// window passed to [crate::Window::run_loop]. Closing that window ends
+    /// the application, while closing an additional window just drops its
+    /// handler.
+    ///
+    /// Where the platform allows it, the graphics contexts of all windows
+    /// share their resources, so an image created in one window may be
+    /// drawn in another. If the platform doesn't allow it (a warning is
+    /// logged), or once a window's context has been recreated after a
+    /// context loss, images from other windows can't be drawn in that
+    /// window: an error is logged and nothing is drawn. The multisampling
+    /// level of additional windows is the same as that of the first window.
+    ///
+    /// For `WebCanvas`, this function has no effect.
+    pub fn create_window<H>(&self, title: &str, options: WindowCreationOptions, handler: H)
+    where
+        H: WindowHandler<UserEventType> + 'static,
+    {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => {
+                inner.create_window(title, options, Box::new(handler))
+            }
+            WindowHelperInner::Headless(inner) => {
+                inner.create_window(title, options, Box::new(handler))
+            }
+        }
+    }
+
+    /// Sets the window icon from the provided RGBA pixels.
+    ///
+    /// On Windows, the base icon size is 16x16, however a multiple of this
+    /// (e.g. 32x32) should be provided for high-resolution displays.
+    ///
+    /// For `WebCanvas`, this function has no effect.
+    pub fn set_icon_from_rgba_pixels<S>(
+        &self,
+        data: Vec<u8>,
+        size: S,
+    ) -> Result<(), BacktraceError<ErrorMessage>>
+    where
+        S: Into<UVec2>,
+    {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => {
+                inner.set_icon_from_rgba_pixels(data, size.into())
+            }
+            WindowHelperInner::Headless(inner) => {
+                inner.set_icon_from_rgba_pixels(data, size.into())
+            }
+        }
+    }
+
+    /// Sets the visibility of the mouse cursor.
+    pub fn set_cursor_visible(&self, visible: bool) {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_cursor_visible(visible),
+            WindowHelperInner::Headless(inner) => inner.set_cursor_visible(visible),
+        }
+    }
+
+    /// Grabs the cursor, preventing it from leaving the window.
+    pub fn set_cursor_grab(&self, grabbed: bool) -> Result<(), BacktraceError<ErrorMessage>> {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_cursor_grab(grabbed),
+            WindowHelperInner::Headless(inner) => inner.set_cursor_grab(grabbed),
+        }
+    }
+
+    /// Set to false to prevent the user from resizing the window.
+    ///
+    /// For `WebCanvas`, this function has no effect.
+    pub fn set_resizable(&self, resizable: bool) {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_resizable(resizable),
+            WindowHelperInner::Headless(inner) => inner.set_resizable(resizable),
+        }
+    }
+
+    /// Enables or disables input method editors (IMEs), which are used to
+    /// type text in languages such as Chinese, Japanese, and Korean. This
+    /// should be enabled while a text field has focus. IMEs are disabled by
+    /// default.
+    ///
+    /// See [WindowHandler::on_ime]. For `WebCanvas`, this function has no
+    /// effect.
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_ime_allowed(allowed),
+            WindowHelperInner::Headless(inner) => inner.set_ime_allowed(allowed),
+        }
+    }
+
+    /// Sets the area of the window which contains the text cursor. The IME
+    /// places its candidate window next to this area, so it doesn't cover
+    /// the text being composed.
+    ///
+    /// The area uses the same coordinates as the mouse position passed to
+    /// [WindowHandler::on_mouse_move], taking into account
+    /// [WindowCreationOptions::with_logical_coordinates] and any virtual
+    /// resolution. The change takes effect from the next frame, and a redraw
+    /// is requested.
     ///
-    /// If your `WindowHandler` struct implements `Drop`, it will be safely
-    /// destructed before exiting.
+    /// For `WebCanvas`, this function has no effect.
+    pub fn set_ime_cursor_area(&self, area: Rect) {
+        self.pending_ime_cursor_area.set(Some(area));
+        self.request_redraw();
+    }
+
+    fn set_ime_cursor_area_pixels(&self, area_pixels: Rect) {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_ime_cursor_area(area_pixels),
+            WindowHelperInner::Headless(inner) => inner.set_ime_cursor_area(area_pixels),
+        }
+    }
+
+    /// Places the specified text on the clipboard.
     ///
-    /// No further callbacks will be given once this function has been called.
-    pub fn terminate_loop(&mut self) {
-        self.inner.terminate_loop()
+    /// For `WebCanvas`, the text is written asynchronously, and the browser
+    /// may refuse to write it unless this is called in response to user
+    /// input, such as a key press.
+    pub fn set_clipboard_text<S: AsRef<str>>(
+        &self,
+        text: S,
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_clipboard_text(text.as_ref()),
+            WindowHelperInner::Headless(inner) => inner.set_clipboard_text(text.as_ref()),
+        }
     }
 
-    /// Sets the window icon from the provided RGBA pixels.
+    /// Requests the text on the clipboard, which is delivered to
+    /// [WindowHandler::on_clipboard_text] once it has been read.
     ///
-    /// On Windows, the base icon size is 16x16, however a multiple of this
-    /// (e.g. 32x32) should be provided for high-resolution displays.
+    /// For `WebCanvas`, the browser may ask the user for permission before
+    /// the text is delivered.
+    pub fn request_clipboard_text(&self) {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.request_clipboard_text(),
+            WindowHelperInner::Headless(inner) => inner.request_clipboard_text(),
+        }
+    }
+
+    /// Places an image on the clipboard. The `data` must be in the format
+    /// specified by `data_type`, with a width and height given by `size`.
     ///
-    /// For `WebCanvas`, this function has no effect.
-    pub fn set_icon_from_rgba_pixels<S>(
+    /// For `WebCanvas`, this function returns an error, as images can't be
+    /// written to the clipboard.
+    #[cfg(feature = "image-loading")]
+    pub fn set_clipboard_image<S>(
         &self,
-        data: Vec<u8>,
+        data_type: ImageDataType,
         size: S,
+        data: &[u8],
     ) -> Result<(), BacktraceError<ErrorMessage>>
     where
         S: Into<UVec2>,
     {
-        self.inner.set_icon_from_rgba_pixels(data, size.into())
-    }
-
-    /// Sets the visibility of the mouse cursor.
-    pub fn set_cursor_visible(&self, visible: bool) {
-        self.inner.set_cursor_visible(visible)
-    }
+        let size = size.into();
+
+        let data = match data_type {
+            ImageDataType::RGB => data
+                .chunks_exact(3)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+                .collect(),
+            ImageDataType::RGBA => data.to_vec(),
+        };
+
+        if data.len() != size.x as usize * size.y as usize * 4 {
+            return Err(ErrorMessage::msg(format!(
+                "Image data has the wrong length for size {}x{}",
+                size.x, size.y
+            )));
+        }
 
-    /// Grabs the cursor, preventing it from leaving the window.
-    pub fn set_cursor_grab(&self, grabbed: bool) -> Result<(), BacktraceError<ErrorMessage>> {
-        self.inner.set_cursor_grab(grabbed)
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_clipboard_image(data, size),
+            WindowHelperInner::Headless(inner) => inner.set_clipboard_image(data, size),
+        }
     }
 
-    /// Set to false to prevent the user from resizing the window.
+    /// Requests the image on the clipboard, which is delivered to
+    /// [WindowHandler::on_clipboard_image] once it has been read.
     ///
     /// For `WebCanvas`, this function has no effect.
-    pub fn set_resizable(&self, resizable: bool) {
-        self.inner.set_resizable(resizable)
+    #[cfg(feature = "image-loading")]
+    pub fn request_clipboard_image(&self) {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.request_clipboard_image(),
+            WindowHelperInner::Headless(inner) => inner.request_clipboard_image(),
+        }
     }
 
     /// Request that the window is redrawn.
@@ -511,12 +1715,133 @@ impl<UserEventType> WindowHelper<UserEventType> {
     /// the next frame.
     #[inline]
     pub fn request_redraw(&self) {
-        self.inner.request_redraw()
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.request_redraw(),
+            WindowHelperInner::Headless(inner) => inner.request_redraw(),
+        }
+    }
+
+    /// Sets a fixed virtual resolution for drawing, or `None` to draw at the
+    /// window's own resolution. The change takes effect from the next frame,
+    /// and a redraw is requested.
+    ///
+    /// See [WindowCreationOptions::with_virtual_resolution].
+    pub fn set_virtual_resolution(&self, virtual_resolution: Option<UVec2>) {
+        self.pending_virtual_resolution.set(Some(virtual_resolution));
+        self.request_redraw();
+    }
+
+    /// Enables or disables the debug overlay, which shows rendering statistics
+    /// on top of each frame. The change takes effect from the next frame, and
+    /// a redraw is requested.
+    ///
+    /// See [GLRenderer::set_debug_overlay_enabled].
+    pub fn set_debug_overlay_enabled(&self, enabled: bool) {
+        self.pending_debug_overlay_enabled.set(Some(enabled));
+        self.request_redraw();
+    }
+
+    /// Enables or disables the emulation of mouse events using the first
+    /// finger on a touchscreen. The change takes effect from the next touch
+    /// event.
+    ///
+    /// See [WindowCreationOptions::with_touch_mouse_emulation].
+    pub fn set_touch_mouse_emulation(&self, enabled: bool) {
+        self.pending_touch_mouse_emulation.set(Some(enabled));
+    }
+
+    /// If enabled, wheel events with Ctrl held are treated as pinch
+    /// gestures. This is disabled by default.
+    ///
+    /// See [WindowHandler::on_pinch]. This only has an effect for
+    /// `WebCanvas`.
+    pub fn set_ctrl_wheel_pinch(&self, enabled: bool) {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_ctrl_wheel_pinch(enabled),
+            WindowHelperInner::Headless(inner) => inner.set_ctrl_wheel_pinch(enabled),
+        }
+    }
+
+    /// Sets when the window is redrawn, and requests a redraw.
+    ///
+    /// See [RedrawMode], [WindowCreationOptions::with_redraw_mode].
+    pub fn set_redraw_mode(&self, redraw_mode: RedrawMode) {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_redraw_mode(redraw_mode),
+            WindowHelperInner::Headless(inner) => inner.set_redraw_mode(redraw_mode),
+        };
+        self.request_redraw();
+    }
+
+    /// Returns the current [RedrawMode]. See [WindowHelper::set_redraw_mode].
+    #[inline]
+    #[must_use]
+    pub fn redraw_mode(&self) -> RedrawMode {
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.redraw_mode(),
+            WindowHelperInner::Headless(inner) => inner.redraw_mode(),
+        }
+    }
+
+    /// Sets the interval at which [WindowHandler::on_update] is invoked, or
+    /// `None` to stop invoking it. The change takes effect from the next
+    /// frame, and a redraw is requested.
+    ///
+    /// While an update timestep is set, frames are drawn continuously, subject
+    /// to any [RedrawMode::FixedFps] limit.
+    ///
+    /// See [WindowCreationOptions::with_update_timestep].
+    pub fn set_update_timestep(&self, update_timestep: Option<Duration>) {
+        self.pending_update_timestep.set(Some(update_timestep));
+        self.request_redraw();
+    }
+
+    /// Returns the current keyboard and mouse state of the window, which can
+    /// be polled as an alternative to handling the input callbacks.
+    #[inline]
+    #[must_use]
+    pub fn input(&self) -> &InputState {
+        &self.input
+    }
+
+    /// Returns the named actions and axes which are bound for this window.
+    #[inline]
+    #[must_use]
+    pub fn action_map(&self) -> &ActionMap {
+        &self.action_map
+    }
+
+    /// Returns the named actions and axes which are bound for this window,
+    /// allowing the bindings to be changed at runtime. Whenever a binding is
+    /// pressed or released, [WindowHandler::on_action] will be invoked.
+    #[inline]
+    #[must_use]
+    pub fn action_map_mut(&mut self) -> &mut ActionMap {
+        &mut self.action_map
+    }
+
+    /// Returns the current value of the specified axis in the
+    /// [WindowHelper::action_map], from `-1.0` to `1.0`.
+    #[inline]
+    #[must_use]
+    pub fn axis_value(&self, axis: &str) -> f32 {
+        self.action_map.axis_value(axis, &self.input)
+    }
+
+    /// Returns timing information for the frame currently being drawn, or the
+    /// most recently drawn frame if called outside [WindowHandler::on_draw].
+    #[inline]
+    #[must_use]
+    pub fn frame_timing(&self) -> FrameTiming {
+        self.frame_timing.get()
     }
 
     /// Sets the window title.
     pub fn set_title<S: AsRef<str>>(&self, title: S) {
-        self.inner.set_title(title.as_ref())
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_title(title.as_ref()),
+            WindowHelperInner::Headless(inner) => inner.set_title(title.as_ref()),
+        }
     }
 
     /// Sets the window fullscreen mode.
@@ -526,7 +1851,10 @@ impl<UserEventType> WindowHelper<UserEventType> {
     /// If the operation is successful, the
     /// [WindowHandler::on_fullscreen_status_changed] callback will be invoked.
     pub fn set_fullscreen_mode(&self, mode: WindowFullscreenMode) {
-        self.inner.set_fullscreen_mode(mode)
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_fullscreen_mode(mode),
+            WindowHelperInner::Headless(inner) => inner.set_fullscreen_mode(mode),
+        }
     }
 
     /// Sets the window size in pixels. This is the window's inner size,
@@ -534,12 +1862,18 @@ impl<UserEventType> WindowHelper<UserEventType> {
     ///
     /// For `WebCanvas`, this function has no effect.
     pub fn set_size_pixels<S: Into<UVec2>>(&self, size: S) {
-        self.inner.set_size_pixels(size)
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_size_pixels(size),
+            WindowHelperInner::Headless(inner) => inner.set_size_pixels(size),
+        }
     }
 
     /// Gets the window size in pixels.
     pub fn get_size_pixels(&self) -> UVec2 {
-        self.inner.get_size_pixels()
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.get_size_pixels(),
+            WindowHelperInner::Headless(inner) => inner.get_size_pixels(),
+        }
     }
 
     /// Sets the position of the window in pixels. If multiple monitors are in
@@ -548,7 +1882,10 @@ impl<UserEventType> WindowHelper<UserEventType> {
     ///
     /// For `WebCanvas`, this function has no effect.
     pub fn set_position_pixels<P: Into<IVec2>>(&self, position: P) {
-        self.inner.set_position_pixels(position)
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_position_pixels(position),
+            WindowHelperInner::Headless(inner) => inner.set_position_pixels(position),
+        }
     }
 
     /// Sets the window size in scaled device-independent pixels. This is the
@@ -556,7 +1893,10 @@ impl<UserEventType> WindowHelper<UserEventType> {
     ///
     /// For `WebCanvas`, this function has no effect.
     pub fn set_size_scaled_pixels<S: Into<Vec2>>(&self, size: S) {
-        self.inner.set_size_scaled_pixels(size)
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_size_scaled_pixels(size),
+            WindowHelperInner::Headless(inner) => inner.set_size_scaled_pixels(size),
+        }
     }
 
     /// Sets the position of the window in scaled device-independent pixels. If
@@ -565,14 +1905,20 @@ impl<UserEventType> WindowHelper<UserEventType> {
     ///
     /// For `WebCanvas`, this function has no effect.
     pub fn set_position_scaled_pixels<P: Into<Vec2>>(&self, position: P) {
-        self.inner.set_position_scaled_pixels(position)
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.set_position_scaled_pixels(position),
+            WindowHelperInner::Headless(inner) => inner.set_position_scaled_pixels(position),
+        }
     }
 
     /// Gets the window's scale factor.
     #[inline]
     #[must_use]
     pub fn get_scale_factor(&self) -> f64 {
-        self.inner.get_scale_factor()
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.get_scale_factor(),
+            WindowHelperInner::Headless(inner) => inner.get_scale_factor(),
+        }
     }
 
     /// Creates a [UserEventSender], which can be used to post custom events to
@@ -580,7 +1926,56 @@ impl<UserEventType> WindowHelper<UserEventType> {
     ///
     /// See [UserEventSender::send_event], [WindowHandler::on_user_event].
     pub fn create_user_event_sender(&self) -> UserEventSender<UserEventType> {
-        self.inner.create_user_event_sender()
+        match &self.inner {
+            WindowHelperInner::Platform(inner) => inner.create_user_event_sender(),
+            WindowHelperInner::Headless(inner) => inner.create_user_event_sender(),
+        }
+    }
+}
+
+impl<UserEventType> WindowHelper<UserEventType> {
+    /// Stops recording events, and returns the events recorded since
+    /// [WindowHelper::start_event_recording] was called. Returns `None` if no
+    /// recording was in progress.
+    pub fn stop_event_recording(&mut self) -> Option<EventLog> {
+        self.event_recorder.take().map(EventRecorder::finish)
+    }
+
+    /// Returns true if events are currently being recorded.
+    #[inline]
+    #[must_use]
+    pub fn is_event_recording(&self) -> bool {
+        self.event_recorder.is_some()
+    }
+
+    #[inline]
+    fn record_event(&mut self, event: RecordedEvent) {
+        if let Some(recorder) = &mut self.event_recorder {
+            recorder.record(event);
+        }
+    }
+
+    #[inline]
+    fn record_user_event(&mut self, user_event: &UserEventType) {
+        if let Some(recorder) = &mut self.event_recorder {
+            recorder.record_user_event(user_event);
+        }
+    }
+}
+
+impl<UserEventType: SerializableUserEvent> WindowHelper<UserEventType> {
+    /// Starts recording every event delivered to this window, along with the
+    /// time at which it occurred, discarding any recording already in
+    /// progress. The recording can be retrieved using
+    /// [WindowHelper::stop_event_recording], saved as text, and later
+    /// replayed using [crate::recording::EventReplayer], for example to
+    /// reproduce a bug report, or as an end-to-end test.
+    pub fn start_event_recording(&mut self) {
+        self.event_recorder = Some(EventRecorder::new(
+            self.get_size_pixels(),
+            self.get_scale_factor(),
+            UserEventType::to_event_string,
+        ));
     }
 }
 
@@ -599,6 +1994,31 @@ pub(crate) enum WindowEventLoopAction {
     Exit,
 }
 
+/// Timing information for a frame. See [WindowHelper::frame_timing].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct FrameTiming {
+    /// The time elapsed since the previous frame started, or zero for the
+    /// first frame.
+    pub delta: Duration,
+
+    /// The number of frames which were drawn before this one.
+    pub frame_index: u64,
+
+    /// The time at which this frame started, relative to the start of the
+    /// first frame.
+    pub timestamp: Duration,
+
+    /// How far the current time is between the most recent
+    /// [WindowHandler::on_update] call and the next one, from `0.0` to `1.0`.
+    /// This can be used to interpolate between the previous and current
+    /// simulation state, to keep motion smooth when the frame rate doesn't
+    /// match the update rate.
+    ///
+    /// If no update timestep is set, this is always `1.0`.
+    pub interpolation_alpha: f64,
+}
+
 /// Information about the starting state of the window.
 #[derive(Debug, PartialEq, Clone)]
 pub struct WindowStartupInfo {
@@ -644,6 +2064,46 @@ pub enum MouseButton {
     Other(u16),
 }
 
+/// An event from an input method editor (IME). See [WindowHandler::on_ime].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// The IME was enabled. This is followed by [ImeEvent::Preedit] and
+    /// [ImeEvent::Commit] events.
+    Enabled,
+    /// The text currently being composed, which should be displayed at the
+    /// text cursor (typically underlined), but not yet inserted. An empty
+    /// string means that the composition was cleared.
+    Preedit {
+        /// The text being composed.
+        text: String,
+        /// The start and end of the cursor or selection within `text`, as
+        /// byte indices, or `None` if the cursor should be hidden.
+        cursor: Option<(usize, usize)>,
+    },
+    /// The composition was completed, and the text should be inserted. Any
+    /// preedit text should be removed.
+    Commit(String),
+    /// The IME was disabled.
+    Disabled,
+}
+
+/// Identifies a finger on a touchscreen. See [WindowHandler::on_touch].
+pub type TouchId = u64;
+
+/// The stage of a touch, as reported to [WindowHandler::on_touch].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum TouchPhase {
+    /// The finger has started touching the screen.
+    Started,
+    /// The finger has moved while touching the screen.
+    Moved,
+    /// The finger was lifted from the screen.
+    Ended,
+    /// The touch was interrupted by the system, for example because the
+    /// window lost focus.
+    Cancelled,
+}
+
 /// Describes a difference in the mouse scroll wheel position.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum MouseScrollDistance {
@@ -959,6 +2419,111 @@ pub enum WindowFullscreenMode {
     FullscreenBorderless,
 }
 
+/// Controls when the window is redrawn. See
+/// [WindowCreationOptions::with_redraw_mode] and
+/// [WindowHelper::set_redraw_mode].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
+pub enum RedrawMode {
+    /// The window is only redrawn when [WindowHelper::request_redraw] is
+    /// called, or when the platform requires it (for example, after a
+    /// resize). This is the default.
+    #[default]
+    OnDemand,
+
+    /// The window is redrawn continuously, as fast as possible. When vsync is
+    /// enabled, this will be at the refresh rate of the display.
+    Continuous,
+
+    /// The window is redrawn continuously at the specified number of frames
+    /// per second. Between frames, the event loop sleeps rather than spinning.
+    /// Calls to [WindowHelper::request_redraw] do not cause additional
+    /// frames to be drawn.
+    FixedFps(u32),
+}
+
+/// Decides when frames are drawn, according to the [RedrawMode].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FramePacer {
+    mode: RedrawMode,
+    next_frame_deadline: Option<Timestamp>,
+    paused: bool,
+}
+
+impl FramePacer {
+    pub(crate) fn new(mode: RedrawMode) -> Self {
+        FramePacer {
+            mode,
+            next_frame_deadline: None,
+            paused: false,
+        }
+    }
+
+    /// Stops frames from being drawn while paused, for example while the
+    /// window is hidden.
+    pub(crate) fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.next_frame_deadline = None;
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn mode(&self) -> RedrawMode {
+        self.mode
+    }
+
+    pub(crate) fn set_mode(&mut self, mode: RedrawMode) {
+        self.mode = mode;
+        self.next_frame_deadline = None;
+    }
+
+    /// Returns true if a frame should be drawn at the time `now`.
+    #[must_use]
+    pub(crate) fn is_frame_due(&self, redraw_requested: bool, now: &Timestamp) -> bool {
+        if self.paused {
+            return false;
+        }
+
+        match self.mode {
+            RedrawMode::OnDemand => redraw_requested,
+            RedrawMode::Continuous => true,
+            RedrawMode::FixedFps(_) => match &self.next_frame_deadline {
+                None => true,
+                Some(deadline) => now >= deadline,
+            },
+        }
+    }
+
+    /// Returns the time at which the next frame is due, if the event loop
+    /// should sleep until then.
+    #[must_use]
+    pub(crate) fn next_frame_deadline(&self) -> Option<Timestamp> {
+        if self.paused {
+            return None;
+        }
+
+        match self.mode {
+            RedrawMode::OnDemand | RedrawMode::Continuous => None,
+            RedrawMode::FixedFps(_) => self.next_frame_deadline,
+        }
+    }
+
+    pub(crate) fn on_frame_start(&mut self, now: &Timestamp) {
+        if let RedrawMode::FixedFps(fps) = self.mode {
+            let interval = Duration::from_secs(1) / fps.max(1);
+
+            let next_frame_deadline = self
+                .next_frame_deadline
+                .map(|deadline| deadline.plus(interval))
+                .filter(|deadline| deadline >= now);
+
+            // If we've fallen behind, start again from now rather than
+            // drawing a burst of frames to catch up
+            self.next_frame_deadline =
+                Some(next_frame_deadline.unwrap_or_else(|| now.plus(interval)));
+        }
+    }
+}
+
 /// Options used during the creation of a window.
 #[derive(Debug, Clone, PartialEq)]
 pub struct WindowCreationOptions {
@@ -970,6 +2535,13 @@ pub struct WindowCreationOptions {
     pub(crate) maximized: bool,
     pub(crate) transparent: bool,
     pub(crate) decorations: bool,
+    pub(crate) logical_coordinates: bool,
+    pub(crate) virtual_resolution: Option<UVec2>,
+    pub(crate) debug_overlay_hotkey: Option<VirtualKeyCode>,
+    pub(crate) update_timestep: Option<Duration>,
+    pub(crate) redraw_mode: RedrawMode,
+    pub(crate) touch_mouse_emulation: bool,
+    pub(crate) pause_when_hidden: bool,
 }
 
 impl WindowCreationOptions {
@@ -999,6 +2571,13 @@ impl WindowCreationOptions {
             maximized: false,
             decorations: true,
             transparent: false,
+            logical_coordinates: false,
+            virtual_resolution: None,
+            debug_overlay_hotkey: None,
+            update_timestep: None,
+            redraw_mode: RedrawMode::OnDemand,
+            touch_mouse_emulation: false,
+            pause_when_hidden: true,
         }
     }
 
@@ -1073,7 +2652,308 @@ impl WindowCreationOptions {
         self.transparent = transparent;
         self
     }
+
+    /// If set to `true`, [Graphics2D] will use logical (DPI-scaled) pixels
+    /// rather than physical pixels, and mouse positions will be reported to
+    /// [WindowHandler::on_mouse_move] in logical pixels. Text is still
+    /// rasterized at the physical resolution, so it remains sharp. The
+    /// default is `false`.
+    ///
+    /// Window sizes (for example in [WindowHandler::on_resize]) continue to
+    /// be reported in physical pixels. Divide by
+    /// [WindowHelper::get_scale_factor] to convert these.
+    #[inline]
+    #[must_use]
+    pub fn with_logical_coordinates(mut self, logical_coordinates: bool) -> Self {
+        self.logical_coordinates = logical_coordinates;
+        self
+    }
+
+    /// Draws each frame at a fixed virtual resolution (for example `320x180`),
+    /// which is then upscaled to the window by an integer factor using
+    /// nearest-neighbour filtering, with black bars around the edges. Mouse
+    /// positions are reported in virtual coordinates. By default, no virtual
+    /// resolution is used.
+    ///
+    /// This can be changed later using [WindowHelper::set_virtual_resolution].
+    /// See also [GLRenderer::set_virtual_resolution].
+    #[inline]
+    #[must_use]
+    pub fn with_virtual_resolution(mut self, virtual_resolution: UVec2) -> Self {
+        self.virtual_resolution = Some(virtual_resolution);
+        self
+    }
+
+    /// Sets a key which toggles the debug overlay, for example
+    /// [VirtualKeyCode::F3]. The key press is still passed on to
+    /// [WindowHandler::on_key_down]. By default, no key is set.
+    ///
+    /// The overlay can also be controlled using
+    /// [WindowHelper::set_debug_overlay_enabled]. See
+    /// [GLRenderer::set_debug_overlay_enabled] for details.
+    #[inline]
+    #[must_use]
+    pub fn with_debug_overlay_hotkey(mut self, hotkey: VirtualKeyCode) -> Self {
+        self.debug_overlay_hotkey = Some(hotkey);
+        self
+    }
+
+    /// Sets the interval at which [WindowHandler::on_update] is invoked, for
+    /// example `Duration::from_secs(1) / 60` for 60 updates per second. By
+    /// default, no update timestep is set, and `on_update` is never invoked.
+    ///
+    /// While an update timestep is set, frames are drawn continuously, and
+    /// the updates which are due run before each frame. A timestep of zero
+    /// is ignored.
+    ///
+    /// See [WindowHelper::set_update_timestep].
+    #[inline]
+    #[must_use]
+    pub fn with_update_timestep(mut self, update_timestep: Duration) -> Self {
+        self.update_timestep = Some(update_timestep);
+        self
+    }
+
+    /// Sets when the window is redrawn. By default, this is
+    /// [RedrawMode::OnDemand].
+    ///
+    /// See [WindowHelper::set_redraw_mode].
+    #[inline]
+    #[must_use]
+    pub fn with_redraw_mode(mut self, redraw_mode: RedrawMode) -> Self {
+        self.redraw_mode = redraw_mode;
+        self
+    }
+
+    /// If enabled, the first finger to touch the window is also reported as
+    /// the left mouse button, using [WindowHandler::on_mouse_move],
+    /// [WindowHandler::on_mouse_button_down], and
+    /// [WindowHandler::on_mouse_button_up]. This allows applications which
+    /// only handle the mouse to be used on touchscreens. Touch events are
+    /// still delivered to [WindowHandler::on_touch] either way.
+    ///
+    /// This is disabled by default. For a [crate::WebCanvas], use
+    /// [WindowHelper::set_touch_mouse_emulation] instead. While it's
+    /// disabled, a `WebCanvas` leaves the browser's own emulated mouse events
+    /// in place, so touches still reach [WindowHandler::on_mouse_move] and
+    /// the related callbacks, and the page may scroll in response to touch
+    /// gestures.
+    #[inline]
+    #[must_use]
+    pub fn with_touch_mouse_emulation(mut self, enabled: bool) -> Self {
+        self.touch_mouse_emulation = enabled;
+        self
+    }
+
+    /// If enabled, no frames are drawn while the window is hidden (for
+    /// example, while it's minimized), regardless of the [RedrawMode]. A
+    /// redraw is requested when the window is shown again. This is enabled
+    /// by default.
+    ///
+    /// See [WindowHandler::on_visibility_changed].
+    #[inline]
+    #[must_use]
+    pub fn with_pause_when_hidden(mut self, pause_when_hidden: bool) -> Self {
+        self.pause_when_hidden = pause_when_hidden;
+        self
+    }
 }
 
 /// Type representing a keyboard scancode.
 pub type KeyScancode = u32;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Exactly representable as an `f64` number of seconds, so no rounding
+    /// occurs when measuring frame durations.
+    const TIMESTEP: Duration = Duration::from_micros(15_625);
+
+    fn clock_with_timestep(update_timestep: Option<Duration>) -> (FrameClock, Timestamp) {
+        let mut clock = FrameClock::default();
+        clock.set_update_timestep(update_timestep);
+
+        let start = Timestamp::now();
+        assert_eq!(clock.begin_frame(start).1, 0);
+
+        (clock, start)
+    }
+
+    #[test]
+    fn test_frame_clock_timing() {
+        let (mut clock, start) = clock_with_timestep(None);
+
+        let (timing, updates) = clock.begin_frame(start.plus(TIMESTEP));
+        assert_eq!(updates, 0);
+        assert_eq!(timing.frame_index, 1);
+        assert_eq!(timing.delta, TIMESTEP);
+        assert_eq!(timing.timestamp, TIMESTEP);
+        assert_eq!(timing.interpolation_alpha, 1.0);
+
+        let (timing, _) = clock.begin_frame(start.plus(TIMESTEP * 3));
+        assert_eq!(timing.frame_index, 2);
+        assert_eq!(timing.delta, TIMESTEP * 2);
+        assert_eq!(timing.timestamp, TIMESTEP * 3);
+    }
+
+    #[test]
+    fn test_frame_clock_zero_timestep_disables_updates() {
+        let (mut clock, start) = clock_with_timestep(Some(Duration::ZERO));
+
+        assert_eq!(clock.update_timestep, None);
+
+        let (timing, updates) = clock.begin_frame(start.plus(TIMESTEP));
+        assert_eq!(updates, 0);
+        assert_eq!(timing.interpolation_alpha, 1.0);
+    }
+
+    #[test]
+    fn test_frame_clock_short_frames() {
+        let (mut clock, start) = clock_with_timestep(Some(TIMESTEP));
+
+        let (timing, updates) = clock.begin_frame(start.plus(TIMESTEP / 2));
+        assert_eq!(updates, 0);
+        assert_eq!(timing.interpolation_alpha, 0.5);
+
+        let (timing, updates) = clock.begin_frame(start.plus(TIMESTEP * 3 / 4));
+        assert_eq!(updates, 0);
+        assert_eq!(timing.interpolation_alpha, 0.75);
+
+        let (timing, updates) = clock.begin_frame(start.plus(TIMESTEP * 5 / 4));
+        assert_eq!(updates, 1);
+        assert_eq!(timing.interpolation_alpha, 0.25);
+    }
+
+    #[test]
+    fn test_frame_clock_exact_frames() {
+        let (mut clock, start) = clock_with_timestep(Some(TIMESTEP));
+
+        for frame in 1..=3 {
+            let (timing, updates) = clock.begin_frame(start.plus(TIMESTEP * frame));
+            assert_eq!(updates, 1);
+            assert_eq!(timing.interpolation_alpha, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_frame_clock_long_frames() {
+        let (mut clock, start) = clock_with_timestep(Some(TIMESTEP));
+
+        let (timing, updates) = clock.begin_frame(start.plus(TIMESTEP * 5 / 2));
+        assert_eq!(updates, 2);
+        assert_eq!(timing.interpolation_alpha, 0.5);
+
+        let (timing, updates) = clock.begin_frame(start.plus(TIMESTEP * 4));
+        assert_eq!(updates, 2);
+        assert_eq!(timing.interpolation_alpha, 0.0);
+    }
+
+    #[test]
+    fn test_frame_clock_stall_is_clamped() {
+        let (mut clock, start) = clock_with_timestep(Some(TIMESTEP));
+
+        let stall = Duration::from_secs(1);
+        let (timing, updates) = clock.begin_frame(start.plus(stall));
+
+        // Only MAX_UPDATE_CATCH_UP is caught up on, but the delta is
+        // reported in full
+        assert_eq!(
+            updates,
+            (MAX_UPDATE_CATCH_UP.as_micros() / TIMESTEP.as_micros()) as u32
+        );
+        assert_eq!(updates, 16);
+        assert_eq!(timing.interpolation_alpha, 0.0);
+        assert_eq!(timing.delta, stall);
+
+        let (_, updates) = clock.begin_frame(start.plus(stall + TIMESTEP));
+        assert_eq!(updates, 1);
+    }
+
+    #[test]
+    fn test_frame_pacer_on_demand() {
+        let mut pacer = FramePacer::new(RedrawMode::OnDemand);
+        let now = Timestamp::now();
+
+        assert!(!pacer.is_frame_due(false, &now));
+        assert!(pacer.is_frame_due(true, &now));
+
+        pacer.on_frame_start(&now);
+        assert_eq!(pacer.next_frame_deadline(), None);
+        assert!(!pacer.is_frame_due(false, &now));
+    }
+
+    #[test]
+    fn test_frame_pacer_continuous() {
+        let mut pacer = FramePacer::new(RedrawMode::Continuous);
+        let now = Timestamp::now();
+
+        assert!(pacer.is_frame_due(false, &now));
+
+        pacer.on_frame_start(&now);
+        assert_eq!(pacer.next_frame_deadline(), None);
+        assert!(pacer.is_frame_due(false, &now));
+
+        pacer.set_paused(true);
+        assert!(!pacer.is_frame_due(true, &now));
+    }
+
+    #[test]
+    fn test_frame_pacer_fixed_fps() {
+        let mut pacer = FramePacer::new(RedrawMode::FixedFps(50));
+        let interval = Duration::from_millis(20);
+        let start = Timestamp::now();
+
+        assert!(pacer.is_frame_due(false, &start));
+        assert_eq!(pacer.next_frame_deadline(), None);
+
+        pacer.on_frame_start(&start);
+        assert_eq!(pacer.next_frame_deadline(), Some(start.plus(interval)));
+
+        // Redraw requests don't cause extra frames
+        assert!(!pacer.is_frame_due(true, &start.plus(interval / 2)));
+        assert!(pacer.is_frame_due(false, &start.plus(interval)));
+
+        // Deadlines advance by the interval, even if the frame starts late
+        pacer.on_frame_start(&start.plus(interval * 3 / 2));
+        assert_eq!(pacer.next_frame_deadline(), Some(start.plus(interval * 2)));
+
+        // After falling further behind, the schedule restarts from now
+        let late = start.plus(interval * 10);
+        pacer.on_frame_start(&late);
+        assert_eq!(pacer.next_frame_deadline(), Some(late.plus(interval)));
+
+        pacer.set_paused(true);
+        assert_eq!(pacer.next_frame_deadline(), None);
+        assert!(!pacer.is_frame_due(true, &late.plus(interval)));
+    }
+
+    #[test]
+    fn test_frame_pacer_zero_fps() {
+        let mut pacer = FramePacer::new(RedrawMode::FixedFps(0));
+        let start = Timestamp::now();
+
+        // Treated as one frame per second
+        pacer.on_frame_start(&start);
+        assert_eq!(
+            pacer.next_frame_deadline(),
+            Some(start.plus(Duration::from_secs(1)))
+        );
+        assert!(!pacer.is_frame_due(false, &start.plus(Duration::from_millis(999))));
+        assert!(pacer.is_frame_due(false, &start.plus(Duration::from_secs(1))));
+    }
+
+    #[test]
+    fn test_frame_pacer_absurd_fps() {
+        let mut pacer = FramePacer::new(RedrawMode::FixedFps(u32::MAX));
+        let start = Timestamp::now();
+
+        // The interval rounds down to zero, so every frame is due
+        pacer.on_frame_start(&start);
+        assert_eq!(pacer.next_frame_deadline(), Some(start));
+        assert!(pacer.is_frame_due(false, &start));
+
+        pacer.on_frame_start(&start);
+        assert!(pacer.is_frame_due(false, &start));
+    }
+}
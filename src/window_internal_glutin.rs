@@ -14,21 +14,26 @@
  *  limitations under the License.
  */
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::convert::{TryFrom, TryInto};
 use std::ffi::CString;
 use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::time::Duration;
 
-use glutin::config::{Config, ConfigTemplateBuilder};
+use glutin::config::{Config, ConfigTemplateBuilder, GetGlConfig};
 use glutin::context::{
     ContextApi,
     ContextAttributesBuilder,
+    GlProfile,
+    NotCurrentContext,
     NotCurrentGlContext,
     PossiblyCurrentContext,
+    PossiblyCurrentGlContext,
     Version
 };
 use glutin::display::{GetGlDisplay, GlDisplay};
+use glutin::error::ErrorKind;
 use glutin::surface::{
     GlSurface,
     Surface,
@@ -36,6 +41,7 @@ use glutin::surface::{
     SwapInterval,
     WindowSurface
 };
+use arboard::Clipboard;
 use glutin_winit::{DisplayBuilder, GlWindow};
 use raw_window_handle::HasRawWindowHandle;
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
@@ -43,9 +49,11 @@ use winit::error::EventLoopError;
 use winit::event::{
     ElementState as GlutinElementState,
     Event as GlutinEvent,
+    Ime as GlutinIme,
     KeyEvent,
     MouseScrollDelta as GlutinMouseScrollDelta,
-    TouchPhase,
+    Touch as GlutinTouch,
+    TouchPhase as GlutinTouchPhase,
     WindowEvent as GlutinWindowEvent
 };
 use winit::event_loop::{
@@ -53,7 +61,8 @@ use winit::event_loop::{
     EventLoop,
     EventLoopBuilder,
     EventLoopClosed,
-    EventLoopProxy
+    EventLoopProxy,
+    EventLoopWindowTarget
 };
 use winit::keyboard::{Key, KeyLocation, NamedKey};
 use winit::monitor::MonitorHandle;
@@ -64,51 +73,123 @@ use winit::window::{
     Window as GlutinWindow,
     Window,
     WindowBuilder,
+    WindowId,
     WindowLevel
 };
 
 use crate::error::{BacktraceError, ErrorMessage};
 use crate::glbackend::constants::GL_VERSION;
 use crate::glbackend::{GLBackend, GLBackendGlow};
+use crate::glwrapper::GLVersion;
+use crate::time::Timestamp;
 use crate::window::{
-    DrawingWindowHandler, EventLoopSendError, ModifiersState, MouseButton, MouseScrollDistance,
-    UserEventSender, VirtualKeyCode, WindowCreationError, WindowCreationMode,
-    WindowCreationOptions, WindowEventLoopAction, WindowFullscreenMode, WindowHandler,
-    WindowHelper, WindowPosition, WindowSize, WindowStartupInfo,
+    DrawingWindowHandler, EventLoopSendError, FramePacer, ImeEvent, ModifiersState, MouseButton,
+    MouseScrollDistance, RedrawMode, TouchPhase, UserEventSender, VirtualKeyCode,
+    WindowCreationError,
+    WindowCreationMode, WindowCreationOptions, WindowEventLoopAction, WindowFullscreenMode,
+    WindowHandler, WindowHelper, WindowPosition, WindowSize, WindowStartupInfo,
 };
-use crate::GLRenderer;
+use crate::{GLRenderer, Rect};
 use glam::{DVec2, IVec2, UVec2, Vec2};
 
+#[cfg(feature = "image-loading")]
+use {
+    crate::image::{ImageDataType, RawBitmapData},
+    arboard::ImageData,
+};
+
+/// A request to open an additional window, which is fulfilled by the event
+/// loop once the current callback has returned.
+struct WindowCreationRequest<UserEventType: 'static> {
+    title: String,
+    options: WindowCreationOptions,
+    handler: Box<dyn WindowHandler<UserEventType>>
+}
+
+type WindowCreationRequestQueue<UserEventType> =
+    Rc<RefCell<Vec<WindowCreationRequest<UserEventType>>>>;
+
 pub(crate) struct WindowHelperGlutin<UserEventType: 'static> {
     window: Rc<Window>,
     event_proxy: EventLoopProxy<UserEventGlutin<UserEventType>>,
+    window_requests: WindowCreationRequestQueue<UserEventType>,
     redraw_requested: Cell<bool>,
+    frame_pacer: Cell<FramePacer>,
     terminate_requested: bool,
+    close_requested: bool,
     physical_size: UVec2,
     is_mouse_grabbed: Cell<bool>,
+    clipboard: RefCell<Option<Clipboard>>,
+    pause_when_hidden: bool,
+    occluded: bool,
+    minimized: bool,
 }
 
 impl<UserEventType> WindowHelperGlutin<UserEventType> {
     #[inline]
-    pub fn new(
+    fn new(
         window: &Rc<Window>,
         event_proxy: EventLoopProxy<UserEventGlutin<UserEventType>>,
+        window_requests: &WindowCreationRequestQueue<UserEventType>,
+        redraw_mode: RedrawMode,
+        pause_when_hidden: bool,
         initial_physical_size: UVec2,
     ) -> Self {
         WindowHelperGlutin {
             window: Rc::clone(window),
             event_proxy,
+            window_requests: Rc::clone(window_requests),
             redraw_requested: Cell::new(false),
+            frame_pacer: Cell::new(FramePacer::new(redraw_mode)),
             terminate_requested: false,
+            close_requested: false,
             physical_size: initial_physical_size,
             is_mouse_grabbed: Cell::new(false),
+            clipboard: RefCell::new(None),
+            pause_when_hidden,
+            occluded: false,
+            minimized: false,
         }
     }
 
     #[inline]
     #[must_use]
-    pub fn is_redraw_requested(&self) -> bool {
-        self.redraw_requested.get()
+    fn is_visible(&self) -> bool {
+        !self.occluded && !self.minimized
+    }
+
+    /// Returns the new visibility of the window, if it changed.
+    fn set_occluded(&mut self, occluded: bool) -> Option<bool> {
+        let was_visible = self.is_visible();
+        self.occluded = occluded;
+        self.on_visibility_updated(was_visible)
+    }
+
+    /// Returns the new visibility of the window, if it changed.
+    fn set_minimized(&mut self, minimized: bool) -> Option<bool> {
+        let was_visible = self.is_visible();
+        self.minimized = minimized;
+        self.on_visibility_updated(was_visible)
+    }
+
+    fn on_visibility_updated(&mut self, was_visible: bool) -> Option<bool> {
+        let visible = self.is_visible();
+
+        if visible == was_visible {
+            return None;
+        }
+
+        if self.pause_when_hidden {
+            let mut frame_pacer = self.frame_pacer.get();
+            frame_pacer.set_paused(!visible);
+            self.frame_pacer.set(frame_pacer);
+        }
+
+        if visible {
+            self.redraw_requested.set(true);
+        }
+
+        Some(visible)
     }
 
     #[inline]
@@ -116,6 +197,37 @@ impl<UserEventType> WindowHelperGlutin<UserEventType> {
         self.redraw_requested.set(redraw_requested);
     }
 
+    #[must_use]
+    fn is_frame_due(&self, now: &Timestamp) -> bool {
+        self.frame_pacer
+            .get()
+            .is_frame_due(self.redraw_requested.get(), now)
+    }
+
+    #[inline]
+    #[must_use]
+    fn next_frame_deadline(&self) -> Option<Timestamp> {
+        self.frame_pacer.get().next_frame_deadline()
+    }
+
+    fn on_frame_start(&self, now: &Timestamp) {
+        let mut frame_pacer = self.frame_pacer.get();
+        frame_pacer.on_frame_start(now);
+        self.frame_pacer.set(frame_pacer);
+    }
+
+    pub fn set_redraw_mode(&self, redraw_mode: RedrawMode) {
+        let mut frame_pacer = self.frame_pacer.get();
+        frame_pacer.set_mode(redraw_mode);
+        self.frame_pacer.set(frame_pacer);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn redraw_mode(&self) -> RedrawMode {
+        self.frame_pacer.get().mode()
+    }
+
     #[inline]
     pub fn get_event_loop_action(&self) -> WindowEventLoopAction {
         match self.terminate_requested {
@@ -128,6 +240,31 @@ impl<UserEventType> WindowHelperGlutin<UserEventType> {
         self.terminate_requested = true;
     }
 
+    pub fn close_window(&mut self) {
+        self.close_requested = true;
+    }
+
+    #[inline]
+    #[must_use]
+    fn is_close_requested(&self) -> bool {
+        self.close_requested
+    }
+
+    pub fn create_window(
+        &self,
+        title: &str,
+        options: WindowCreationOptions,
+        handler: Box<dyn WindowHandler<UserEventType>>
+    ) {
+        self.window_requests
+            .borrow_mut()
+            .push(WindowCreationRequest {
+                title: title.to_string(),
+                options,
+                handler
+            });
+    }
+
     pub fn set_icon_from_rgba_pixels(
         &self,
         data: Vec<u8>,
@@ -170,7 +307,10 @@ impl<UserEventType> WindowHelperGlutin<UserEventType> {
                 self.is_mouse_grabbed.set(grabbed);
                 if self
                     .event_proxy
-                    .send_event(UserEventGlutin::MouseGrabStatusChanged(grabbed))
+                    .send_event(UserEventGlutin::MouseGrabStatusChanged(
+                        self.window.id(),
+                        grabbed
+                    ))
                     .is_err()
                 {
                     log::error!("Failed to notify app of cursor grab: event loop closed");
@@ -185,6 +325,97 @@ impl<UserEventType> WindowHelperGlutin<UserEventType> {
         self.window.set_resizable(resizable);
     }
 
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.window.set_ime_allowed(allowed);
+    }
+
+    #[inline]
+    pub fn set_ctrl_wheel_pinch(&self, _enabled: bool) {
+        // Do nothing, as pinch gestures are reported directly
+    }
+
+    pub fn set_ime_cursor_area(&self, area_pixels: Rect) {
+        self.window.set_ime_cursor_area(
+            PhysicalPosition::new(area_pixels.top_left.x, area_pixels.top_left.y),
+            PhysicalSize::new(area_pixels.width(), area_pixels.height())
+        );
+    }
+
+    /// Runs the action using the system clipboard, which is opened the first
+    /// time it's needed. The clipboard is kept open afterwards, as on some
+    /// platforms, the contents we place on it are lost when it's closed.
+    fn with_clipboard<R, F>(&self, action: F) -> Result<R, BacktraceError<ErrorMessage>>
+    where
+        F: FnOnce(&mut Clipboard) -> Result<R, arboard::Error>
+    {
+        let mut clipboard = self.clipboard.borrow_mut();
+
+        if clipboard.is_none() {
+            *clipboard = Some(Clipboard::new().map_err(|err| {
+                ErrorMessage::msg_with_cause("Could not open the clipboard", err)
+            })?);
+        }
+
+        action(clipboard.as_mut().unwrap())
+            .map_err(|err| ErrorMessage::msg_with_cause("Clipboard operation failed", err))
+    }
+
+    pub fn set_clipboard_text(&self, text: &str) -> Result<(), BacktraceError<ErrorMessage>> {
+        self.with_clipboard(|clipboard| clipboard.set_text(text))
+    }
+
+    pub fn request_clipboard_text(&self) {
+        match self.with_clipboard(|clipboard| clipboard.get_text()) {
+            Ok(text) => {
+                if self
+                    .event_proxy
+                    .send_event(UserEventGlutin::ClipboardText(self.window.id(), text))
+                    .is_err()
+                {
+                    log::error!("Failed to deliver clipboard text: event loop closed");
+                }
+            }
+            Err(err) => log::warn!("Could not read text from the clipboard: {:?}", err)
+        }
+    }
+
+    #[cfg(feature = "image-loading")]
+    pub fn set_clipboard_image(
+        &self,
+        data: Vec<u8>,
+        size: UVec2
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        self.with_clipboard(|clipboard| {
+            clipboard.set_image(ImageData {
+                width: size.x as usize,
+                height: size.y as usize,
+                bytes: data.into()
+            })
+        })
+    }
+
+    #[cfg(feature = "image-loading")]
+    pub fn request_clipboard_image(&self) {
+        match self.with_clipboard(|clipboard| clipboard.get_image()) {
+            Ok(image) => {
+                let image = RawBitmapData::new(
+                    image.bytes.into_owned(),
+                    (image.width as u32, image.height as u32),
+                    ImageDataType::RGBA
+                );
+
+                if self
+                    .event_proxy
+                    .send_event(UserEventGlutin::ClipboardImage(self.window.id(), image))
+                    .is_err()
+                {
+                    log::error!("Failed to deliver clipboard image: event loop closed");
+                }
+            }
+            Err(err) => log::warn!("Could not read an image from the clipboard: {:?}", err)
+        }
+    }
+
     #[inline]
     pub fn request_redraw(&self) {
         self.redraw_requested.set(true);
@@ -211,7 +442,10 @@ impl<UserEventType> WindowHelperGlutin<UserEventType> {
 
         if self
             .event_proxy
-            .send_event(UserEventGlutin::FullscreenStatusChanged(is_fullscreen))
+            .send_event(UserEventGlutin::FullscreenStatusChanged(
+                window.id(),
+                is_fullscreen
+            ))
             .is_err()
         {
             log::error!("Failed to notify app of fullscreen status change: event loop closed");
@@ -271,6 +505,13 @@ pub(crate) struct WindowGlutin<UserEventType: 'static> {
     context: Rc<PossiblyCurrentContext>,
     surface: Rc<Surface<WindowSurface>>,
     gl_backend: Rc<dyn GLBackend>,
+    gl_version: GLVersion,
+    logical_coordinates: bool,
+    debug_overlay_hotkey: Option<VirtualKeyCode>,
+    update_timestep: Option<Duration>,
+    redraw_mode: RedrawMode,
+    touch_mouse_emulation: bool,
+    pause_when_hidden: bool,
 }
 
 impl<UserEventType: 'static> WindowGlutin<UserEventType> {
@@ -281,13 +522,7 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
         let event_loop: EventLoop<UserEventGlutin<UserEventType>> =
             EventLoopBuilder::with_user_event().build()?;
 
-        let primary_monitor = event_loop
-            .primary_monitor()
-            .or_else(|| {
-                log::error!("Couldn't find primary monitor. Using first available monitor.");
-                event_loop.available_monitors().next()
-            })
-            .ok_or_else(|| BacktraceError::new(WindowCreationError::PrimaryMonitorNotFound))?;
+        let primary_monitor = find_primary_monitor(&event_loop)?;
 
         for (num, monitor) in event_loop.available_monitors().enumerate() {
             log::debug!(
@@ -305,85 +540,16 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
             );
         }
 
-        let mut window_builder = WindowBuilder::new()
-            .with_title(title)
-            .with_resizable(options.resizable)
-            .with_window_level(
-                if options.always_on_top {
-                    WindowLevel::AlwaysOnTop
-                } else {
-                    WindowLevel::Normal
-                }
-            )
-            .with_maximized(options.maximized)
-            .with_visible(false)
-            .with_transparent(options.transparent)
-            .with_decorations(options.decorations);
-
-        match &options.mode {
-            WindowCreationMode::Windowed { size, .. } => {
-                window_builder =
-                    window_builder.with_inner_size(compute_window_size(&primary_monitor, size));
-            }
-
-            WindowCreationMode::FullscreenBorderless => {
-                window_builder = window_builder.with_fullscreen(Some(
-                    winit::window::Fullscreen::Borderless(Some(primary_monitor.clone()))
-                ));
-            }
-        }
+        let window_builder = create_window_builder(title, &options, &primary_monitor);
 
-        let (context, window, surface) =
+        let (context, window, surface, gl_version) =
             create_best_context(&window_builder, &event_loop, &options).ok_or_else(
                 || BacktraceError::new(WindowCreationError::SuitableContextNotFound)
             )?;
 
-        if let WindowCreationMode::Windowed {
-            position: Some(position),
-            ..
-        } = &options.mode
-        {
-            position_window(&primary_monitor, &window, position);
-        }
-
-        // Show window after positioning to avoid the window jumping around
-        window.set_visible(true);
+        show_window(&primary_monitor, &window, &options);
 
-        // Set the position again to work around an issue on Linux
-        if let WindowCreationMode::Windowed {
-            position: Some(position),
-            ..
-        } = &options.mode
-        {
-            position_window(&primary_monitor, &window, position);
-        }
-
-        let glow_context = unsafe {
-            glow::Context::from_loader_function(|ptr| {
-                context.display().get_proc_address(
-                    CString::new(ptr)
-                        .expect("Invalid GL function name string")
-                        .as_c_str()
-                ) as *const _
-            })
-        };
-
-        let gl_backend = Rc::new(GLBackendGlow::new(glow_context));
-
-        if let Some(error_name) = gl_backend.gl_get_error_name() {
-            log::warn!(
-                "Ignoring error in GL bindings during startup: {}",
-                error_name
-            );
-        }
-
-        let version = unsafe { gl_backend.gl_get_string(GL_VERSION) };
-
-        log::info!("Using OpenGL version: {}", version);
-
-        unsafe {
-            gl_backend.gl_enable_debug_message_callback();
-        };
+        let gl_backend = create_gl_backend(&context);
 
         Ok(WindowGlutin {
             event_loop,
@@ -391,6 +557,13 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
             context: Rc::new(context),
             surface: Rc::new(surface),
             gl_backend,
+            gl_version,
+            logical_coordinates: options.logical_coordinates,
+            debug_overlay_hotkey: options.debug_overlay_hotkey,
+            update_timestep: options.update_timestep,
+            redraw_mode: options.redraw_mode,
+            touch_mouse_emulation: options.touch_mouse_emulation,
+            pause_when_hidden: options.pause_when_hidden,
         })
     }
 
@@ -404,7 +577,7 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
 
     fn loop_handle_event<Handler>(
         window: &Rc<Window>,
-        context: &Rc<PossiblyCurrentContext>,
+        context: &mut Rc<PossiblyCurrentContext>,
         surface: &Rc<Surface<WindowSurface>>,
         handler: &mut DrawingWindowHandler<UserEventType, Handler>,
         event: GlutinEvent<UserEventGlutin<UserEventType>>,
@@ -417,12 +590,19 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
             GlutinEvent::LoopExiting => return WindowEventLoopAction::Exit,
 
             GlutinEvent::UserEvent(event) => match event {
-                UserEventGlutin::MouseGrabStatusChanged(grabbed) => {
+                UserEventGlutin::MouseGrabStatusChanged(_, grabbed) => {
                     handler.on_mouse_grab_status_changed(helper, grabbed)
                 }
-                UserEventGlutin::FullscreenStatusChanged(fullscreen) => {
+                UserEventGlutin::FullscreenStatusChanged(_, fullscreen) => {
                     handler.on_fullscreen_status_changed(helper, fullscreen)
                 }
+                UserEventGlutin::ClipboardText(_, text) => {
+                    handler.on_clipboard_text(helper, &text)
+                }
+                #[cfg(feature = "image-loading")]
+                UserEventGlutin::ClipboardImage(_, image) => {
+                    handler.on_clipboard_image(helper, &image)
+                }
                 UserEventGlutin::UserEvent(event) => handler.on_user_event(helper, event),
             },
 
@@ -441,10 +621,25 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
                         surface.resize(context, w, h);
                     }
                     helper.inner().physical_size = physical_size_to_uvec2(physical_size);
-                    handler.on_resize(helper, physical_size_to_uvec2(physical_size))
+                    handler.on_resize(helper, physical_size_to_uvec2(physical_size));
+
+                    // Some platforms report a minimized window as having zero size
+                    let minimized = physical_size.width == 0 || physical_size.height == 0;
+
+                    if let Some(visible) = helper.inner().set_minimized(minimized) {
+                        handler.on_visibility_changed(helper, visible);
+                    }
+                }
+
+                GlutinWindowEvent::Occluded(occluded) => {
+                    if let Some(visible) = helper.inner().set_occluded(occluded) {
+                        handler.on_visibility_changed(helper, visible);
+                    }
                 }
 
-                GlutinWindowEvent::CloseRequested => return WindowEventLoopAction::Exit,
+                GlutinWindowEvent::Focused(focused) => handler.on_focus_changed(helper, focused),
+
+                GlutinWindowEvent::CloseRequested => helper.inner().close_window(),
 
                 GlutinWindowEvent::CursorMoved { position, .. } => {
                     let position = DVec2::new(position.x, position.y);
@@ -479,7 +674,7 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
 
                 GlutinWindowEvent::MouseWheel {
                     delta,
-                    phase: TouchPhase::Moved,
+                    phase: GlutinTouchPhase::Moved,
                     ..
                 } => {
                     let distance = match delta {
@@ -498,6 +693,40 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
                     handler.on_mouse_wheel_scroll(helper, distance);
                 }
 
+                GlutinWindowEvent::HoveredFile(path) => handler.on_file_hover(helper, &path),
+
+                GlutinWindowEvent::HoveredFileCancelled => {
+                    handler.on_file_hover_cancelled(helper)
+                }
+
+                GlutinWindowEvent::DroppedFile(path) => handler.on_file_drop(helper, &path),
+
+                GlutinWindowEvent::TouchpadMagnify { delta, .. } => {
+                    handler.on_pinch(helper, delta)
+                }
+
+                GlutinWindowEvent::TouchpadRotate { delta, .. } => {
+                    handler.on_rotate_gesture(helper, delta)
+                }
+
+                GlutinWindowEvent::SmartMagnify { .. } => handler.on_smart_zoom(helper),
+
+                GlutinWindowEvent::Ime(ime) => handler.on_ime(helper, ime.into()),
+
+                GlutinWindowEvent::Touch(GlutinTouch {
+                    id,
+                    phase,
+                    location,
+                    force,
+                    ..
+                }) => handler.on_touch(
+                    helper,
+                    id,
+                    phase.into(),
+                    DVec2::new(location.x, location.y).as_vec2(),
+                    force.map(|force| force.normalized() as f32)
+                ),
+
                 GlutinWindowEvent::KeyboardInput { event, .. } => {
                     let virtual_key_code = VirtualKeyCode::try_from(&event).ok();
 
@@ -539,10 +768,21 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
             },
 
             GlutinEvent::AboutToWait => {
-                if helper.inner().is_redraw_requested() {
+                let now = Timestamp::now();
+
+                if helper.inner().is_frame_due(&now) {
                     helper.inner().set_redraw_requested(false);
+                    helper.inner().on_frame_start(&now);
                     handler.on_draw(helper);
-                    surface.swap_buffers(context).unwrap();
+
+                    match surface.swap_buffers(context) {
+                        Ok(()) => {}
+                        Err(err) if err.error_kind() == ErrorKind::ContextLost => {
+                            handler.on_context_lost(helper);
+                            Self::recreate_context(window, context, surface, handler, helper);
+                        }
+                        Err(err) => log::error!("Failed to swap buffers: {err:?}")
+                    }
                 }
             }
 
@@ -552,67 +792,122 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
         helper.inner().get_event_loop_action()
     }
 
+    fn recreate_context<Handler>(
+        window: &Window,
+        context: &mut Rc<PossiblyCurrentContext>,
+        surface: &Surface<WindowSurface>,
+        handler: &mut DrawingWindowHandler<UserEventType, Handler>,
+        helper: &mut WindowHelper<UserEventType>
+    ) where
+        Handler: WindowHandler<UserEventType> + 'static
+    {
+        log::info!("Recreating lost GL context");
+
+        let (new_context, gl_version) = match create_context(&context.config(), window, None) {
+            Some(result) => result,
+            None => {
+                log::error!("Failed to recreate GL context");
+                return;
+            }
+        };
+
+        let new_context = match new_context.make_current(surface) {
+            Ok(new_context) => new_context,
+            Err(err) => {
+                log::error!("Failed to make recreated context current: {err:?}");
+                return;
+            }
+        };
+
+        let gl_backend = create_gl_backend(&new_context);
+
+        *context = Rc::new(new_context);
+
+        // The new context doesn't share objects with other windows, so their
+        // images can no longer be drawn in this one
+        handler
+            .renderer_mut()
+            .replace_gl_backend(gl_backend, gl_version);
+
+        handler.renderer().mark_context_current();
+
+        handler.on_context_restored(helper);
+    }
+
     pub fn run_loop<Handler>(self, handler: Handler, renderer: GLRenderer) -> !
     where
         Handler: WindowHandler<UserEventType> + 'static,
     {
         let window = self.window;
-        let context = self.context;
-        let surface = self.surface;
         let event_loop = self.event_loop;
+        let event_proxy = event_loop.create_proxy();
+        let window_requests: WindowCreationRequestQueue<UserEventType> = Rc::default();
 
         let initial_viewport_size_pixels = physical_size_to_uvec2(window.inner_size());
 
-        let mut handler = DrawingWindowHandler::new(handler, renderer);
+        let handler = DrawingWindowHandler::new(handler, renderer)
+            .with_logical_coordinates(self.logical_coordinates)
+            .with_debug_overlay_hotkey(self.debug_overlay_hotkey)
+            .with_update_timestep(self.update_timestep)
+            .with_touch_mouse_emulation(self.touch_mouse_emulation);
 
-        let mut helper = WindowHelper::new(WindowHelperGlutin::new(
+        let helper = WindowHelper::new(WindowHelperGlutin::new(
             &window,
-            event_loop.create_proxy(),
+            event_proxy.clone(),
+            &window_requests,
+            self.redraw_mode,
+            self.pause_when_hidden,
             initial_viewport_size_pixels,
         ));
 
-        handler.on_start(
-            &mut helper,
-            WindowStartupInfo::new(initial_viewport_size_pixels, window.scale_factor()),
+        let mut primary = WindowInstance {
+            window,
+            context: self.context,
+            surface: self.surface,
+            handler,
+            helper
+        };
+
+        primary.handler.on_start(
+            &mut primary.helper,
+            WindowStartupInfo::new(initial_viewport_size_pixels, primary.window.scale_factor()),
         );
 
-        match helper.inner().get_event_loop_action() {
-            WindowEventLoopAction::Continue => {
-                // Do nothing
-            }
-            WindowEventLoopAction::Exit => {
-                log::info!("Start callback requested exit!");
-                drop(handler);
-                std::process::exit(0);
-            }
+        if primary.get_event_loop_action() == WindowEventLoopAction::Exit
+            || primary.is_close_requested()
+        {
+            log::info!("Start callback requested exit!");
+            drop(primary);
+            std::process::exit(0);
         }
 
-        let mut handler = Some(handler);
+        let mut windows = Some(WindowSet {
+            current_window_id: Some(primary.id()),
+            primary,
+            secondary: Vec::new(),
+            event_proxy,
+            window_requests
+        });
 
         let result = event_loop.run(
             move |event: GlutinEvent<UserEventGlutin<UserEventType>>, target| {
-                if handler.is_none() {
+                if windows.is_none() {
                     target.exit();
                 } else {
-                    let action = WindowGlutin::loop_handle_event(
-                        &window,
-                        &context,
-                        &surface,
-                        handler.as_mut().unwrap(),
-                        event,
-                        &mut helper
-                    );
+                    let windows_ref = windows.as_mut().unwrap();
+
+                    let mut action = windows_ref.handle_event(event);
+
+                    if action == WindowEventLoopAction::Continue {
+                        action = windows_ref.create_requested_windows(target);
+                    }
 
                     match action {
                         WindowEventLoopAction::Continue => {
-                            if helper.inner().is_redraw_requested() {
-                                target.set_control_flow(ControlFlow::Poll)
-                            } else {
-                                target.set_control_flow(ControlFlow::Wait)
-                            }
+                            target.set_control_flow(windows_ref.control_flow())
                         }
                         WindowEventLoopAction::Exit => {
-                            handler = None;
+                            windows = None;
                             target.exit();
                         }
                     }
@@ -633,6 +928,472 @@ impl<UserEventType: 'static> WindowGlutin<UserEventType> {
     pub fn gl_backend(&self) -> &Rc<dyn GLBackend> {
         &self.gl_backend
     }
+
+    #[inline]
+    #[must_use]
+    pub fn gl_version(&self) -> GLVersion {
+        self.gl_version
+    }
+}
+
+/// The state of one open window, and the handler receiving its events.
+struct WindowInstance<UserEventType, Handler>
+where
+    UserEventType: 'static,
+    Handler: WindowHandler<UserEventType>
+{
+    window: Rc<Window>,
+    context: Rc<PossiblyCurrentContext>,
+    surface: Rc<Surface<WindowSurface>>,
+    handler: DrawingWindowHandler<UserEventType, Handler>,
+    helper: WindowHelper<UserEventType>
+}
+
+impl<UserEventType, Handler> WindowInstance<UserEventType, Handler>
+where
+    UserEventType: 'static,
+    Handler: WindowHandler<UserEventType> + 'static
+{
+    #[inline]
+    #[must_use]
+    fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// Makes this window's context current, if it isn't already.
+    fn make_current(&self, current_window_id: &mut Option<WindowId>) {
+        if *current_window_id == Some(self.id()) {
+            return;
+        }
+
+        *current_window_id = None;
+
+        if let Err(err) = self.context.make_current(&self.surface) {
+            log::error!("Failed to make window context current: {err:?}");
+            return;
+        }
+
+        self.handler.renderer().mark_context_current();
+        *current_window_id = Some(self.id());
+    }
+
+    fn handle_event(
+        &mut self,
+        event: GlutinEvent<UserEventGlutin<UserEventType>>
+    ) -> WindowEventLoopAction {
+        WindowGlutin::loop_handle_event(
+            &self.window,
+            &mut self.context,
+            &self.surface,
+            &mut self.handler,
+            event,
+            &mut self.helper
+        )
+    }
+
+    #[inline]
+    #[must_use]
+    fn get_event_loop_action(&mut self) -> WindowEventLoopAction {
+        self.helper.inner().get_event_loop_action()
+    }
+
+    #[inline]
+    #[must_use]
+    fn is_close_requested(&mut self) -> bool {
+        self.helper.inner().is_close_requested()
+    }
+}
+
+impl<UserEventType> WindowInstance<UserEventType, Box<dyn WindowHandler<UserEventType>>>
+where
+    UserEventType: 'static
+{
+    /// Creates an additional window. The window reuses the GL config of
+    /// `shared_context`, and its own context shares GL objects with
+    /// `shared_context` (which is in `share_group`) where the platform allows
+    /// it.
+    ///
+    /// On success, the context of the new window is current.
+    fn new_secondary(
+        target: &EventLoopWindowTarget<UserEventGlutin<UserEventType>>,
+        event_proxy: EventLoopProxy<UserEventGlutin<UserEventType>>,
+        window_requests: &WindowCreationRequestQueue<UserEventType>,
+        shared_context: &PossiblyCurrentContext,
+        share_group: u64,
+        request: WindowCreationRequest<UserEventType>
+    ) -> Result<Self, BacktraceError<WindowCreationError>> {
+        let options = request.options;
+        let primary_monitor = find_primary_monitor(target)?;
+        let window_builder = create_window_builder(&request.title, &options, &primary_monitor);
+
+        let gl_config = shared_context.config();
+
+        let window = glutin_winit::finalize_window(target, window_builder, &gl_config)
+            .map_err(|err| {
+                BacktraceError::new_with_cause(WindowCreationError::SuitableContextNotFound, err)
+            })?;
+
+        let attrs = window.build_surface_attributes(SurfaceAttributesBuilder::default());
+
+        let surface = unsafe {
+            gl_config
+                .display()
+                .create_window_surface(&gl_config, &attrs)
+        }
+        .map_err(|err| {
+            BacktraceError::new_with_cause(WindowCreationError::SuitableContextNotFound, err)
+        })?;
+
+        let (context, gl_version, is_shared) =
+            match create_context(&gl_config, &window, Some(shared_context)) {
+                Some((context, gl_version)) => (context, gl_version, true),
+                None => {
+                    log::warn!(
+                        "Failed to create shared context. Images from other windows can't be \
+                         drawn in this one."
+                    );
+
+                    let (context, gl_version) = create_context(&gl_config, &window, None)
+                        .ok_or_else(|| {
+                            BacktraceError::new(WindowCreationError::SuitableContextNotFound)
+                        })?;
+
+                    (context, gl_version, false)
+                }
+            };
+
+        let context = context.make_current(&surface).map_err(|err| {
+            BacktraceError::new_with_cause(WindowCreationError::MakeContextCurrentFailed, err)
+        })?;
+
+        if options.vsync {
+            if let Err(err) = surface.set_swap_interval(
+                &context,
+                SwapInterval::Wait(NonZeroU32::new(1).unwrap())
+            ) {
+                log::error!("Error setting vsync, continuing anyway: {err:?}");
+            }
+        }
+
+        show_window(&primary_monitor, &window, &options);
+
+        let window = Rc::new(window);
+        let initial_viewport_size_pixels = physical_size_to_uvec2(window.inner_size());
+
+        let mut renderer = GLRenderer::new_with_gl_backend(
+            initial_viewport_size_pixels,
+            create_gl_backend(&context),
+            gl_version
+        )
+        .map_err(|err| {
+            BacktraceError::new_with_cause(WindowCreationError::RendererCreationFailed, err)
+        })?;
+
+        if is_shared {
+            renderer.join_share_group(share_group);
+        }
+
+        renderer.mark_context_current();
+
+        if options.virtual_resolution.is_some() {
+            renderer
+                .set_virtual_resolution(options.virtual_resolution)
+                .map_err(|err| {
+                    BacktraceError::new_with_cause(WindowCreationError::RendererCreationFailed, err)
+                })?;
+        }
+
+        let handler = DrawingWindowHandler::new(request.handler, renderer)
+            .with_logical_coordinates(options.logical_coordinates)
+            .with_debug_overlay_hotkey(options.debug_overlay_hotkey)
+            .with_update_timestep(options.update_timestep)
+            .with_touch_mouse_emulation(options.touch_mouse_emulation);
+
+        let helper = WindowHelper::new(WindowHelperGlutin::new(
+            &window,
+            event_proxy,
+            window_requests,
+            options.redraw_mode,
+            options.pause_when_hidden,
+            initial_viewport_size_pixels
+        ));
+
+        Ok(WindowInstance {
+            window,
+            context: Rc::new(context),
+            surface: Rc::new(surface),
+            handler,
+            helper
+        })
+    }
+}
+
+impl<UserEventType, Handler> Drop for WindowInstance<UserEventType, Handler>
+where
+    UserEventType: 'static,
+    Handler: WindowHandler<UserEventType>
+{
+    fn drop(&mut self) {
+        // The renderer is about to be dropped, and must delete its GL objects
+        // using its own context
+        match self.context.make_current(&self.surface) {
+            Ok(()) => self.handler.renderer().mark_context_current(),
+            Err(err) => log::error!("Failed to make window context current: {err:?}")
+        }
+    }
+}
+
+/// All the open windows in the application, which share one event loop.
+struct WindowSet<UserEventType, Handler>
+where
+    UserEventType: 'static,
+    Handler: WindowHandler<UserEventType>
+{
+    primary: WindowInstance<UserEventType, Handler>,
+    secondary: Vec<WindowInstance<UserEventType, Box<dyn WindowHandler<UserEventType>>>>,
+    current_window_id: Option<WindowId>,
+    event_proxy: EventLoopProxy<UserEventGlutin<UserEventType>>,
+    window_requests: WindowCreationRequestQueue<UserEventType>
+}
+
+impl<UserEventType, Handler> WindowSet<UserEventType, Handler>
+where
+    UserEventType: 'static,
+    Handler: WindowHandler<UserEventType> + 'static
+{
+    /// Dispatches the event to the window it concerns. Events which don't
+    /// belong to a specific window, such as user events, are given to the
+    /// primary window.
+    fn handle_event(
+        &mut self,
+        event: GlutinEvent<UserEventGlutin<UserEventType>>
+    ) -> WindowEventLoopAction {
+        if let GlutinEvent::AboutToWait = event {
+            // Give every window the chance to redraw
+            for index in (0..self.secondary.len()).rev() {
+                if self.handle_secondary_event(index, GlutinEvent::AboutToWait)
+                    == WindowEventLoopAction::Exit
+                {
+                    return WindowEventLoopAction::Exit;
+                }
+            }
+        }
+
+        let window_id = match &event {
+            GlutinEvent::WindowEvent { window_id, .. } => Some(*window_id),
+            GlutinEvent::UserEvent(UserEventGlutin::MouseGrabStatusChanged(window_id, _)) => {
+                Some(*window_id)
+            }
+            GlutinEvent::UserEvent(UserEventGlutin::FullscreenStatusChanged(window_id, _)) => {
+                Some(*window_id)
+            }
+            GlutinEvent::UserEvent(UserEventGlutin::ClipboardText(window_id, _)) => {
+                Some(*window_id)
+            }
+            #[cfg(feature = "image-loading")]
+            GlutinEvent::UserEvent(UserEventGlutin::ClipboardImage(window_id, _)) => {
+                Some(*window_id)
+            }
+            _ => None
+        };
+
+        match window_id {
+            Some(window_id) if window_id != self.primary.id() => {
+                match self
+                    .secondary
+                    .iter()
+                    .position(|window| window.id() == window_id)
+                {
+                    Some(index) => self.handle_secondary_event(index, event),
+                    None => {
+                        // The window has already been closed
+                        WindowEventLoopAction::Continue
+                    }
+                }
+            }
+
+            _ => {
+                self.primary.make_current(&mut self.current_window_id);
+
+                let action = self.primary.handle_event(event);
+
+                if self.primary.is_close_requested() {
+                    WindowEventLoopAction::Exit
+                } else {
+                    action
+                }
+            }
+        }
+    }
+
+    fn handle_secondary_event(
+        &mut self,
+        index: usize,
+        event: GlutinEvent<UserEventGlutin<UserEventType>>
+    ) -> WindowEventLoopAction {
+        let window = &mut self.secondary[index];
+
+        window.make_current(&mut self.current_window_id);
+
+        let action = window.handle_event(event);
+
+        if window.is_close_requested() {
+            log::info!("Closing window");
+            self.secondary.remove(index);
+            self.current_window_id = None;
+        }
+
+        action
+    }
+
+    /// Opens any windows requested using [WindowHelper::create_window] since
+    /// the last call.
+    fn create_requested_windows(
+        &mut self,
+        target: &EventLoopWindowTarget<UserEventGlutin<UserEventType>>
+    ) -> WindowEventLoopAction {
+        loop {
+            let requests = std::mem::take(&mut *self.window_requests.borrow_mut());
+
+            if requests.is_empty() {
+                return WindowEventLoopAction::Continue;
+            }
+
+            for request in requests {
+                log::info!("Creating window '{}'", request.title);
+
+                let mut window = match WindowInstance::new_secondary(
+                    target,
+                    self.event_proxy.clone(),
+                    &self.window_requests,
+                    &self.primary.context,
+                    self.primary.handler.renderer().share_group(),
+                    request
+                ) {
+                    Ok(window) => window,
+                    Err(err) => {
+                        log::error!("Failed to create window: {err:?}");
+                        self.current_window_id = None;
+                        continue;
+                    }
+                };
+
+                self.current_window_id = Some(window.id());
+
+                let info = WindowStartupInfo::new(
+                    window.helper.inner().physical_size,
+                    window.window.scale_factor()
+                );
+
+                window.handler.on_start(&mut window.helper, info);
+
+                if window.get_event_loop_action() == WindowEventLoopAction::Exit {
+                    return WindowEventLoopAction::Exit;
+                }
+
+                if window.is_close_requested() {
+                    self.current_window_id = None;
+                } else {
+                    self.secondary.push(window);
+                }
+            }
+        }
+    }
+
+    /// Returns the control flow which wakes the event loop in time for the
+    /// next frame which is due in any of the windows.
+    #[must_use]
+    fn control_flow(&mut self) -> ControlFlow {
+        let now = Timestamp::now();
+        let mut next_frame_deadline: Option<Timestamp> = None;
+
+        let helpers = std::iter::once(&mut self.primary.helper)
+            .chain(self.secondary.iter_mut().map(|window| &mut window.helper));
+
+        for helper in helpers {
+            if helper.inner().is_frame_due(&now) {
+                return ControlFlow::Poll;
+            }
+
+            let deadline = helper.inner().next_frame_deadline();
+
+            next_frame_deadline = match (next_frame_deadline, deadline) {
+                (Some(earliest), Some(deadline)) if earliest < deadline => Some(earliest),
+                (earliest, None) => earliest,
+                (_, deadline) => deadline
+            };
+        }
+
+        match next_frame_deadline {
+            Some(deadline) => ControlFlow::WaitUntil(deadline.instant()),
+            None => ControlFlow::Wait
+        }
+    }
+}
+
+fn find_primary_monitor<UserEventType>(
+    target: &EventLoopWindowTarget<UserEventType>
+) -> Result<MonitorHandle, BacktraceError<WindowCreationError>> {
+    target
+        .primary_monitor()
+        .or_else(|| {
+            log::error!("Couldn't find primary monitor. Using first available monitor.");
+            target.available_monitors().next()
+        })
+        .ok_or_else(|| BacktraceError::new(WindowCreationError::PrimaryMonitorNotFound))
+}
+
+fn create_window_builder(
+    title: &str,
+    options: &WindowCreationOptions,
+    primary_monitor: &MonitorHandle
+) -> WindowBuilder {
+    let window_builder = WindowBuilder::new()
+        .with_title(title)
+        .with_resizable(options.resizable)
+        .with_window_level(
+            if options.always_on_top {
+                WindowLevel::AlwaysOnTop
+            } else {
+                WindowLevel::Normal
+            }
+        )
+        .with_maximized(options.maximized)
+        .with_visible(false)
+        .with_transparent(options.transparent)
+        .with_decorations(options.decorations);
+
+    match &options.mode {
+        WindowCreationMode::Windowed { size, .. } => {
+            window_builder.with_inner_size(compute_window_size(primary_monitor, size))
+        }
+
+        WindowCreationMode::FullscreenBorderless => window_builder.with_fullscreen(Some(
+            winit::window::Fullscreen::Borderless(Some(primary_monitor.clone()))
+        ))
+    }
+}
+
+fn show_window(primary_monitor: &MonitorHandle, window: &Window, options: &WindowCreationOptions) {
+    if let WindowCreationMode::Windowed {
+        position: Some(position),
+        ..
+    } = &options.mode
+    {
+        position_window(primary_monitor, window, position);
+    }
+
+    // Show window after positioning to avoid the window jumping around
+    window.set_visible(true);
+
+    // Set the position again to work around an issue on Linux
+    if let WindowCreationMode::Windowed {
+        position: Some(position),
+        ..
+    } = &options.mode
+    {
+        position_window(primary_monitor, window, position);
+    }
 }
 
 fn gl_config_picker(mut configs: Box<dyn Iterator<Item = Config> + '_>) -> Config
@@ -644,7 +1405,12 @@ fn create_best_context<UserEventType>(
     window_builder: &WindowBuilder,
     event_loop: &EventLoop<UserEventType>,
     options: &WindowCreationOptions
-) -> Option<(PossiblyCurrentContext, Window, Surface<WindowSurface>)> {
+) -> Option<(
+    PossiblyCurrentContext,
+    Window,
+    Surface<WindowSurface>,
+    GLVersion
+)> {
     for multisampling in &[options.multisampling, 16, 8, 4, 2, 1, 0] {
         log::info!("Trying multisampling={}...", multisampling);
 
@@ -677,20 +1443,10 @@ fn create_best_context<UserEventType>(
             }
         };
 
-        let gl_display = gl_config.display();
-
-        let context_attributes = ContextAttributesBuilder::new()
-            .with_context_api(ContextApi::OpenGl(Some(Version::new(2, 0))))
-            .build(Some(window.raw_window_handle()));
-
-        let context =
-            match unsafe { gl_display.create_context(&gl_config, &context_attributes) } {
-                Ok(context) => context,
-                Err(err) => {
-                    log::info!("Failed to create context with error: {err:?}");
-                    continue;
-                }
-            };
+        let (context, gl_version) = match create_context(&gl_config, &window, None) {
+            Some(result) => result,
+            None => continue
+        };
 
         let window = match glutin_winit::finalize_window(
             event_loop,
@@ -735,13 +1491,92 @@ fn create_best_context<UserEventType>(
             }
         }
 
-        return Some((context, window, surface));
+        return Some((context, window, surface, gl_version));
     }
 
     log::error!("Failed to create any context.");
     None
 }
 
+fn create_gl_backend(context: &PossiblyCurrentContext) -> Rc<GLBackendGlow> {
+    let glow_context = unsafe {
+        glow::Context::from_loader_function(|ptr| {
+            context.display().get_proc_address(
+                CString::new(ptr)
+                    .expect("Invalid GL function name string")
+                    .as_c_str()
+            ) as *const _
+        })
+    };
+
+    let gl_backend = Rc::new(GLBackendGlow::new(glow_context));
+
+    if let Some(error_name) = gl_backend.gl_get_error_name() {
+        log::warn!(
+            "Ignoring error in GL bindings during startup: {}",
+            error_name
+        );
+    }
+
+    let version = unsafe { gl_backend.gl_get_string(GL_VERSION) };
+
+    log::info!("Using OpenGL version: {}", version);
+
+    unsafe {
+        gl_backend.gl_enable_debug_message_callback();
+    };
+
+    gl_backend
+}
+
+fn create_context(
+    gl_config: &Config,
+    window: &Window,
+    shared_context: Option<&PossiblyCurrentContext>
+) -> Option<(NotCurrentContext, GLVersion)> {
+    let gl_display = gl_config.display();
+
+    // Prefer the core profile where available, and fall back to GL 2.0, and
+    // then to GLES 2.0 for devices without desktop GL
+    let attempts = [
+        (
+            GLVersion::OpenGL3_3Core,
+            ContextAttributesBuilder::new()
+                .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+                .with_profile(GlProfile::Core)
+        ),
+        (
+            GLVersion::OpenGL2_0,
+            ContextAttributesBuilder::new()
+                .with_context_api(ContextApi::OpenGl(Some(Version::new(2, 0))))
+        ),
+        (
+            GLVersion::OpenGLES2_0,
+            ContextAttributesBuilder::new()
+                .with_context_api(ContextApi::Gles(Some(Version::new(2, 0))))
+        )
+    ];
+
+    for (gl_version, context_attributes) in attempts {
+        log::info!("Trying to create context for {gl_version:?}...");
+
+        let context_attributes = match shared_context {
+            Some(shared_context) => context_attributes.with_sharing(shared_context),
+            None => context_attributes
+        }
+        .build(Some(window.raw_window_handle()));
+
+        match unsafe { gl_display.create_context(gl_config, &context_attributes) } {
+            Ok(context) => return Some((context, gl_version)),
+            Err(err) => {
+                log::info!("Failed to create context with error: {err:?}");
+            }
+        }
+    }
+
+    None
+}
+
 fn position_window(monitor: &MonitorHandle, window: &GlutinWindow, position: &WindowPosition) {
     let monitor_position = monitor.position();
 
@@ -820,6 +1655,28 @@ impl From<winit::event::MouseButton> for MouseButton {
     }
 }
 
+impl From<GlutinTouchPhase> for TouchPhase {
+    fn from(phase: GlutinTouchPhase) -> Self {
+        match phase {
+            GlutinTouchPhase::Started => TouchPhase::Started,
+            GlutinTouchPhase::Moved => TouchPhase::Moved,
+            GlutinTouchPhase::Ended => TouchPhase::Ended,
+            GlutinTouchPhase::Cancelled => TouchPhase::Cancelled
+        }
+    }
+}
+
+impl From<GlutinIme> for ImeEvent {
+    fn from(ime: GlutinIme) -> Self {
+        match ime {
+            GlutinIme::Enabled => ImeEvent::Enabled,
+            GlutinIme::Preedit(text, cursor) => ImeEvent::Preedit { text, cursor },
+            GlutinIme::Commit(text) => ImeEvent::Commit(text),
+            GlutinIme::Disabled => ImeEvent::Disabled
+        }
+    }
+}
+
 impl TryFrom<&KeyEvent> for VirtualKeyCode {
     type Error = ();
 
@@ -983,8 +1840,11 @@ impl From<winit::keyboard::ModifiersState> for ModifiersState {
 }
 
 pub(crate) enum UserEventGlutin<UserEventType: 'static> {
-    MouseGrabStatusChanged(bool),
-    FullscreenStatusChanged(bool),
+    MouseGrabStatusChanged(WindowId, bool),
+    FullscreenStatusChanged(WindowId, bool),
+    ClipboardText(WindowId, String),
+    #[cfg(feature = "image-loading")]
+    ClipboardImage(WindowId, RawBitmapData),
     UserEvent(UserEventType),
 }
 
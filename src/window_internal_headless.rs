@@ -0,0 +1,862 @@
+/*
+ *  Copyright 2021 QuantumBadger
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::Duration;
+
+use glam::{IVec2, UVec2, Vec2};
+
+use crate::error::{BacktraceError, ErrorMessage};
+use crate::recording::RecordedEvent;
+use crate::time::Timestamp;
+use crate::window::{
+    DrawingWindowHandler, EventLoopSendError, RedrawMode, UserEventSender, WindowCreationOptions,
+    WindowFullscreenMode, WindowHandler, WindowHelper, WindowStartupInfo,
+};
+use crate::{GLRenderer, Rect};
+
+#[cfg(feature = "image-loading")]
+use crate::image::{ImageDataType, RawBitmapData};
+
+/// A window helper which isn't attached to a real window, as used by
+/// [crate::HeadlessWindow] and [crate::recording::EventReplayer]. Requests
+/// made by the handler are stored, so that they can be inspected.
+pub(crate) struct WindowHelperHeadless<UserEventType: 'static> {
+    user_event_sender: Sender<UserEventType>,
+    user_event_receiver: Receiver<UserEventType>,
+    redraw_requested: Cell<bool>,
+    redraw_mode: Cell<RedrawMode>,
+    terminate_requested: bool,
+    close_requested: bool,
+    size_pixels: UVec2,
+    scale_factor: f64,
+    requested_size_pixels: Cell<Option<UVec2>>,
+    position_pixels: Cell<Option<IVec2>>,
+    title: RefCell<String>,
+    cursor_visible: Cell<bool>,
+    cursor_grabbed: Cell<bool>,
+    resizable: Cell<bool>,
+    ime_allowed: Cell<bool>,
+    ctrl_wheel_pinch: Cell<bool>,
+    ime_cursor_area: Cell<Option<Rect>>,
+    clipboard_text: RefCell<Option<String>>,
+    clipboard_text_requested: Cell<bool>,
+    #[cfg(feature = "image-loading")]
+    clipboard_image: RefCell<Option<(UVec2, Vec<u8>)>>,
+    #[cfg(feature = "image-loading")]
+    clipboard_image_requested: Cell<bool>,
+    fullscreen_mode: Cell<WindowFullscreenMode>,
+    pending_mouse_grab_status: Cell<Option<bool>>,
+    pending_fullscreen_status: Cell<Option<bool>>,
+}
+
+impl<UserEventType> WindowHelperHeadless<UserEventType> {
+    pub fn new(size_pixels: UVec2, scale_factor: f64, redraw_mode: RedrawMode) -> Self {
+        let (user_event_sender, user_event_receiver) = channel();
+
+        WindowHelperHeadless {
+            user_event_sender,
+            user_event_receiver,
+            redraw_requested: Cell::new(false),
+            redraw_mode: Cell::new(redraw_mode),
+            terminate_requested: false,
+            close_requested: false,
+            size_pixels,
+            scale_factor,
+            requested_size_pixels: Cell::new(None),
+            position_pixels: Cell::new(None),
+            title: RefCell::new(String::new()),
+            cursor_visible: Cell::new(true),
+            cursor_grabbed: Cell::new(false),
+            resizable: Cell::new(true),
+            ime_allowed: Cell::new(false),
+            ctrl_wheel_pinch: Cell::new(false),
+            ime_cursor_area: Cell::new(None),
+            clipboard_text: RefCell::new(None),
+            clipboard_text_requested: Cell::new(false),
+            #[cfg(feature = "image-loading")]
+            clipboard_image: RefCell::new(None),
+            #[cfg(feature = "image-loading")]
+            clipboard_image_requested: Cell::new(false),
+            fullscreen_mode: Cell::new(WindowFullscreenMode::Windowed),
+            pending_mouse_grab_status: Cell::new(None),
+            pending_fullscreen_status: Cell::new(None),
+        }
+    }
+
+    /// Returns the next event sent using a [UserEventSender] created by this
+    /// helper, if any.
+    #[inline]
+    #[must_use]
+    pub fn take_user_event(&self) -> Option<UserEventType> {
+        self.user_event_receiver.try_recv().ok()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_redraw_requested(&self) -> bool {
+        self.redraw_requested.get()
+    }
+
+    #[inline]
+    pub fn clear_redraw_requested(&self) {
+        self.redraw_requested.set(false);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_terminate_requested(&self) -> bool {
+        self.terminate_requested
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_close_requested(&self) -> bool {
+        self.close_requested
+    }
+
+    pub fn set_size_and_scale_factor(&mut self, size_pixels: UVec2, scale_factor: f64) {
+        self.size_pixels = size_pixels;
+        self.scale_factor = scale_factor;
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn take_requested_size_pixels(&self) -> Option<UVec2> {
+        self.requested_size_pixels.take()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn take_pending_mouse_grab_status(&self) -> Option<bool> {
+        self.pending_mouse_grab_status.take()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn take_pending_fullscreen_status(&self) -> Option<bool> {
+        self.pending_fullscreen_status.take()
+    }
+
+    /// Returns the clipboard text, if the handler has requested it since this
+    /// was last called.
+    #[must_use]
+    pub fn take_requested_clipboard_text(&self) -> Option<String> {
+        if self.clipboard_text_requested.take() {
+            self.clipboard_text()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the clipboard image, if the handler has requested it since
+    /// this was last called.
+    #[cfg(feature = "image-loading")]
+    #[must_use]
+    pub fn take_requested_clipboard_image(&self) -> Option<(UVec2, Vec<u8>)> {
+        if self.clipboard_image_requested.take() {
+            self.clipboard_image.borrow().clone()
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn position_pixels(&self) -> Option<IVec2> {
+        self.position_pixels.get()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn title(&self) -> String {
+        self.title.borrow().clone()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_cursor_visible(&self) -> bool {
+        self.cursor_visible.get()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_cursor_grabbed(&self) -> bool {
+        self.cursor_grabbed.get()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_resizable(&self) -> bool {
+        self.resizable.get()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_ime_allowed(&self) -> bool {
+        self.ime_allowed.get()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_ctrl_wheel_pinch_enabled(&self) -> bool {
+        self.ctrl_wheel_pinch.get()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn ime_cursor_area(&self) -> Option<Rect> {
+        self.ime_cursor_area.get()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn clipboard_text(&self) -> Option<String> {
+        self.clipboard_text.borrow().clone()
+    }
+
+    #[cfg(feature = "image-loading")]
+    #[must_use]
+    pub fn clipboard_image(&self) -> Option<RawBitmapData> {
+        self.clipboard_image
+            .borrow()
+            .as_ref()
+            .map(|(size, data)| RawBitmapData::new(data.clone(), *size, ImageDataType::RGBA))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn fullscreen_mode(&self) -> WindowFullscreenMode {
+        self.fullscreen_mode.get()
+    }
+
+    pub fn set_redraw_mode(&self, redraw_mode: RedrawMode) {
+        self.redraw_mode.set(redraw_mode);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn redraw_mode(&self) -> RedrawMode {
+        self.redraw_mode.get()
+    }
+
+    pub fn terminate_loop(&mut self) {
+        self.terminate_requested = true;
+    }
+
+    pub fn close_window(&mut self) {
+        self.close_requested = true;
+    }
+
+    pub fn create_window(
+        &self,
+        _title: &str,
+        _options: WindowCreationOptions,
+        _handler: Box<dyn WindowHandler<UserEventType>>,
+    ) {
+        log::warn!("Additional windows cannot be created from a headless window");
+    }
+
+    pub fn set_icon_from_rgba_pixels(
+        &self,
+        data: Vec<u8>,
+        size: UVec2,
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        if data.len() != size.x as usize * size.y as usize * 4 {
+            return Err(ErrorMessage::msg("Icon data was invalid"));
+        }
+
+        Ok(())
+    }
+
+    pub fn set_cursor_visible(&self, visible: bool) {
+        self.cursor_visible.set(visible);
+    }
+
+    pub fn set_cursor_grab(&self, grabbed: bool) -> Result<(), BacktraceError<ErrorMessage>> {
+        self.cursor_grabbed.set(grabbed);
+        self.pending_mouse_grab_status.set(Some(grabbed));
+        Ok(())
+    }
+
+    pub fn set_resizable(&self, resizable: bool) {
+        self.resizable.set(resizable);
+    }
+
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.ime_allowed.set(allowed);
+    }
+
+    pub fn set_ctrl_wheel_pinch(&self, enabled: bool) {
+        self.ctrl_wheel_pinch.set(enabled);
+    }
+
+    pub fn set_ime_cursor_area(&self, area_pixels: Rect) {
+        self.ime_cursor_area.set(Some(area_pixels));
+    }
+
+    pub fn set_clipboard_text(&self, text: &str) -> Result<(), BacktraceError<ErrorMessage>> {
+        *self.clipboard_text.borrow_mut() = Some(text.to_string());
+        #[cfg(feature = "image-loading")]
+        {
+            *self.clipboard_image.borrow_mut() = None;
+        }
+        Ok(())
+    }
+
+    pub fn request_clipboard_text(&self) {
+        self.clipboard_text_requested.set(true);
+    }
+
+    #[cfg(feature = "image-loading")]
+    pub fn set_clipboard_image(
+        &self,
+        data: Vec<u8>,
+        size: UVec2,
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        *self.clipboard_image.borrow_mut() = Some((size, data));
+        *self.clipboard_text.borrow_mut() = None;
+        Ok(())
+    }
+
+    #[cfg(feature = "image-loading")]
+    pub fn request_clipboard_image(&self) {
+        self.clipboard_image_requested.set(true);
+    }
+
+    #[inline]
+    pub fn request_redraw(&self) {
+        self.redraw_requested.set(true);
+    }
+
+    pub fn set_title(&self, title: &str) {
+        *self.title.borrow_mut() = title.to_string();
+    }
+
+    pub fn set_fullscreen_mode(&self, mode: WindowFullscreenMode) {
+        self.fullscreen_mode.set(mode);
+        self.pending_fullscreen_status
+            .set(Some(mode == WindowFullscreenMode::FullscreenBorderless));
+    }
+
+    pub fn set_size_pixels<S: Into<UVec2>>(&self, size: S) {
+        self.requested_size_pixels.set(Some(size.into()));
+    }
+
+    pub fn get_size_pixels(&self) -> UVec2 {
+        self.size_pixels
+    }
+
+    pub fn set_size_scaled_pixels<S: Into<Vec2>>(&self, size: S) {
+        let size = (size.into() * self.scale_factor as f32).round();
+        self.set_size_pixels(size.as_uvec2());
+    }
+
+    pub fn set_position_pixels<P: Into<IVec2>>(&self, position: P) {
+        self.position_pixels.set(Some(position.into()));
+    }
+
+    pub fn set_position_scaled_pixels<P: Into<Vec2>>(&self, position: P) {
+        let position = (position.into() * self.scale_factor as f32).round();
+        self.set_position_pixels(position.as_ivec2());
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn get_scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    pub fn create_user_event_sender(&self) -> UserEventSender<UserEventType> {
+        UserEventSender::new_headless(UserEventSenderHeadless {
+            sender: self.user_event_sender.clone(),
+        })
+    }
+}
+
+pub(crate) struct UserEventSenderHeadless<UserEventType: 'static> {
+    sender: Sender<UserEventType>,
+}
+
+impl<UserEventType> Clone for UserEventSenderHeadless<UserEventType> {
+    fn clone(&self) -> Self {
+        UserEventSenderHeadless {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<UserEventType> UserEventSenderHeadless<UserEventType> {
+    pub fn send_event(&self, event: UserEventType) -> Result<(), EventLoopSendError> {
+        self.sender
+            .send(event)
+            .map_err(|_| EventLoopSendError::EventLoopNoLongerExists)
+    }
+}
+
+/// A handler and helper which aren't attached to a real window. Events are
+/// delivered by calling the methods of this struct, and time only advances
+/// when frames are drawn.
+pub(crate) struct HeadlessWindowImpl<UserEventType, H>
+where
+    UserEventType: 'static,
+    H: WindowHandler<UserEventType>,
+{
+    handler: DrawingWindowHandler<UserEventType, H>,
+    helper: WindowHelper<UserEventType>,
+    start: Timestamp,
+    elapsed: Duration,
+}
+
+impl<UserEventType, H> HeadlessWindowImpl<UserEventType, H>
+where
+    UserEventType: 'static,
+    H: WindowHandler<UserEventType>,
+{
+    /// Creates the window, and invokes [WindowHandler::on_start].
+    pub fn new(
+        size_pixels: UVec2,
+        scale_factor: f64,
+        mut renderer: GLRenderer,
+        options: WindowCreationOptions,
+        handler: H,
+    ) -> Result<Self, BacktraceError<ErrorMessage>> {
+        renderer.set_viewport_size_pixels(size_pixels);
+
+        if options.virtual_resolution.is_some() {
+            renderer
+                .set_virtual_resolution(options.virtual_resolution)
+                .map_err(|err| {
+                    ErrorMessage::msg_with_cause("Failed to set virtual resolution", err)
+                })?;
+        }
+
+        let mut handler = DrawingWindowHandler::new(handler, renderer)
+            .with_logical_coordinates(options.logical_coordinates)
+            .with_debug_overlay_hotkey(options.debug_overlay_hotkey)
+            .with_update_timestep(options.update_timestep)
+            .with_touch_mouse_emulation(options.touch_mouse_emulation);
+
+        let mut helper = WindowHelper::new_headless(WindowHelperHeadless::new(
+            size_pixels,
+            scale_factor,
+            options.redraw_mode,
+        ));
+
+        handler.on_start(
+            &mut helper,
+            WindowStartupInfo::new(size_pixels, scale_factor),
+        );
+
+        Ok(HeadlessWindowImpl {
+            handler,
+            helper,
+            start: Timestamp::now(),
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn handler(&self) -> &H {
+        self.handler.window_handler()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn handler_mut(&mut self) -> &mut H {
+        self.handler.window_handler_mut()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn helper(&mut self) -> &mut WindowHelper<UserEventType> {
+        &mut self.helper
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn headless(&self) -> &WindowHelperHeadless<UserEventType> {
+        self.helper.headless()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn renderer_mut(&mut self) -> &mut GLRenderer {
+        self.handler.renderer_mut()
+    }
+
+    /// The time at which the most recent frame was drawn, relative to the
+    /// creation of the window.
+    #[inline]
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns true once the handler has terminated the loop or closed the
+    /// window, after which no further callbacks are given.
+    #[inline]
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        let headless = self.headless();
+        headless.is_terminate_requested() || headless.is_close_requested()
+    }
+
+    /// Draws a frame, timed as if it was drawn at the specified time after the
+    /// creation of the window.
+    pub fn draw_frame_at(&mut self, elapsed: Duration) {
+        if self.is_finished() {
+            return;
+        }
+
+        self.elapsed = elapsed;
+        self.helper.headless().clear_redraw_requested();
+        self.handler
+            .on_draw_at(&mut self.helper, self.start.plus(elapsed));
+    }
+
+    pub fn deliver_user_event(&mut self, user_event: UserEventType) {
+        if self.is_finished() {
+            return;
+        }
+
+        self.handler.on_user_event(&mut self.helper, user_event);
+    }
+
+    /// Delivers an event to the handler, updating the window state first if
+    /// the event changes it. [RecordedEvent::Draw] draws a frame at the
+    /// current time.
+    pub fn deliver(&mut self, event: RecordedEvent) {
+        if self.is_finished() {
+            return;
+        }
+
+        let helper = &mut self.helper;
+        let handler = &mut self.handler;
+
+        match event {
+            RecordedEvent::UserEvent(_) => {
+                log::error!("User events must be parsed before they are delivered")
+            }
+            RecordedEvent::Resize(size) => {
+                let scale_factor = helper.get_scale_factor();
+                helper
+                    .headless_mut()
+                    .set_size_and_scale_factor(size, scale_factor);
+                handler.on_resize(helper, size)
+            }
+            RecordedEvent::MouseGrabStatusChanged(grabbed) => {
+                handler.on_mouse_grab_status_changed(helper, grabbed)
+            }
+            RecordedEvent::FullscreenStatusChanged(fullscreen) => {
+                handler.on_fullscreen_status_changed(helper, fullscreen)
+            }
+            RecordedEvent::ScaleFactorChanged(scale_factor) => {
+                let size = helper.get_size_pixels();
+                helper
+                    .headless_mut()
+                    .set_size_and_scale_factor(size, scale_factor);
+                handler.on_scale_factor_changed(helper, scale_factor)
+            }
+            RecordedEvent::Draw => self.draw_frame_at(self.elapsed),
+            RecordedEvent::MouseMove(position) => handler.on_mouse_move(helper, position),
+            RecordedEvent::MouseButtonDown(button) => handler.on_mouse_button_down(helper, button),
+            RecordedEvent::MouseButtonUp(button) => handler.on_mouse_button_up(helper, button),
+            RecordedEvent::MouseWheelScroll(distance) => {
+                handler.on_mouse_wheel_scroll(helper, distance)
+            }
+            RecordedEvent::FocusChanged(focused) => handler.on_focus_changed(helper, focused),
+            RecordedEvent::VisibilityChanged(visible) => {
+                handler.on_visibility_changed(helper, visible)
+            }
+            RecordedEvent::Pinch(delta) => handler.on_pinch(helper, delta),
+            RecordedEvent::RotateGesture(delta) => handler.on_rotate_gesture(helper, delta),
+            RecordedEvent::SmartZoom => handler.on_smart_zoom(helper),
+            RecordedEvent::FileHover(path) => handler.on_file_hover(helper, &path),
+            RecordedEvent::FileHoverCancelled => handler.on_file_hover_cancelled(helper),
+            RecordedEvent::FileDrop(path) => handler.on_file_drop(helper, &path),
+            RecordedEvent::FileDropData(name, bytes) => {
+                handler.on_file_drop_data(helper, &name, &bytes)
+            }
+            RecordedEvent::ClipboardText(text) => handler.on_clipboard_text(helper, &text),
+            #[cfg(feature = "image-loading")]
+            RecordedEvent::ClipboardImage(size, pixels) => handler.on_clipboard_image(
+                helper,
+                &RawBitmapData::new(pixels, size, ImageDataType::RGBA),
+            ),
+            RecordedEvent::Touch(id, phase, position, force) => {
+                handler.on_touch(helper, id, phase, position, force)
+            }
+            RecordedEvent::KeyDown(virtual_key_code, scancode) => {
+                handler.on_key_down(helper, virtual_key_code, scancode)
+            }
+            RecordedEvent::KeyUp(virtual_key_code, scancode) => {
+                handler.on_key_up(helper, virtual_key_code, scancode)
+            }
+            RecordedEvent::KeyboardChar(codepoint) => handler.on_keyboard_char(helper, codepoint),
+            RecordedEvent::Ime(event) => handler.on_ime(helper, event),
+            RecordedEvent::KeyboardModifiersChanged(state) => {
+                handler.on_keyboard_modifiers_changed(helper, state)
+            }
+            RecordedEvent::ContextLost => handler.on_context_lost(helper),
+            RecordedEvent::ContextRestored => handler.on_context_restored(helper),
+        }
+    }
+
+    /// Delivers the events which a real window would generate in response to
+    /// the handler's requests: user events, cursor grab and fullscreen
+    /// notifications, clipboard contents, and resizes.
+    pub fn process_pending_requests(&mut self) {
+        let headless = self.headless();
+
+        let user_events: Vec<UserEventType> =
+            std::iter::from_fn(|| headless.take_user_event()).collect();
+        let mouse_grabbed = headless.take_pending_mouse_grab_status();
+        let fullscreen = headless.take_pending_fullscreen_status();
+        let clipboard_text = headless.take_requested_clipboard_text();
+        #[cfg(feature = "image-loading")]
+        let clipboard_image = headless.take_requested_clipboard_image();
+        let size = headless.take_requested_size_pixels();
+
+        for user_event in user_events {
+            self.deliver_user_event(user_event);
+        }
+
+        if let Some(mouse_grabbed) = mouse_grabbed {
+            self.deliver(RecordedEvent::MouseGrabStatusChanged(mouse_grabbed));
+        }
+
+        if let Some(fullscreen) = fullscreen {
+            self.deliver(RecordedEvent::FullscreenStatusChanged(fullscreen));
+        }
+
+        if let Some(text) = clipboard_text {
+            self.deliver(RecordedEvent::ClipboardText(text));
+        }
+
+        #[cfg(feature = "image-loading")]
+        if let Some((size, pixels)) = clipboard_image {
+            self.deliver(RecordedEvent::ClipboardImage(size, pixels));
+        }
+
+        if let Some(size) = size {
+            if size != self.headless().get_size_pixels() {
+                self.deliver(RecordedEvent::Resize(size));
+            }
+        }
+    }
+
+    /// Discards the handler's pending requests, without delivering any
+    /// events.
+    pub fn discard_pending_requests(&mut self) {
+        let headless = self.headless();
+
+        while headless.take_user_event().is_some() {}
+        let _ = headless.take_pending_mouse_grab_status();
+        let _ = headless.take_pending_fullscreen_status();
+        let _ = headless.take_requested_clipboard_text();
+        #[cfg(feature = "image-loading")]
+        let _ = headless.take_requested_clipboard_image();
+        let _ = headless.take_requested_size_pixels();
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use std::time::Duration;
+
+    use glam::Vec2;
+
+    use crate::color::Color;
+    use crate::window::{
+        MouseButton, TouchPhase, VirtualKeyCode, WindowCreationOptions, WindowHandler,
+        WindowHelper, WindowSize,
+    };
+    use crate::{Graphics2D, HeadlessWindow, Rect};
+
+    #[derive(Default)]
+    struct TestHandler {
+        draw_count: u32,
+        grab_notifications: Vec<bool>,
+    }
+
+    impl WindowHandler for TestHandler {
+        fn on_mouse_grab_status_changed(&mut self, _helper: &mut WindowHelper, grabbed: bool) {
+            self.grab_notifications.push(grabbed);
+        }
+
+        fn on_draw(&mut self, _helper: &mut WindowHelper, graphics: &mut Graphics2D) {
+            self.draw_count += 1;
+            graphics.clear_screen(Color::RED);
+        }
+
+        fn on_mouse_button_down(&mut self, helper: &mut WindowHelper, _button: MouseButton) {
+            helper.set_cursor_grab(true).unwrap();
+        }
+
+        fn on_key_down(
+            &mut self,
+            helper: &mut WindowHelper,
+            virtual_key_code: Option<VirtualKeyCode>,
+            _scancode: u32,
+        ) {
+            match virtual_key_code {
+                Some(VirtualKeyCode::T) => helper.set_title("Changed"),
+                Some(VirtualKeyCode::Escape) => helper.terminate_loop(),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_helper_requests() {
+        let mut window = HeadlessWindow::new((20, 10), TestHandler::default()).unwrap();
+
+        window.on_key_down(Some(VirtualKeyCode::T), 20);
+        assert_eq!(window.title(), "Changed");
+
+        window.on_mouse_button_down(MouseButton::Left);
+        assert!(window.is_cursor_grabbed());
+        assert_eq!(window.handler().grab_notifications, vec![true]);
+
+        window.step_frames(3);
+        assert_eq!(window.handler().draw_count, 3);
+        assert_eq!(window.elapsed(), Duration::from_secs(1) / 60 * 3);
+
+        window.on_key_down(Some(VirtualKeyCode::Escape), 1);
+        assert!(window.is_terminate_requested());
+
+        window.step_frame();
+        assert_eq!(window.handler().draw_count, 3);
+    }
+
+    struct TouchHandler;
+
+    impl WindowHandler for TouchHandler {}
+
+    #[test]
+    fn test_touch_mouse_emulation() {
+        let options =
+            WindowCreationOptions::new_windowed(WindowSize::PhysicalPixels((20, 10).into()), None)
+                .with_touch_mouse_emulation(true);
+
+        let mut window = HeadlessWindow::new_with_options((20, 10), options, TouchHandler).unwrap();
+
+        window.on_touch(1, TouchPhase::Started, (5.0, 6.0), None);
+        window.on_touch(2, TouchPhase::Started, (15.0, 2.0), Some(0.5));
+        window.on_touch(1, TouchPhase::Moved, (7.0, 8.0), None);
+
+        let input = window.helper().input();
+        assert!(input.is_button_down(MouseButton::Left));
+        assert_eq!(input.mouse_position(), Vec2::new(7.0, 8.0));
+
+        window.on_touch(2, TouchPhase::Ended, (15.0, 2.0), None);
+        assert!(window.helper().input().is_button_down(MouseButton::Left));
+
+        window.on_touch(1, TouchPhase::Cancelled, (7.0, 8.0), None);
+        assert!(!window.helper().input().is_button_down(MouseButton::Left));
+    }
+
+    #[test]
+    fn test_ime_cursor_area_uses_virtual_resolution() {
+        let options =
+            WindowCreationOptions::new_windowed(WindowSize::PhysicalPixels((40, 20).into()), None)
+                .with_virtual_resolution((20, 10).into());
+
+        let mut window = HeadlessWindow::new_with_options((40, 20), options, TouchHandler).unwrap();
+
+        window
+            .helper()
+            .set_ime_cursor_area(Rect::new(Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0)));
+        assert_eq!(window.ime_cursor_area(), None);
+
+        window.step_frame();
+        assert_eq!(
+            window.ime_cursor_area(),
+            Some(Rect::new(Vec2::new(2.0, 4.0), Vec2::new(6.0, 8.0)))
+        );
+    }
+
+    #[test]
+    fn test_focus_loss_releases_input() {
+        let mut window = HeadlessWindow::new((20, 10), TouchHandler).unwrap();
+
+        window.on_key_down(Some(VirtualKeyCode::W), 17);
+        window.on_mouse_button_down(MouseButton::Right);
+        assert!(window.helper().input().is_key_down(VirtualKeyCode::W));
+
+        window.on_focus_changed(false);
+
+        let input = window.helper().input();
+        assert!(!input.is_key_down(VirtualKeyCode::W));
+        assert!(!input.is_scancode_down(17));
+        assert!(!input.is_button_down(MouseButton::Right));
+        assert!(input.was_key_released_this_frame(VirtualKeyCode::W));
+    }
+
+    #[derive(Default)]
+    struct ClipboardHandler {
+        pasted: Vec<String>,
+    }
+
+    impl WindowHandler for ClipboardHandler {
+        fn on_clipboard_text(&mut self, _helper: &mut WindowHelper, text: &str) {
+            self.pasted.push(text.to_string());
+        }
+
+        fn on_key_down(
+            &mut self,
+            helper: &mut WindowHelper,
+            virtual_key_code: Option<VirtualKeyCode>,
+            _scancode: u32,
+        ) {
+            match virtual_key_code {
+                Some(VirtualKeyCode::C) => helper.set_clipboard_text("copied").unwrap(),
+                #[cfg(feature = "image-loading")]
+                Some(VirtualKeyCode::I) => helper
+                    .set_clipboard_image(crate::image::ImageDataType::RGB, (1, 1), &[1, 2, 3])
+                    .unwrap(),
+                Some(VirtualKeyCode::V) => helper.request_clipboard_text(),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_clipboard() {
+        let mut window = HeadlessWindow::new((20, 10), ClipboardHandler::default()).unwrap();
+
+        window.on_key_down(Some(VirtualKeyCode::V), 47);
+        assert!(window.handler().pasted.is_empty());
+
+        window.on_key_down(Some(VirtualKeyCode::C), 46);
+        window.on_key_down(Some(VirtualKeyCode::V), 47);
+        assert_eq!(window.clipboard_text().as_deref(), Some("copied"));
+        assert_eq!(window.handler().pasted, vec!["copied"]);
+
+        #[cfg(feature = "image-loading")]
+        {
+            window.on_key_down(Some(VirtualKeyCode::I), 23);
+            assert_eq!(window.clipboard_text(), None);
+            assert_eq!(
+                window.clipboard_image().unwrap().data(),
+                &vec![1, 2, 3, 255]
+            );
+        }
+    }
+}
@@ -21,19 +21,22 @@ use std::ops::{Deref, DerefMut, Mul};
 use std::rc::Rc;
 
 use wasm_bindgen::closure::Closure;
-use wasm_bindgen::JsCast;
-use web_sys::{KeyboardEvent, MouseEvent, WheelEvent};
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::js_sys::{Promise, Reflect, Uint8Array};
+use web_sys::{Event, KeyboardEvent, MouseEvent, WheelEvent};
 
 use crate::dimen::{IVec2, UVec2, Vec2};
 use crate::error::{BacktraceError, ErrorMessage};
 use crate::numeric::RoundFloat;
+use crate::time::Timestamp;
 use crate::web::{WebCanvasElement, WebCursorType, WebDocument, WebPending, WebWindow};
 use crate::window::{
-    DrawingWindowHandler, EventLoopSendError, KeyScancode, ModifiersState, MouseButton,
-    MouseScrollDistance, UserEventSender, VirtualKeyCode, WindowFullscreenMode, WindowHandler,
-    WindowHelper, WindowStartupInfo,
+    DrawingWindowHandler, EventLoopSendError, FramePacer, KeyScancode, ModifiersState, MouseButton,
+    MouseScrollDistance, RedrawMode, TouchId, TouchPhase, UserEventSender, VirtualKeyCode,
+    WindowCreationOptions, WindowFullscreenMode, WindowHandler, WindowHelper, WindowStartupInfo,
 };
-use crate::GLRenderer;
+use crate::{GLRenderer, Rect};
 
 fn key_code_from_web(code: &str) -> Option<VirtualKeyCode> {
     match code {
@@ -371,7 +374,13 @@ where
 {
     redraw_pending: RefCell<Option<WebPending>>,
     redraw_request_action: Option<Box<RefCell<dyn FnMut() -> WebPending>>>,
+    redraw_timeout_pending: RefCell<Option<WebPending>>,
+    redraw_timeout_action: Option<Box<RefCell<dyn FnMut(i32) -> WebPending>>>,
+    frame_pacer: Cell<FramePacer>,
+    visible: Cell<bool>,
+    ctrl_wheel_pinch: Cell<bool>,
     post_user_event_action: Option<Rc<RefCell<UserEventSenderActionType<UserEventType>>>>,
+    clipboard_text_action: Option<Box<dyn Fn()>>,
     terminate_loop_action: Option<Box<dyn FnOnce()>>,
     canvas: WebCanvasElement,
     document: WebDocument,
@@ -383,7 +392,13 @@ impl<UserEventType: 'static> WindowHelperWeb<UserEventType> {
         Self {
             redraw_pending: RefCell::new(None),
             redraw_request_action: None,
+            redraw_timeout_pending: RefCell::new(None),
+            redraw_timeout_action: None,
+            frame_pacer: Cell::new(FramePacer::new(RedrawMode::OnDemand)),
+            visible: Cell::new(!document.is_hidden()),
+            ctrl_wheel_pinch: Cell::new(false),
             post_user_event_action: None,
+            clipboard_text_action: None,
             terminate_loop_action: None,
             canvas,
             document,
@@ -398,6 +413,13 @@ impl<UserEventType: 'static> WindowHelperWeb<UserEventType> {
         self.redraw_request_action = Some(Box::new(RefCell::new(redraw_request_action)));
     }
 
+    pub fn set_redraw_timeout_action<F>(&mut self, redraw_timeout_action: F)
+    where
+        F: FnMut(i32) -> WebPending + 'static,
+    {
+        self.redraw_timeout_action = Some(Box::new(RefCell::new(redraw_timeout_action)));
+    }
+
     pub fn set_post_user_event_action<F>(&mut self, post_user_event_action: F)
     where
         F: FnMut(UserEventType) -> Result<(), BacktraceError<ErrorMessage>> + 'static,
@@ -405,6 +427,13 @@ impl<UserEventType: 'static> WindowHelperWeb<UserEventType> {
         self.post_user_event_action = Some(Rc::new(RefCell::new(post_user_event_action)));
     }
 
+    pub fn set_clipboard_text_action<F>(&mut self, clipboard_text_action: F)
+    where
+        F: Fn() + 'static,
+    {
+        self.clipboard_text_action = Some(Box::new(clipboard_text_action));
+    }
+
     pub fn set_terminate_loop_action<F>(&mut self, terminate_loop_action: F)
     where
         F: FnOnce() + 'static,
@@ -419,14 +448,75 @@ impl<UserEventType: 'static> WindowHelperWeb<UserEventType> {
         self.redraw_pending.replace(None);
     }
 
+    pub fn clear_redraw_timeout_pending_flag(&self) {
+        if let Some(pending) = self.redraw_timeout_pending.borrow_mut().deref_mut() {
+            pending.mark_as_triggered()
+        }
+        self.redraw_timeout_pending.replace(None);
+    }
+
+    pub fn on_frame_start(&self) {
+        let mut frame_pacer = self.frame_pacer.get();
+        frame_pacer.on_frame_start(&Timestamp::now());
+        self.frame_pacer.set(frame_pacer);
+    }
+
+    /// Requests the next frame, if frames are being drawn continuously.
+    pub fn schedule_next_frame(&self) {
+        match self.frame_pacer.get().mode() {
+            RedrawMode::OnDemand => {}
+            RedrawMode::Continuous | RedrawMode::FixedFps(_) => self.request_redraw(),
+        }
+    }
+
+    /// Stops drawing frames while the page is hidden, and requests a frame
+    /// once it's shown again.
+    pub fn set_visible(&self, visible: bool) {
+        self.visible.set(visible);
+
+        if visible {
+            self.request_redraw();
+        }
+    }
+
+    pub fn set_redraw_mode(&self, redraw_mode: RedrawMode) {
+        let mut frame_pacer = self.frame_pacer.get();
+        frame_pacer.set_mode(redraw_mode);
+        self.frame_pacer.set(frame_pacer);
+
+        // Any frame waiting for a timeout is now requested immediately
+        self.redraw_timeout_pending.replace(None);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn redraw_mode(&self) -> RedrawMode {
+        self.frame_pacer.get().mode()
+    }
+
     pub fn terminate_loop(&mut self) {
         self.redraw_pending.replace(None);
         self.redraw_request_action = None;
+        self.redraw_timeout_pending.replace(None);
+        self.redraw_timeout_action = None;
         if let Some(action) = self.terminate_loop_action.take() {
             action();
         }
     }
 
+    pub fn close_window(&mut self) {
+        self.terminate_loop()
+    }
+
+    pub fn create_window(
+        &self,
+        title: &str,
+        _options: WindowCreationOptions,
+        _handler: Box<dyn WindowHandler<UserEventType>>,
+    ) {
+        log::warn!("Ignoring request to create window '{title}': not supported on the web");
+    }
+
     pub fn set_icon_from_rgba_pixels<S>(
         &self,
         _data: Vec<u8>,
@@ -461,6 +551,55 @@ impl<UserEventType: 'static> WindowHelperWeb<UserEventType> {
         // Do nothing
     }
 
+    #[inline]
+    pub fn set_ctrl_wheel_pinch(&self, enabled: bool) {
+        self.ctrl_wheel_pinch.set(enabled);
+    }
+
+    #[inline]
+    #[must_use]
+    fn is_ctrl_wheel_pinch_enabled(&self) -> bool {
+        self.ctrl_wheel_pinch.get()
+    }
+
+    #[inline]
+    pub fn set_ime_allowed(&self, _allowed: bool) {
+        // Do nothing
+    }
+
+    #[inline]
+    pub fn set_ime_cursor_area(&self, _area_pixels: Rect) {
+        // Do nothing
+    }
+
+    pub fn set_clipboard_text(&self, text: &str) -> Result<(), BacktraceError<ErrorMessage>> {
+        self.window.write_clipboard_text(text);
+        Ok(())
+    }
+
+    pub fn request_clipboard_text(&self) {
+        if let Some(action) = &self.clipboard_text_action {
+            action();
+        }
+    }
+
+    #[cfg(feature = "image-loading")]
+    pub fn set_clipboard_image(
+        &self,
+        _data: Vec<u8>,
+        _size: UVec2,
+    ) -> Result<(), BacktraceError<ErrorMessage>> {
+        Err(ErrorMessage::msg(
+            "Images can't be placed on the clipboard from a WebCanvas",
+        ))
+    }
+
+    #[cfg(feature = "image-loading")]
+    #[inline]
+    pub fn request_clipboard_image(&self) {
+        // Do nothing
+    }
+
     #[inline]
     pub fn request_redraw(&self) {
         if self.redraw_request_action.borrow().is_none() {
@@ -468,6 +607,27 @@ impl<UserEventType: 'static> WindowHelperWeb<UserEventType> {
             return;
         }
 
+        if !self.visible.get() {
+            // A frame will be requested once the page is visible again
+            return;
+        }
+
+        let now = Timestamp::now();
+
+        if let Some(deadline) = self.frame_pacer.get().next_frame_deadline() {
+            if now < deadline {
+                // Wait until the next frame is due, rather than drawing it now
+                if self.redraw_timeout_pending.borrow().is_none() {
+                    if let Some(action) = &self.redraw_timeout_action {
+                        let delay_millis = (deadline.secs_since(&now) * 1000.0).ceil() as i32;
+                        self.redraw_timeout_pending
+                            .replace(Some(action.borrow_mut()(delay_millis)));
+                    }
+                }
+                return;
+            }
+        }
+
         if self.redraw_pending.borrow().is_none() {
             self.redraw_pending.replace(Some(self
                 .redraw_request_action
@@ -670,8 +830,14 @@ impl WebCanvasImpl {
                 RefCell::borrow_mut(Rc::borrow(&helper_inner))
                     .inner()
                     .clear_redraw_pending_flag();
+                RefCell::borrow_mut(Rc::borrow(&helper_inner))
+                    .inner()
+                    .on_frame_start();
                 RefCell::borrow_mut(Rc::borrow(&handler))
                     .on_draw(RefCell::borrow_mut(Rc::borrow(&helper_inner)).deref_mut());
+                RefCell::borrow_mut(Rc::borrow(&helper_inner))
+                    .inner()
+                    .schedule_next_frame();
             }) as Box<dyn FnMut()>));
 
             let redraw_request_action =
@@ -682,6 +848,24 @@ impl WebCanvasImpl {
                 .set_redraw_request_action(redraw_request_action);
         }
 
+        {
+            let helper_inner = helper.clone();
+            let window = window.clone();
+
+            let timeout_callback = RefCell::new(Closure::wrap(Box::new(move || {
+                let mut helper = RefCell::borrow_mut(Rc::borrow(&helper_inner));
+                helper.inner().clear_redraw_timeout_pending_flag();
+                helper.inner().request_redraw();
+            }) as Box<dyn FnMut()>));
+
+            let redraw_timeout_action =
+                move |delay_millis| window.set_timeout(&timeout_callback, delay_millis).unwrap();
+
+            RefCell::borrow_mut(Rc::borrow(&helper))
+                .inner()
+                .set_redraw_timeout_action(redraw_timeout_action);
+        }
+
         {
             let user_event_queue = Rc::new(RefCell::new(Vec::new()));
             let user_event_callback_pending = Rc::new(RefCell::new(None));
@@ -726,6 +910,26 @@ impl WebCanvasImpl {
                 })
         }
 
+        {
+            let handler = handler.clone();
+            let helper_inner = helper.clone();
+            let window = window.clone();
+
+            RefCell::borrow_mut(Rc::borrow(&helper))
+                .inner()
+                .set_clipboard_text_action(move || {
+                    let handler = handler.clone();
+                    let helper = helper_inner.clone();
+
+                    window.read_clipboard_text(move |text| {
+                        RefCell::borrow_mut(Rc::borrow(&handler)).on_clipboard_text(
+                            RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut(),
+                            &text,
+                        )
+                    });
+                });
+        }
+
         let canvas_event_target = canvas
             .html_element()
             .element()
@@ -799,6 +1003,46 @@ impl WebCanvasImpl {
         {
             let handler = handler.clone();
             let helper = helper.clone();
+            let document_inner = document.clone();
+
+            event_listeners_to_clean_up.push(
+                document
+                    .clone()
+                    .dyn_into_event_target()?
+                    .register_event_listener_void("visibilitychange", move || {
+                        let visible = !document_inner.is_hidden();
+
+                        RefCell::borrow_mut(Rc::borrow(&helper))
+                            .inner()
+                            .set_visible(visible);
+
+                        RefCell::borrow_mut(Rc::borrow(&handler)).on_visibility_changed(
+                            RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut(),
+                            visible,
+                        );
+                    })?,
+            );
+        }
+
+        for (listener_type, focused) in [("focus", true), ("blur", false)] {
+            let handler = handler.clone();
+            let helper = helper.clone();
+
+            event_listeners_to_clean_up.push(canvas_event_target.register_event_listener_void(
+                listener_type,
+                move || {
+                    RefCell::borrow_mut(Rc::borrow(&handler)).on_focus_changed(
+                        RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut(),
+                        focused,
+                    );
+                },
+            )?);
+        }
+
+        {
+            let handler = handler.clone();
+            let helper = helper.clone();
+            let canvas = canvas.clone();
 
             event_listeners_to_clean_up.push(
                 document
@@ -887,6 +1131,26 @@ impl WebCanvasImpl {
                 move |event| {
                     let event: WheelEvent = event.dyn_into().unwrap();
 
+                    // Browsers report touchpad pinch gestures as wheel events with
+                    // Ctrl held, where the scale factor is exp(-delta_y / 100). A
+                    // mouse wheel with Ctrl held looks the same, so this is opt-in.
+                    let ctrl_wheel_pinch = RefCell::borrow_mut(Rc::borrow(&helper))
+                        .inner()
+                        .is_ctrl_wheel_pinch_enabled();
+
+                    if ctrl_wheel_pinch && event.ctrl_key() && event.delta_mode() == 0x00 {
+                        // Stops the browser from zooming the page
+                        event.prevent_default();
+
+                        let delta = (-event.delta_y() / 100.0).exp() - 1.0;
+
+                        handler
+                            .borrow_mut()
+                            .on_pinch(helper.borrow_mut().deref_mut(), delta);
+
+                        return;
+                    }
+
                     let delta = match event.delta_mode() {
                         0x00 => MouseScrollDistance::Pixels {
                             x: event.delta_x(),
@@ -919,6 +1183,200 @@ impl WebCanvasImpl {
             )?);
         }
 
+        event_listeners_to_clean_up.push(canvas_event_target.register_event_listener_drag(
+            "dragover",
+            // Without this, the browser won't allow files to be dropped
+            move |event| event.prevent_default(),
+        )?);
+
+        {
+            let handler = handler.clone();
+            let helper = helper.clone();
+
+            event_listeners_to_clean_up.push(canvas_event_target.register_event_listener_drag(
+                "drop",
+                move |event| {
+                    // Stops the browser from navigating to the file
+                    event.prevent_default();
+
+                    let files = match event.data_transfer().and_then(|data| data.files()) {
+                        None => return,
+                        Some(files) => files,
+                    };
+
+                    for index in 0..files.length() {
+                        let file = match files.get(index) {
+                            None => continue,
+                            Some(file) => file,
+                        };
+
+                        let name = file.name();
+                        let handler = handler.clone();
+                        let helper = helper.clone();
+
+                        let on_read = Closure::once_into_js(move |buffer: JsValue| {
+                            let bytes = Uint8Array::new(&buffer).to_vec();
+
+                            RefCell::borrow_mut(Rc::borrow(&handler)).on_file_drop_data(
+                                RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut(),
+                                &name,
+                                &bytes,
+                            );
+                        });
+
+                        let on_error = Closure::once_into_js(|err: JsValue| {
+                            log::error!("File drop: Failed to read file: {err:?}");
+                        });
+
+                        // The file is read asynchronously. The callbacks are plain JS
+                        // functions, so the one which is invoked is freed afterwards.
+                        let _ = then_with_callbacks(&file.array_buffer(), &on_read, &on_error);
+                    }
+                },
+            )?);
+        }
+
+        // Safari reports touchpad gestures using its own non-standard events, with
+        // the total scale and rotation since the start of the gesture
+        let last_gesture_scale_and_rotation = Rc::new(Cell::new((1.0, 0.0)));
+
+        {
+            let last_gesture_scale_and_rotation = last_gesture_scale_and_rotation.clone();
+
+            event_listeners_to_clean_up.push(canvas_event_target.register_event_listener_event(
+                "gesturestart",
+                move |event| {
+                    event.prevent_default();
+                    last_gesture_scale_and_rotation.set((1.0, 0.0));
+                },
+            )?);
+        }
+
+        {
+            let handler = handler.clone();
+            let helper = helper.clone();
+
+            event_listeners_to_clean_up.push(canvas_event_target.register_event_listener_event(
+                "gesturechange",
+                move |event| {
+                    event.prevent_default();
+
+                    let (scale, rotation) = match gesture_scale_and_rotation(&event) {
+                        None => {
+                            log::error!("Gesture change: Missing scale or rotation");
+                            return;
+                        }
+                        Some(values) => values,
+                    };
+
+                    let (last_scale, last_rotation) =
+                        last_gesture_scale_and_rotation.replace((scale, rotation));
+
+                    if scale != last_scale && last_scale > 0.0 {
+                        RefCell::borrow_mut(Rc::borrow(&handler)).on_pinch(
+                            RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut(),
+                            scale / last_scale - 1.0,
+                        );
+                    }
+
+                    if rotation != last_rotation {
+                        // Safari measures rotation clockwise
+                        RefCell::borrow_mut(Rc::borrow(&handler)).on_rotate_gesture(
+                            RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut(),
+                            (last_rotation - rotation) as f32,
+                        );
+                    }
+                },
+            )?);
+        }
+
+        event_listeners_to_clean_up.push(
+            canvas_event_target.register_event_listener_event("gestureend", move |event| {
+                event.prevent_default()
+            })?,
+        );
+
+        for (listener_type, phase) in [
+            ("touchstart", TouchPhase::Started),
+            ("touchmove", TouchPhase::Moved),
+            ("touchend", TouchPhase::Ended),
+            ("touchcancel", TouchPhase::Cancelled),
+        ] {
+            let handler = handler.clone();
+            let helper = helper.clone();
+            let canvas = canvas.clone();
+            let current_dpr = current_dpr.clone();
+
+            event_listeners_to_clean_up.push(canvas_event_target.register_event_listener_touch(
+                listener_type,
+                move |event| {
+                    let current_dpr = Cell::get(Rc::borrow(&current_dpr));
+                    let top_left = canvas.html_element().element().top_left();
+                    let touches = event.changed_touches();
+
+                    for index in 0..touches.length() {
+                        let touch = match touches.get(index) {
+                            None => continue,
+                            Some(touch) => touch,
+                        };
+
+                        let position = Vec2::new(
+                            ((touch.client_x() as f64 - top_left.x) * current_dpr) as f32,
+                            ((touch.client_y() as f64 - top_left.y) * current_dpr) as f32,
+                        );
+
+                        // Browsers report a force of zero when it isn't supported
+                        let force = Some(touch.force()).filter(|force| *force > 0.0);
+
+                        RefCell::borrow_mut(Rc::borrow(&handler)).on_touch(
+                            RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut(),
+                            touch.identifier() as TouchId,
+                            phase,
+                            position,
+                            force,
+                        );
+                    }
+
+                    // Stops the page from scrolling, and stops the browser from
+                    // sending its own emulated mouse events. Without our own
+                    // emulation, applications which only handle the mouse rely
+                    // on the browser's events, so they're left alone.
+                    if RefCell::borrow(Rc::borrow(&handler)).is_touch_mouse_emulation_enabled() {
+                        event.prevent_default();
+                    }
+                },
+            )?);
+        }
+
+        {
+            let handler = handler.clone();
+            let helper = helper.clone();
+
+            event_listeners_to_clean_up.push(canvas_event_target.register_event_listener_event(
+                "webglcontextlost",
+                move |event| {
+                    // Without this, the browser won't attempt to restore the context
+                    event.prevent_default();
+
+                    RefCell::borrow_mut(Rc::borrow(&handler))
+                        .on_context_lost(RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut());
+                },
+            )?);
+        }
+
+        {
+            let handler = handler.clone();
+            let helper = helper.clone();
+
+            event_listeners_to_clean_up.push(canvas_event_target.register_event_listener_event(
+                "webglcontextrestored",
+                move |_event| {
+                    RefCell::borrow_mut(Rc::borrow(&handler))
+                        .on_context_restored(RefCell::borrow_mut(Rc::borrow(&helper)).deref_mut());
+                },
+            )?);
+        }
+
         let modifier_state = Rc::new(RefCell::new(ModifiersState::default()));
 
         {
@@ -1059,6 +1517,24 @@ impl Drop for WebCanvasImpl {
     }
 }
 
+#[wasm_bindgen]
+extern "C" {
+    // `Promise::then2` only accepts a `Closure`, which must be leaked to
+    // outlive the caller
+    #[wasm_bindgen(method, js_name = then)]
+    fn then_with_callbacks(
+        this: &Promise,
+        on_fulfilled: &JsValue,
+        on_rejected: &JsValue,
+    ) -> Promise;
+}
+
+fn gesture_scale_and_rotation(event: &Event) -> Option<(f64, f64)> {
+    let property = |name: &str| Reflect::get(event, &JsValue::from_str(name)).ok()?.as_f64();
+
+    Some((property("scale")?, property("rotation")?))
+}
+
 fn mouse_button_from_event(event: &MouseEvent) -> Option<MouseButton> {
     let button: i16 = event.button();
     match button {